@@ -14,6 +14,9 @@ pub struct CosmicCompConfig {
     pub input_touchpad: input::InputConfig,
     pub input_devices: HashMap<String, input::InputConfig>,
     pub xkb_config: XkbConfig,
+    pub keyboard_config: KeyboardConfig,
+    pub focus_config: FocusConfig,
+    pub floating_config: FloatingConfig,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -23,6 +26,20 @@ pub struct XkbConfig {
     pub layout: String,
     pub variant: String,
     pub options: Option<String>,
+    /// Delay in milliseconds before a held key starts repeating
+    #[serde(default = "default_repeat_delay")]
+    pub repeat_delay: u32,
+    /// Repeated key presses per second
+    #[serde(default = "default_repeat_rate")]
+    pub repeat_rate: u32,
+}
+
+fn default_repeat_delay() -> u32 {
+    200
+}
+
+fn default_repeat_rate() -> u32 {
+    25
 }
 
 impl Default for XkbConfig {
@@ -33,6 +50,104 @@ impl Default for XkbConfig {
             layout: String::new(),
             variant: String::new(),
             options: None,
+            repeat_delay: default_repeat_delay(),
+            repeat_rate: default_repeat_rate(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct KeyboardConfig {
+    #[serde(default)]
+    pub numlock_state: NumlockState,
+    #[serde(default)]
+    pub layout_memory: LayoutMemory,
+}
+
+/// State of NumLock when a keyboard is added
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum NumlockState {
+    BootOn,
+    #[default]
+    BootOff,
+}
+
+/// Which keyboard layout is active after keyboard focus changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LayoutMemory {
+    /// The active layout is shared by all windows
+    #[default]
+    Global,
+    /// Every window restores the layout last used in it, new windows start with the first layout
+    PerWindow,
+}
+
+/// How keyboard focus and the pointer follow each other
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FocusConfig {
+    /// Focus windows when the pointer enters them, without raising them
+    #[serde(default)]
+    pub focus_follows_cursor: bool,
+    /// Delay in milliseconds the pointer has to stay on a window before it gets focused
+    #[serde(default = "default_focus_follows_cursor_delay")]
+    pub focus_follows_cursor_delay: u64,
+    /// Move the pointer to the center of windows focused with the keyboard, by switching
+    /// workspaces or by activation
+    #[serde(default)]
+    pub cursor_follows_focus: bool,
+}
+
+fn default_focus_follows_cursor_delay() -> u64 {
+    250
+}
+
+impl Default for FocusConfig {
+    fn default() -> FocusConfig {
+        FocusConfig {
+            focus_follows_cursor: false,
+            focus_follows_cursor_delay: default_focus_follows_cursor_delay(),
+            cursor_follows_focus: false,
+        }
+    }
+}
+
+/// Placement of floating windows moved with the pointer or the keyboard
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FloatingConfig {
+    /// Distance in pixels at which moved windows snap to output edges, exclusive zones of panels
+    /// and edges of other floating windows, 0 disables snapping
+    #[serde(default = "default_snap_threshold")]
+    pub snap_threshold: u32,
+    /// Size in pixels of a grid moved windows snap to, when no edge is close, 0 disables the grid
+    #[serde(default)]
+    pub snap_grid: u32,
+    /// Pixels windows are moved or resized by with the keyboard
+    #[serde(default = "default_keyboard_step")]
+    pub keyboard_step: u32,
+    /// Pixels windows are moved or resized by with the keyboard, while shift is held
+    #[serde(default = "default_keyboard_step_large")]
+    pub keyboard_step_large: u32,
+}
+
+fn default_snap_threshold() -> u32 {
+    16
+}
+
+fn default_keyboard_step() -> u32 {
+    20
+}
+
+fn default_keyboard_step_large() -> u32 {
+    100
+}
+
+impl Default for FloatingConfig {
+    fn default() -> FloatingConfig {
+        FloatingConfig {
+            snap_threshold: default_snap_threshold(),
+            snap_grid: 0,
+            keyboard_step: default_keyboard_step(),
+            keyboard_step_large: default_keyboard_step_large(),
         }
     }
 }
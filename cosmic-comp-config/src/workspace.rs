@@ -12,6 +12,10 @@ pub struct WorkspaceConfig {
     pub workspace_amount: WorkspaceAmount,
     #[serde(default = "default_workspace_layout")]
     pub workspace_layout: WorkspaceLayout,
+    /// Names of the first workspaces with a static amount,
+    /// otherwise workspaces created in addition to the numbered ones
+    #[serde(default)]
+    pub workspace_names: Vec<String>,
 }
 
 impl Default for WorkspaceConfig {
@@ -20,6 +24,7 @@ impl Default for WorkspaceConfig {
             workspace_mode: WorkspaceMode::OutputBound,
             workspace_amount: WorkspaceAmount::Dynamic,
             workspace_layout: WorkspaceLayout::Vertical,
+            workspace_names: Vec::new(),
         }
     }
 }
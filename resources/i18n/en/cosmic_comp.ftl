@@ -20,4 +20,5 @@ window-menu-close-all = Close all windows
 window-menu-resize-edge-top = Top
 window-menu-resize-edge-left = Left
 window-menu-resize-edge-right = Right
-window-menu-resize-edge-bottom = Bottom
\ No newline at end of file
+window-menu-resize-edge-bottom = Bottom
+config-error = Failed to load the compositor config
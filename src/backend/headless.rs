@@ -0,0 +1,393 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::{
+    backend::render,
+    config::OutputConfig,
+    state::{BackendData, Common},
+    utils::prelude::*,
+    wayland::protocols::screencopy::{BufferParams, Session as ScreencopySession},
+};
+use anyhow::{Context, Result};
+use smithay::{
+    backend::{
+        allocator::Fourcc,
+        egl::{EGLContext, EGLDevice, EGLDisplay},
+        renderer::{
+            damage::{OutputDamageTracker, RenderOutputResult},
+            gles::GlesRenderbuffer,
+            glow::GlowRenderer,
+            ImportDma, Offscreen,
+        },
+    },
+    desktop::layer_map_for_output,
+    output::{Mode, Output, PhysicalProperties, Scale, Subpixel},
+    reexports::{
+        calloop::{
+            timer::{TimeoutAction, Timer},
+            EventLoop, LoopHandle, RegistrationToken,
+        },
+        wayland_protocols::wp::presentation_time::server::wp_presentation_feedback,
+        wayland_server::DisplayHandle,
+    },
+    utils::{Buffer as BufferCoords, Size, Transform},
+};
+use std::{cell::RefCell, time::Duration};
+use tracing::{error, info, warn};
+
+#[cfg(feature = "debug")]
+use crate::state::Fps;
+
+use super::render::init_shaders;
+
+/// Parameters of the virtual outputs created by the headless backend.
+///
+/// Read from the environment by [`HeadlessConfig::from_env`]:
+/// - `COSMIC_HEADLESS_OUTPUTS`: number of outputs (default `1`)
+/// - `COSMIC_HEADLESS_SIZE`: mode of each output as `WIDTHxHEIGHT` (default `1920x1080`)
+/// - `COSMIC_HEADLESS_SCALE`: scale of each output (default `1.0`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadlessConfig {
+    pub outputs: usize,
+    pub size: (i32, i32),
+    pub scale: f64,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        HeadlessConfig {
+            outputs: 1,
+            size: (1920, 1080),
+            scale: 1.0,
+        }
+    }
+}
+
+impl HeadlessConfig {
+    pub fn from_env() -> HeadlessConfig {
+        let mut config = HeadlessConfig::default();
+
+        if let Ok(val) = std::env::var("COSMIC_HEADLESS_OUTPUTS") {
+            match val.parse::<usize>() {
+                Ok(outputs) if outputs > 0 => config.outputs = outputs,
+                _ => warn!(?val, "Invalid COSMIC_HEADLESS_OUTPUTS, using default."),
+            }
+        }
+        if let Ok(val) = std::env::var("COSMIC_HEADLESS_SIZE") {
+            match val
+                .split_once('x')
+                .and_then(|(w, h)| Some((w.parse::<i32>().ok()?, h.parse::<i32>().ok()?)))
+            {
+                Some((w, h)) if w > 0 && h > 0 => config.size = (w, h),
+                _ => warn!(?val, "Invalid COSMIC_HEADLESS_SIZE, using default."),
+            }
+        }
+        if let Ok(val) = std::env::var("COSMIC_HEADLESS_SCALE") {
+            match val.parse::<f64>() {
+                Ok(scale) if scale > 0.0 => config.scale = scale,
+                _ => warn!(?val, "Invalid COSMIC_HEADLESS_SCALE, using default."),
+            }
+        }
+
+        config
+    }
+}
+
+#[derive(Debug)]
+pub struct HeadlessState {
+    pub renderer: GlowRenderer,
+    _egl: EGLDisplay,
+    surfaces: Vec<Surface>,
+    loop_handle: LoopHandle<'static, State>,
+}
+
+#[derive(Debug)]
+pub struct Surface {
+    output: Output,
+    damage_tracker: OutputDamageTracker,
+    buffer: Option<GlesRenderbuffer>,
+    screencopy: Vec<(ScreencopySession, BufferParams)>,
+    pending: Option<RegistrationToken>,
+    #[cfg(feature = "debug")]
+    fps: Fps,
+}
+
+impl HeadlessState {
+    pub fn add_output(&mut self, size: (i32, i32), scale: f64) -> Output {
+        let name = format!("HEADLESS-{}", self.surfaces.len());
+        let props = PhysicalProperties {
+            size: (0, 0).into(),
+            subpixel: Subpixel::Unknown,
+            make: "COSMIC".to_string(),
+            model: name.clone(),
+        };
+        let mode = Mode {
+            size: size.into(),
+            refresh: 60_000,
+        };
+        // place outputs next to each other, ordered by creation
+        let position = self
+            .surfaces
+            .iter()
+            .map(|s| s.output.geometry())
+            .map(|geo| geo.loc.x + geo.size.w)
+            .max()
+            .unwrap_or(0);
+        let output = Output::new(name, props);
+        output.add_mode(mode);
+        output.set_preferred(mode);
+        output.change_current_state(
+            Some(mode),
+            Some(Transform::Normal),
+            Some(Scale::Fractional(scale)),
+            Some((position, 0).into()),
+        );
+        output.user_data().insert_if_missing(|| {
+            RefCell::new(OutputConfig {
+                mode: (size, None),
+                scale,
+                position: (position, 0),
+                ..Default::default()
+            })
+        });
+
+        self.surfaces.push(Surface {
+            damage_tracker: OutputDamageTracker::from_output(&output),
+            output: output.clone(),
+            buffer: None,
+            screencopy: Vec::new(),
+            pending: None,
+            #[cfg(feature = "debug")]
+            fps: Fps::new(&mut self.renderer),
+        });
+
+        output
+    }
+
+    pub fn schedule_render(
+        &mut self,
+        output: &Output,
+        screencopy: Option<Vec<(ScreencopySession, BufferParams)>>,
+    ) {
+        if let Some(surface) = self.surfaces.iter_mut().find(|s| s.output == *output) {
+            if let Some(sessions) = screencopy {
+                surface.screencopy.extend(sessions);
+            }
+            if surface.pending.is_some() {
+                return;
+            }
+
+            // emulate vblank by rendering at most once per refresh cycle
+            let refresh = output
+                .current_mode()
+                .map(|mode| Duration::from_secs_f64(1_000.0 / mode.refresh as f64))
+                .unwrap_or(Duration::from_millis(16));
+            let output = output.clone();
+            match self.loop_handle.insert_source(
+                Timer::from_duration(refresh),
+                move |_, _, state| {
+                    let headless_state = state.backend.headless();
+                    if let Some(surface) = headless_state
+                        .surfaces
+                        .iter_mut()
+                        .find(|s| s.output == output)
+                    {
+                        surface.pending = None;
+                        if let Err(err) =
+                            surface.render_output(&mut headless_state.renderer, &mut state.common)
+                        {
+                            error!(?err, "Error rendering.");
+                        }
+                    }
+                    TimeoutAction::Drop
+                },
+            ) {
+                Ok(token) => surface.pending = Some(token),
+                Err(err) => error!(?err, "Failed to schedule render for headless output."),
+            }
+        }
+    }
+
+    pub fn apply_config_for_output(
+        &mut self,
+        output: &Output,
+        test_only: bool,
+    ) -> Result<(), anyhow::Error> {
+        let config = output
+            .user_data()
+            .get::<RefCell<OutputConfig>>()
+            .unwrap()
+            .borrow();
+        if !config.enabled {
+            anyhow::bail!("Cannot disable headless outputs");
+        }
+        if test_only {
+            return Ok(());
+        }
+
+        // virtual outputs can take any mode, so just add it
+        let mode = config.output_mode();
+        if output.current_mode() != Some(mode) {
+            if let Some(old_mode) = output.current_mode() {
+                output.delete_mode(old_mode);
+            }
+            output.add_mode(mode);
+            output.set_preferred(mode);
+            output.change_current_state(Some(mode), None, None, None);
+            layer_map_for_output(output).arrange();
+            if let Some(surface) = self.surfaces.iter_mut().find(|s| s.output == *output) {
+                surface.buffer = None;
+                surface.damage_tracker = OutputDamageTracker::from_output(output);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Surface {
+    pub fn render_output(&mut self, renderer: &mut GlowRenderer, state: &mut Common) -> Result<()> {
+        let size = self
+            .output
+            .current_mode()
+            .map(|mode| Size::<i32, BufferCoords>::from((mode.size.w, mode.size.h)))
+            .with_context(|| "Output has no mode")?;
+        // we only ever need a single buffer, so any content is as old as the last frame
+        let age = if self.buffer.is_some() { 1 } else { 0 };
+        let buffer = match self.buffer.as_ref() {
+            Some(buffer) => buffer.clone(),
+            None => {
+                let buffer =
+                    Offscreen::<GlesRenderbuffer>::create_buffer(renderer, Fourcc::Abgr8888, size)
+                        .with_context(|| "Failed to allocate buffer")?;
+                self.buffer = Some(buffer.clone());
+                buffer
+            }
+        };
+
+        match render::render_output::<_, _, GlesRenderbuffer, _>(
+            None,
+            renderer,
+            buffer.clone(),
+            &mut self.damage_tracker,
+            age,
+            state,
+            &self.output,
+            render::CursorMode::NotDefault,
+            if !self.screencopy.is_empty() {
+                Some((buffer, &self.screencopy))
+            } else {
+                None
+            },
+            #[cfg(not(feature = "debug"))]
+            None,
+            #[cfg(feature = "debug")]
+            Some(&mut self.fps),
+        ) {
+            Ok(RenderOutputResult { damage, states, .. }) => {
+                self.screencopy.clear();
+                #[cfg(feature = "debug")]
+                self.fps.displayed();
+                state.send_frames(&self.output, &states, |_| None);
+                if damage.is_some() {
+                    let mut output_presentation_feedback =
+                        state.take_presentation_feedback(&self.output, &states);
+                    output_presentation_feedback.presented(
+                        state.clock.now(),
+                        self.output
+                            .current_mode()
+                            .map(|mode| Duration::from_secs_f64(1_000.0 / mode.refresh as f64))
+                            .unwrap_or_default(),
+                        0,
+                        wp_presentation_feedback::Kind::empty(),
+                    )
+                }
+            }
+            Err(err) => {
+                for (session, params) in self.screencopy.drain(..) {
+                    state.still_pending(session, params)
+                }
+                self.buffer = None;
+                anyhow::bail!("Rendering failed: {}", err);
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// Initializes the headless backend without launching xwayland.
+pub fn init_headless(
+    dh: &DisplayHandle,
+    event_loop: &mut EventLoop<'static, State>,
+    state: &mut State,
+    config: HeadlessConfig,
+) -> Result<Vec<Output>> {
+    // Prefer a software rasterizer (e.g. llvmpipe), we don't want to depend on any hardware.
+    let device = EGLDevice::enumerate()
+        .with_context(|| "Failed to enumerate EGL devices")?
+        .find(|device| device.is_software())
+        .with_context(|| "Failed to find a software EGLDevice")?;
+    let egl = EGLDisplay::new(device).with_context(|| "Failed to create EGL display")?;
+    let context = EGLContext::new(&egl).with_context(|| "Failed to create EGL context")?;
+    let mut renderer =
+        unsafe { GlowRenderer::new(context) }.with_context(|| "Failed to initialize renderer")?;
+    init_shaders(&mut renderer).context("Failed to initialize renderer")?;
+
+    let dmabuf_formats = renderer.dmabuf_formats().collect::<Vec<_>>();
+    if !dmabuf_formats.is_empty() {
+        state
+            .common
+            .dmabuf_state
+            .create_global::<State>(dh, dmabuf_formats);
+    }
+    info!("Software rendering enabled.");
+
+    state.backend = BackendData::Headless(HeadlessState {
+        renderer,
+        _egl: egl,
+        surfaces: Vec::new(),
+        loop_handle: event_loop.handle(),
+    });
+
+    let outputs = (0..config.outputs)
+        .map(|_| {
+            state
+                .backend
+                .headless()
+                .add_output(config.size, config.scale)
+        })
+        .collect::<Vec<_>>();
+    state
+        .common
+        .output_configuration_state
+        .add_heads(outputs.iter());
+    for output in &outputs {
+        state.common.shell.add_output(output);
+    }
+    let seats = state.common.seats().cloned().collect::<Vec<_>>();
+    state.common.config.read_outputs(
+        &mut state.common.output_configuration_state,
+        &mut state.backend,
+        &mut state.common.shell,
+        seats.iter().cloned(),
+        &state.common.event_loop_handle,
+    );
+
+    // schedule first render
+    for output in &outputs {
+        state.backend.headless().schedule_render(output, None);
+    }
+
+    Ok(outputs)
+}
+
+pub fn init_backend(
+    dh: &DisplayHandle,
+    event_loop: &mut EventLoop<'static, State>,
+    state: &mut State,
+) -> Result<()> {
+    init_headless(dh, event_loop, state, HeadlessConfig::from_env())?;
+    state.launch_xwayland(None);
+
+    Ok(())
+}
@@ -1216,12 +1216,7 @@ impl Surface {
             );
         }
 
-        let (previous_workspace, workspace) = state.shell.workspaces.active(&self.output);
-        let (previous_idx, idx) = state.shell.workspaces.active_num(&self.output);
-        let previous_workspace = previous_workspace
-            .zip(previous_idx)
-            .map(|((w, start), idx)| (w.handle, idx, start));
-        let workspace = (workspace.handle, idx);
+        let (previous_workspace, workspace) = state.shell.workspaces.visible(&self.output);
 
         let elements = workspace_elements(
             Some(&render_node),
@@ -12,11 +12,12 @@ use std::{
 use crate::debug::{fps_ui, profiler_ui};
 use crate::{
     shell::{
+        element::window::CosmicWindowRenderElement,
         focus::target::WindowGroup,
-        grabs::{SeatMenuGrabState, SeatMoveGrabState},
+        grabs::{SeatMenuGrabState, SeatMoveGrabState, SeatWindowSwitcherState},
         layout::tiling::ANIMATION_DURATION,
         CosmicMapped, CosmicMappedRenderElement, OverviewMode, SessionLock, Trigger,
-        WorkspaceRenderElement,
+        WorkspaceDelta, WorkspaceRenderElement,
     },
     state::{Common, Fps},
     utils::prelude::*,
@@ -36,7 +37,6 @@ use crate::{
 
 use cosmic_comp_config::workspace::WorkspaceLayout;
 use cosmic_protocols::screencopy::v1::server::zcosmic_screencopy_session_v1::FailureReason;
-use keyframe::{ease, functions::EaseInOutCubic};
 use smithay::{
     backend::{
         allocator::dmabuf::Dmabuf,
@@ -61,7 +61,7 @@ use smithay::{
     },
     desktop::{layer_map_for_output, PopupManager},
     output::{Output, OutputNoMode},
-    utils::{IsAlive, Logical, Point, Rectangle, Scale},
+    utils::{IsAlive, Logical, Point, Rectangle, Scale, Size},
     wayland::{
         dmabuf::get_dmabuf,
         shell::wlr_layer::Layer,
@@ -85,6 +85,11 @@ pub static CLEAR_COLOR: [f32; 4] = [0.153, 0.161, 0.165, 1.0];
 pub static OUTLINE_SHADER: &str = include_str!("./shaders/rounded_outline.frag");
 pub static RECTANGLE_SHADER: &str = include_str!("./shaders/rounded_rectangle.frag");
 
+const MODE_INDICATOR_HEIGHT: i32 = 48;
+const MODE_INDICATOR_MARGIN: i32 = 16;
+const NOTIFICATION_HEIGHT: i32 = 96;
+const NOTIFICATION_MARGIN: i32 = 16;
+
 pub struct IndicatorShader(pub GlesPixelProgram);
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
@@ -435,6 +440,17 @@ where
         {
             elements.extend(grab_elements.into_iter().map(Into::into));
         }
+
+        if let Some(switcher_elements) = seat
+            .user_data()
+            .get::<SeatWindowSwitcherState>()
+            .unwrap()
+            .borrow()
+            .as_ref()
+            .map(|state| state.render::<E, R>(renderer, output))
+        {
+            elements.extend(switcher_elements);
+        }
     }
 
     elements
@@ -445,7 +461,7 @@ pub fn workspace_elements<R>(
     renderer: &mut R,
     state: &mut Common,
     output: &Output,
-    previous: Option<(WorkspaceHandle, usize, Instant)>,
+    previous: Option<(WorkspaceHandle, usize, WorkspaceDelta)>,
     current: (WorkspaceHandle, usize),
     cursor_mode: CursorMode,
     _fps: &mut Option<&mut Fps>,
@@ -543,6 +559,7 @@ where
     let active_output = last_active_seat.active_output();
     let output_size = output.geometry().size;
     let output_scale = output.current_scale().fractional_scale();
+    let expose = state.shell.expose_progress();
 
     let set = state
         .shell
@@ -569,6 +586,51 @@ where
     elements.extend(overlay_popups.into_iter().map(Into::into));
     elements.extend(overlay_elements.into_iter().map(Into::into));
 
+    // the binding mode indicator is shown above the bottom edge of the active output
+    if let Some(indicator) = state.shell.mode_indicator().filter(|_| is_active_space) {
+        let size = Size::<i32, Logical>::from((output_size.w, MODE_INDICATOR_HEIGHT));
+        let loc = Point::<i32, Logical>::from((
+            0,
+            output_size.h - MODE_INDICATOR_HEIGHT - MODE_INDICATOR_MARGIN,
+        ));
+        indicator.resize(size);
+        indicator.output_enter(output, output.geometry().as_logical());
+        elements.extend(
+            indicator
+                .render_elements::<CosmicWindowRenderElement<R>>(
+                    renderer,
+                    loc.to_physical_precise_round(output_scale),
+                    output_scale.into(),
+                    1.0,
+                )
+                .into_iter()
+                .map(|elem| {
+                    WorkspaceRenderElement::Window(CosmicMappedRenderElement::from(elem)).into()
+                }),
+        );
+    }
+
+    // notifications are shown below the top edge of the active output
+    if let Some(notification) = state.shell.notification().filter(|_| is_active_space) {
+        let size = Size::<i32, Logical>::from((output_size.w, NOTIFICATION_HEIGHT));
+        let loc = Point::<i32, Logical>::from((0, NOTIFICATION_MARGIN));
+        notification.resize(size);
+        notification.output_enter(output, output.geometry().as_logical());
+        elements.extend(
+            notification
+                .render_elements::<CosmicWindowRenderElement<R>>(
+                    renderer,
+                    loc.to_physical_precise_round(output_scale),
+                    output_scale.into(),
+                    1.0,
+                )
+                .into_iter()
+                .map(|elem| {
+                    WorkspaceRenderElement::Window(CosmicMappedRenderElement::from(elem)).into()
+                }),
+        );
+    }
+
     let mut window_elements = if !has_fullscreen {
         let (top_elements, top_popups) =
             split_layer_elements(renderer, output, Layer::Top, exclude_workspace_overview);
@@ -614,6 +676,43 @@ where
             }),
     );
 
+    // the exposé replaces the workspace, showing all of its windows side by side
+    if let Some(progress) = expose.filter(|_| previous.is_none()) {
+        elements.extend(
+            state
+                .shell
+                .expose_elements(renderer, output, progress, &state.event_loop_handle)
+                .into_iter()
+                .map(|element| {
+                    CosmicElement::Workspace(RelocateRenderElement::from_element(
+                        WorkspaceRenderElement::Window(element),
+                        (0, 0),
+                        Relocate::Relative,
+                    ))
+                }),
+        );
+
+        let (w_elements, p_elements) =
+            background_layer_elements(renderer, output, exclude_workspace_overview);
+        elements.extend(p_elements.into_iter().map(|p_element| {
+            CosmicElement::Workspace(RelocateRenderElement::from_element(
+                p_element,
+                (0, 0),
+                Relocate::Relative,
+            ))
+        }));
+        window_elements.extend(w_elements.into_iter().map(|w_element| {
+            CosmicElement::Workspace(RelocateRenderElement::from_element(
+                w_element,
+                (0, 0),
+                Relocate::Relative,
+            ))
+        }));
+
+        elements.extend(window_elements);
+        return Ok(elements);
+    }
+
     // sticky windows
     if !has_fullscreen {
         let alpha = match &overview.0 {
@@ -663,7 +762,7 @@ where
     }
 
     let offset = match previous.as_ref() {
-        Some((previous, previous_idx, start)) => {
+        Some((previous, previous_idx, delta)) => {
             let layout = state.config.workspace.workspace_layout;
 
             let workspace = state
@@ -674,11 +773,7 @@ where
             let has_fullscreen = workspace.fullscreen.is_some();
             let is_active_space = workspace.outputs().any(|o| o == &active_output);
 
-            let percentage = {
-                let percentage = Instant::now().duration_since(*start).as_millis() as f32
-                    / ANIMATION_DURATION.as_millis() as f32;
-                ease(EaseInOutCubic, 0.0, 1.0, percentage)
-            };
+            let percentage = delta.percentage();
             let offset = Point::<i32, Logical>::from(match (layout, *previous_idx < current.1) {
                 (WorkspaceLayout::Vertical, true) => {
                     (0, (-output_size.h as f32 * percentage).round() as i32)
@@ -966,12 +1061,7 @@ where
     WorkspaceRenderElement<R>: RenderElement<R>,
     Source: Clone,
 {
-    let (previous_workspace, workspace) = state.shell.workspaces.active(output);
-    let (previous_idx, idx) = state.shell.workspaces.active_num(output);
-    let previous_workspace = previous_workspace
-        .zip(previous_idx)
-        .map(|((w, start), idx)| (w.handle, idx, start));
-    let workspace = (workspace.handle, idx);
+    let (previous_workspace, workspace) = state.shell.workspaces.visible(output);
 
     let result = render_workspace(
         gpu,
@@ -1000,7 +1090,7 @@ pub fn render_workspace<R, Target, OffTarget, Source>(
     age: usize,
     state: &mut Common,
     output: &Output,
-    previous: Option<(WorkspaceHandle, usize, Instant)>,
+    previous: Option<(WorkspaceHandle, usize, WorkspaceDelta)>,
     current: (WorkspaceHandle, usize),
     mut cursor_mode: CursorMode,
     screencopy: Option<(Source, &[(ScreencopySession, BufferParams)])>,
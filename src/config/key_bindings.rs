@@ -1,11 +1,14 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::shell::{focus::FocusDirection, grabs::ResizeEdge, Direction, ResizeDirection};
+use crate::shell::{
+    focus::FocusDirection, grabs::ResizeEdge, Direction, EqualizeScope, ResizeDimension,
+    ResizeDirection, ResizeSize,
+};
 use cosmic_comp_config::workspace::WorkspaceLayout;
 use serde::Deserialize;
 use smithay::{
     backend::input::KeyState,
-    input::keyboard::{xkb::keysym_get_name, ModifiersState},
+    input::keyboard::{xkb::keysym_get_name, KeysymHandle, ModifiersState},
 };
 use std::collections::HashMap;
 
@@ -92,6 +95,13 @@ pub struct KeyPattern {
     /// The actual key, that was pressed
     #[serde(deserialize_with = "deserialize_Keysym", default)]
     pub key: Option<Keysym>,
+    /// The xkb keycode of the key, independent of the active layout
+    #[serde(default)]
+    pub keycode: Option<u32>,
+    /// Trigger on release of the key instead of on press.
+    /// Patterns of only modifiers always trigger on release.
+    #[serde(default)]
+    pub release: bool,
 }
 
 impl KeyPattern {
@@ -99,9 +109,24 @@ impl KeyPattern {
         KeyPattern {
             modifiers: modifiers.into(),
             key,
+            keycode: None,
+            release: false,
         }
     }
 
+    pub fn is_modifiers_only(&self) -> bool {
+        self.key.is_none() && self.keycode.is_none()
+    }
+
+    /// Checks the key of the pattern, ignoring modifiers and key state.
+    pub fn matches_key(&self, handle: &KeysymHandle<'_>) -> bool {
+        self.key
+            .map_or(false, |key| handle.raw_syms().contains(&key))
+            || self
+                .keycode
+                .map_or(false, |keycode| handle.raw_code().raw() == keycode)
+    }
+
     pub fn inferred_direction(&self) -> Option<Direction> {
         match self.key? {
             Keysym::Left | Keysym::h | Keysym::H => Some(Direction::Left),
@@ -131,6 +156,8 @@ impl ToString for KeyPattern {
 
         if let Some(key) = self.key {
             result += &keysym_get_name(key);
+        } else if let Some(keycode) = self.keycode {
+            result += &format!("Keycode {}", keycode);
         } else {
             result.remove(result.len() - 1);
         }
@@ -138,6 +165,85 @@ impl ToString for KeyPattern {
     }
 }
 
+/// A pointer button or scroll direction that might be bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Side,
+    Extra,
+    Forward,
+    Back,
+    /// Any other button by its evdev code
+    Code(u32),
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+impl MouseButton {
+    /// The evdev code of the button, `None` for scroll directions
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            MouseButton::Left => Some(0x110),
+            MouseButton::Right => Some(0x111),
+            MouseButton::Middle => Some(0x112),
+            MouseButton::Side => Some(0x113),
+            MouseButton::Extra => Some(0x114),
+            MouseButton::Forward => Some(0x115),
+            MouseButton::Back => Some(0x116),
+            MouseButton::Code(code) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+/// Description of a pointer button or scroll direction combined
+/// with keyboard modifiers that might be handled by the compositor.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct MousePattern {
+    /// What modifiers are expected to be pressed alongside the button
+    #[serde(deserialize_with = "deserialize_KeyModifiers")]
+    pub modifiers: KeyModifiers,
+    pub button: MouseButton,
+}
+
+impl MousePattern {
+    pub fn new(modifiers: impl Into<KeyModifiers>, button: MouseButton) -> MousePattern {
+        MousePattern {
+            modifiers: modifiers.into(),
+            button,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Hash)]
+pub enum PinchDirection {
+    In,
+    Out,
+}
+
+/// A touchpad gesture that might be handled by the compositor.
+/// Gestures with a bound amount of fingers are never passed to clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Hash)]
+pub enum Gesture {
+    /// Swipe of the given amount of fingers into a direction
+    Swipe(u32, Direction),
+    /// Pinch of the given amount of fingers
+    Pinch(u32, PinchDirection),
+}
+
+impl Gesture {
+    pub fn fingers(&self) -> u32 {
+        match self {
+            Gesture::Swipe(fingers, _) | Gesture::Pinch(fingers, _) => *fingers,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub enum Action {
     Terminate,
@@ -158,6 +264,9 @@ pub enum Action {
     SendToNextWorkspace,
     SendToPreviousWorkspace,
     SendToLastWorkspace,
+    WorkspaceByName(String),
+    MoveToWorkspaceByName(String),
+    SendToWorkspaceByName(String),
 
     NextOutput,
     PreviousOutput,
@@ -175,21 +284,53 @@ pub enum Action {
 
     Focus(FocusDirection),
     Move(Direction),
+    MoveFloating(Direction),
+    ResizeFloating(Direction),
+    CenterFloating,
+    TileFloating(crate::shell::layout::floating::TiledCorners),
+    RestoreFloating,
 
     ToggleOrientation,
     Orientation(crate::shell::layout::Orientation),
+    SetTilingMode(crate::shell::layout::TilingMode),
+    SplitHorizontal,
+    SplitVertical,
+    SplitStacked,
 
     ToggleStacking,
     ToggleTiling,
+    ToggleScrolling,
     ToggleWindowFloating,
     ToggleSticky,
     SwapWindow,
+    WindowSwitcher,
+    WindowSwitcherPrevious,
+    Expose,
 
     Resizing(ResizeDirection),
+    ResizeTo(ResizeDimension, ResizeSize),
+    Equalize(EqualizeScope),
     #[serde(skip)]
     _ResizingInternal(ResizeDirection, ResizeEdge, KeyState),
+    Minimize,
     Maximize,
+    MoveToScratchpad,
+    ShowScratchpad,
     Spawn(String),
+
+    EnterMode(String),
+    ExitMode,
+
+    NextLayout,
+    PreviousLayout,
+    SetLayout(u32),
+}
+
+impl Action {
+    /// Actions taking a larger step while Shift is held, their bindings also match with Shift pressed
+    pub fn has_large_step(&self) -> bool {
+        matches!(self, Action::MoveFloating(_) | Action::ResizeFloating(_))
+    }
 }
 
 fn insert_binding(
@@ -200,10 +341,7 @@ fn insert_binding(
 ) {
     if !key_bindings.values().any(|a| a == &action) {
         for key in keys {
-            let pattern = KeyPattern {
-                modifiers: modifiers.clone(),
-                key: Some(key),
-            };
+            let pattern = KeyPattern::new(modifiers.clone(), Some(key));
             if !key_bindings.contains_key(&pattern) {
                 key_bindings.insert(pattern, action.clone());
             }
@@ -321,3 +459,26 @@ pub fn add_default_bindings(
         Action::MoveToOutput(output_next_dir),
     );
 }
+
+/// Adds four finger swipes switching workspaces, unless the actions are bound already.
+///
+/// Three finger swipes are left to applications: binding any gesture with three fingers
+/// would stop forwarding all three finger swipes to clients, which commonly use them.
+pub fn add_default_gestures(
+    gestures: &mut HashMap<Gesture, Action>,
+    workspace_layout: WorkspaceLayout,
+) {
+    let (previous, next) = match workspace_layout {
+        WorkspaceLayout::Horizontal => (Direction::Right, Direction::Left),
+        WorkspaceLayout::Vertical => (Direction::Down, Direction::Up),
+    };
+
+    for (gesture, action) in [
+        (Gesture::Swipe(4, previous), Action::PreviousWorkspace),
+        (Gesture::Swipe(4, next), Action::NextWorkspace),
+    ] {
+        if !gestures.values().any(|a| a == &action) {
+            gestures.entry(gesture).or_insert(action);
+        }
+    }
+}
@@ -1,13 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use crate::{
-    shell::Shell,
+    shell::{layout::saved::SavedLayouts, Shell},
     state::{BackendData, State},
     wayland::protocols::output_configuration::OutputConfigurationState,
 };
 use cosmic_config::ConfigGet;
+use notify::{
+    event::{AccessKind, AccessMode, ModifyKind},
+    EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
 use serde::{Deserialize, Serialize};
-use smithay::input::Seat;
 pub use smithay::{
     backend::input::KeyState,
     input::keyboard::{keysyms as KeySyms, Keysym, ModifiersState},
@@ -21,26 +24,50 @@ pub use smithay::{
     },
     utils::{Logical, Physical, Point, Size, Transform},
 };
-use std::{cell::RefCell, collections::HashMap, fs::OpenOptions, path::PathBuf};
-use tracing::{debug, error, info, warn};
+use smithay::{
+    input::Seat,
+    reexports::calloop::{
+        channel::{self, Event},
+        timer::{TimeoutAction, Timer},
+    },
+};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fs::OpenOptions,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tracing::{error, info, warn};
 
 mod input_config;
 mod key_bindings;
-pub use key_bindings::{Action, KeyModifier, KeyModifiers, KeyPattern};
+pub use key_bindings::{
+    Action, Gesture, KeyModifier, KeyModifiers, KeyPattern, MouseButton, MousePattern,
+    PinchDirection,
+};
 mod types;
 pub use self::types::*;
+mod window_rules;
 use cosmic_comp_config::{
     input::InputConfig,
     workspace::{WorkspaceConfig, WorkspaceLayout},
-    XkbConfig,
+    FloatingConfig, FocusConfig, KeyboardConfig, XkbConfig,
 };
+pub use window_rules::{Pattern, WindowRule, WindowRuleActions, WindowType};
 
 #[derive(Debug)]
 pub struct Config {
     pub static_conf: StaticConfig,
+    /// The file `static_conf` was read from, if any
+    pub static_path: Option<PathBuf>,
+    static_modified: Option<SystemTime>,
     pub dynamic_conf: DynamicConfig,
     pub config: cosmic_config::Config,
     pub xkb: XkbConfig,
+    pub keyboard: KeyboardConfig,
+    pub focus: FocusConfig,
+    pub floating: FloatingConfig,
     pub input_default: InputConfig,
     pub input_touchpad: InputConfig,
     pub input_devices: HashMap<String, InputConfig>,
@@ -52,9 +79,103 @@ pub struct StaticConfig {
     pub key_bindings: HashMap<key_bindings::KeyPattern, key_bindings::Action>,
     pub tiling_enabled: bool,
     pub data_control_enabled: bool,
+    #[serde(default)]
+    pub mouse_bindings: HashMap<key_bindings::MousePattern, key_bindings::Action>,
+    #[serde(default)]
+    pub gestures: HashMap<key_bindings::Gesture, key_bindings::Action>,
+    /// Named binding modes, replacing `key_bindings` while active
+    #[serde(default)]
+    pub modes: HashMap<String, HashMap<key_bindings::KeyPattern, key_bindings::Action>>,
+    #[serde(default)]
+    pub window_rules: Vec<WindowRule>,
+}
+
+/// Errors reading the static config file
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read {}: {1}", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error(
+        "{}:{}:{}: {}",
+        .0.display(),
+        .1.position.line,
+        .1.position.col,
+        .1.code
+    )]
+    Parse(PathBuf, #[source] ron::error::SpannedError),
 }
 
 impl StaticConfig {
+    /// Parses the config file at `path`, without adding any defaults.
+    pub fn parse(path: &Path) -> Result<StaticConfig, ConfigError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .open(path)
+            .map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+        ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_reader(file)
+            .map_err(|err| ConfigError::Parse(path.to_path_buf(), err))
+    }
+
+    /// Parses the config file at `path` and adds the default bindings and rules.
+    pub fn load(
+        path: &Path,
+        workspace_layout: WorkspaceLayout,
+    ) -> Result<StaticConfig, ConfigError> {
+        let mut config = Self::parse(path)?;
+
+        key_bindings::add_default_bindings(&mut config.key_bindings, workspace_layout);
+        key_bindings::add_default_gestures(&mut config.gestures, workspace_layout);
+        let user_rules = std::mem::take(&mut config.window_rules);
+        config.window_rules = window_rules::default_rules();
+        config.window_rules.extend(user_rules);
+
+        Ok(config)
+    }
+
+    /// The config used without any config file
+    pub fn fallback(workspace_layout: WorkspaceLayout) -> StaticConfig {
+        let mut gestures = HashMap::new();
+        key_bindings::add_default_gestures(&mut gestures, workspace_layout);
+
+        StaticConfig {
+            key_bindings: HashMap::new(),
+            tiling_enabled: false,
+            data_control_enabled: false,
+            mouse_bindings: HashMap::new(),
+            gestures,
+            modes: HashMap::new(),
+            window_rules: window_rules::default_rules(),
+        }
+    }
+
+    /// The key bindings of the given mode, or the default ones for `None` or unknown modes.
+    pub fn key_bindings(
+        &self,
+        mode: Option<&str>,
+    ) -> &HashMap<key_bindings::KeyPattern, key_bindings::Action> {
+        mode.and_then(|name| self.modes.get(name))
+            .unwrap_or(&self.key_bindings)
+    }
+
+    /// The mouse bindings of the given mode. Modes only bind keys,
+    /// so the default mouse bindings are only used outside of known modes.
+    pub fn mouse_bindings(
+        &self,
+        mode: Option<&str>,
+    ) -> impl Iterator<Item = (&key_bindings::MousePattern, &key_bindings::Action)> {
+        mode.filter(|name| self.modes.contains_key(*name))
+            .is_none()
+            .then_some(&self.mouse_bindings)
+            .into_iter()
+            .flatten()
+    }
+
+    pub fn window_rule_actions(&self, window: &crate::shell::CosmicSurface) -> WindowRuleActions {
+        window_rules::window_rule_actions(&self.window_rules, window)
+    }
+
     pub fn get_shortcut_for_action(&self, action: &Action) -> Option<String> {
         let possible_variants = self
             .key_bindings
@@ -65,7 +186,7 @@ impl StaticConfig {
 
         possible_variants
             .iter()
-            .find(|b| b.key.is_none()) // prefer short bindings
+            .find(|b| b.is_modifiers_only()) // prefer short bindings
             .or_else(|| {
                 possible_variants
                     .iter() // prefer bindings containing arrow keys
@@ -87,9 +208,10 @@ impl StaticConfig {
 #[derive(Debug)]
 pub struct DynamicConfig {
     outputs: (Option<PathBuf>, OutputsConfig),
+    layouts: (Option<PathBuf>, SavedLayouts),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct OutputsConfig {
     pub config: HashMap<Vec<OutputInfo>, Vec<OutputConfig>>,
 }
@@ -172,10 +294,20 @@ impl Config {
             .expect("Failed to add cosmic-config to the event loop");
         let xdg = xdg::BaseDirectories::new().ok();
         let workspace = get_config::<WorkspaceConfig>(&config, "workspaces");
+        let (static_conf, static_path) =
+            Self::load_static(xdg.as_ref(), workspace.workspace_layout, loop_handle);
+        let static_modified = static_path.as_deref().and_then(modified_time);
+        watch_static(loop_handle, xdg.clone());
+        persist_layouts(loop_handle);
         Config {
-            static_conf: Self::load_static(xdg.as_ref(), workspace.workspace_layout),
+            static_conf,
+            static_path,
+            static_modified,
             dynamic_conf: Self::load_dynamic(xdg.as_ref()),
             xkb: get_config(&config, "xkb_config"),
+            keyboard: get_config(&config, "keyboard_config"),
+            focus: get_config(&config, "focus_config"),
+            floating: get_config(&config, "floating_config"),
             input_default: get_config(&config, "input_default"),
             input_touchpad: get_config(&config, "input_touchpad"),
             input_devices: get_config(&config, "input_devices"),
@@ -187,73 +319,63 @@ impl Config {
     fn load_static(
         xdg: Option<&xdg::BaseDirectories>,
         workspace_layout: WorkspaceLayout,
-    ) -> StaticConfig {
-        let mut locations = if let Some(base) = xdg {
-            vec![
-                base.get_config_file("cosmic-comp.ron"),
-                base.get_config_file("cosmic-comp/config.ron"),
-            ]
-        } else {
-            Vec::with_capacity(3)
+        loop_handle: &LoopHandle<'_, State>,
+    ) -> (StaticConfig, Option<PathBuf>) {
+        let Some(path) = static_config_path(xdg) else {
+            info!("No config file found, using the default config");
+            return (StaticConfig::fallback(workspace_layout), None);
         };
-        if cfg!(debug_assertions) {
-            if let Ok(mut cwd) = std::env::current_dir() {
-                cwd.push("config.ron");
-                locations.push(cwd);
-            }
-        }
-        locations.push(PathBuf::from("/etc/cosmic-comp/config.ron"));
-        locations.push(PathBuf::from("/etc/cosmic-comp.ron"));
 
-        for path in locations {
-            debug!("Trying config location: {}", path.display());
-            if path.exists() {
-                info!("Using config at {}", path.display());
-                let mut config: StaticConfig =
-                    ron::de::from_reader(OpenOptions::new().read(true).open(path).unwrap())
-                        .expect("Malformed config file");
-
-                key_bindings::add_default_bindings(&mut config.key_bindings, workspace_layout);
-
-                return config;
+        info!("Using config at {}", path.display());
+        match StaticConfig::load(&path, workspace_layout) {
+            Ok(config) => (config, Some(path)),
+            Err(err) => {
+                error!("{}", err);
+                warn!("Falling back to the default config");
+                // the shell doesn't exist yet, show the error once we are up
+                let body = err.to_string();
+                loop_handle.insert_idle(move |state| {
+                    state.common.shell.show_notification(
+                        crate::fl!("config-error"),
+                        body,
+                        state.common.event_loop_handle.clone(),
+                    );
+                });
+                (StaticConfig::fallback(workspace_layout), Some(path))
             }
         }
-
-        StaticConfig {
-            key_bindings: HashMap::new(),
-            tiling_enabled: false,
-            data_control_enabled: false,
-        }
     }
 
     fn load_dynamic(xdg: Option<&xdg::BaseDirectories>) -> DynamicConfig {
         let output_path =
             xdg.and_then(|base| base.place_state_file("cosmic-comp/outputs.ron").ok());
-        let outputs = Self::load_outputs(&output_path);
+        let outputs = Self::load_state_file(&output_path);
+        let layouts_path =
+            xdg.and_then(|base| base.place_state_file("cosmic-comp/layouts.ron").ok());
+        let layouts = Self::load_state_file(&layouts_path);
 
         DynamicConfig {
             outputs: (output_path, outputs),
+            layouts: (layouts_path, layouts),
         }
     }
 
-    fn load_outputs(path: &Option<PathBuf>) -> OutputsConfig {
+    fn load_state_file<T: Default + serde::de::DeserializeOwned>(path: &Option<PathBuf>) -> T {
         if let Some(path) = path.as_ref() {
             if path.exists() {
                 match ron::de::from_reader(OpenOptions::new().read(true).open(path).unwrap()) {
                     Ok(config) => return config,
                     Err(err) => {
-                        warn!(?err, "Failed to read output_config, resetting..");
+                        warn!(?err, "Failed to read {}, resetting..", path.display());
                         if let Err(err) = std::fs::remove_file(path) {
-                            error!(?err, "Failed to remove output_config.");
+                            error!(?err, "Failed to remove {}.", path.display());
                         }
                     }
                 };
             }
         }
 
-        OutputsConfig {
-            config: HashMap::new(),
-        }
+        T::default()
     }
 
     pub fn read_outputs(
@@ -492,6 +614,14 @@ impl DynamicConfig {
     pub fn outputs_mut<'a>(&'a mut self) -> PersistenceGuard<'a, OutputsConfig> {
         PersistenceGuard(self.outputs.0.clone(), &mut self.outputs.1)
     }
+
+    pub fn layouts(&self) -> &SavedLayouts {
+        &self.layouts.1
+    }
+
+    pub fn layouts_mut<'a>(&'a mut self) -> PersistenceGuard<'a, SavedLayouts> {
+        PersistenceGuard(self.layouts.0.clone(), &mut self.layouts.1)
+    }
 }
 
 fn get_config<T: Default + serde::de::DeserializeOwned>(
@@ -504,6 +634,169 @@ fn get_config<T: Default + serde::de::DeserializeOwned>(
     })
 }
 
+/// The first existing location of the static config file
+/// Possible locations of the static config file, in order of precedence
+fn static_config_locations(xdg: Option<&xdg::BaseDirectories>) -> Vec<PathBuf> {
+    let mut locations = if let Some(base) = xdg {
+        vec![
+            base.get_config_file("cosmic-comp.ron"),
+            base.get_config_file("cosmic-comp/config.ron"),
+        ]
+    } else {
+        Vec::with_capacity(3)
+    };
+    if cfg!(debug_assertions) {
+        if let Ok(mut cwd) = std::env::current_dir() {
+            cwd.push("config.ron");
+            locations.push(cwd);
+        }
+    }
+    locations.push(PathBuf::from("/etc/cosmic-comp/config.ron"));
+    locations.push(PathBuf::from("/etc/cosmic-comp.ron"));
+    locations
+}
+
+fn static_config_path(xdg: Option<&xdg::BaseDirectories>) -> Option<PathBuf> {
+    static_config_locations(xdg)
+        .into_iter()
+        .find(|path| path.exists())
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Reloads the static config, when its file is changed, created or removed.
+fn watch_static(loop_handle: &LoopHandle<'_, State>, xdg: Option<xdg::BaseDirectories>) {
+    let (sender, channel) = channel::channel();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let _ = sender.send(event);
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            error!(?err, "Failed to watch the config file");
+            return;
+        }
+    };
+    // the file might be created later on, so watch the directories containing it
+    let mut watched = HashSet::new();
+    let mut watch_dirs = move |watcher: &mut RecommendedWatcher, locations: &[PathBuf]| {
+        for dir in locations.iter().filter_map(|path| path.parent()) {
+            if !watched.contains(dir) && watcher.watch(dir, RecursiveMode::NonRecursive).is_ok() {
+                watched.insert(dir.to_path_buf());
+            }
+        }
+    };
+    watch_dirs(&mut watcher, &static_config_locations(xdg.as_ref()));
+
+    if let Err(err) = loop_handle.insert_source(channel, move |event, _, state| {
+        let Event::Msg(event) = event else {
+            return;
+        };
+        let locations = static_config_locations(xdg.as_ref());
+        match event {
+            Ok(event) if !static_config_changed(&event, &locations) => return,
+            Ok(_) => {}
+            Err(err) => warn!(?err, "Error watching the config file"),
+        }
+        // directories containing the file might have been created
+        watch_dirs(&mut watcher, &locations);
+
+        let path = locations.into_iter().find(|path| path.exists());
+        let modified = path.as_deref().and_then(modified_time);
+        let config = &state.common.config;
+        if path != config.static_path || modified != config.static_modified {
+            reload_static(state, path);
+        }
+    }) {
+        error!(?err, "Failed to watch the config file");
+    }
+}
+
+/// Whether `event` might have changed the contents of the static config, or which file is used.
+fn static_config_changed(event: &notify::Event, locations: &[PathBuf]) -> bool {
+    let finished = match event.kind {
+        // wait for writes to finish, instead of reading partially written files
+        EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Metadata(_)) => false,
+        EventKind::Access(kind) => kind == AccessKind::Close(AccessMode::Write),
+        _ => true,
+    };
+    finished
+        && event.paths.iter().any(|path| {
+            locations
+                .iter()
+                .any(|location| location == path || location.parent() == Some(path))
+        })
+}
+
+/// How often the window layouts are saved, if they changed
+const LAYOUTS_PERSIST_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Periodically saves the window layouts, so they can be restored after a restart or crash.
+fn persist_layouts(loop_handle: &LoopHandle<'_, State>) {
+    let timer = Timer::from_duration(LAYOUTS_PERSIST_INTERVAL);
+    if let Err(err) = loop_handle.insert_source(timer, |_, _, state| {
+        // keep the previous session around until its windows are mapped again
+        if !state.common.shell.restoring_layouts() {
+            let layouts = state.common.shell.saved_layouts();
+            if &layouts != state.common.config.dynamic_conf.layouts() {
+                *state.common.config.dynamic_conf.layouts_mut() = layouts;
+            }
+        }
+        TimeoutAction::ToDuration(LAYOUTS_PERSIST_INTERVAL)
+    }) {
+        error!(?err, "Failed to persist window layouts");
+    }
+}
+
+/// Replaces the static config with the one at `path`, or the fallback config for `None`.
+///
+/// If the file is malformed, the error is shown and the previous config stays in effect.
+pub fn reload_static(state: &mut State, path: Option<PathBuf>) {
+    let workspace_layout = state.common.config.workspace.workspace_layout;
+    let result = match path.as_deref() {
+        Some(path) => StaticConfig::load(path, workspace_layout),
+        None => Ok(StaticConfig::fallback(workspace_layout)),
+    };
+    // don't try again until the file changes
+    state.common.config.static_modified = path.as_deref().and_then(modified_time);
+    state.common.config.static_path = path;
+
+    match result {
+        Ok(static_conf) => {
+            match state.common.config.static_path.as_ref() {
+                Some(path) => info!("Reloaded config at {}", path.display()),
+                None => info!("Config file removed, using the default config"),
+            }
+            state.common.config.static_conf = static_conf;
+            state
+                .common
+                .shell
+                .dismiss_notification(&state.common.event_loop_handle);
+
+            let shell = &mut state.common.shell;
+            if shell
+                .binding_mode()
+                .is_some_and(|mode| !state.common.config.static_conf.modes.contains_key(mode))
+            {
+                shell.set_binding_mode(None, state.common.event_loop_handle.clone());
+            }
+        }
+        Err(err) => {
+            error!("{}", err);
+            warn!("Keeping the previous config");
+            state.common.shell.show_notification(
+                crate::fl!("config-error"),
+                err.to_string(),
+                state.common.event_loop_handle.clone(),
+            );
+        }
+    }
+}
+
 fn update_input(state: &mut State) {
     if let BackendData::Kms(ref mut kms_state) = &mut state.backend {
         for device in kms_state.input_devices.values_mut() {
@@ -515,18 +808,37 @@ fn update_input(state: &mut State) {
 fn config_changed(config: cosmic_config::Config, keys: Vec<String>, state: &mut State) {
     for key in &keys {
         match key.as_str() {
-            "xkb-config" => {
-                let value = get_config::<XkbConfig>(&config, "xkb-config");
+            "xkb_config" => {
+                let value = get_config::<XkbConfig>(&config, "xkb_config");
                 for seat in state.common.seats().cloned().collect::<Vec<_>>().iter() {
                     if let Some(keyboard) = seat.get_keyboard() {
+                        // a new keymap resets the locked modifiers
+                        let num_lock = keyboard.modifier_state().num_lock;
                         if let Err(err) = keyboard.set_xkb_config(state, xkb_config_to_wl(&value)) {
                             error!(?err, "Failed to load provided xkb config");
                             // TODO Revert to default?
                         }
+                        state.set_numlock(seat, num_lock);
+                        keyboard.change_repeat_info(
+                            value.repeat_rate as i32,
+                            value.repeat_delay as i32,
+                        );
                     }
                 }
                 state.common.config.xkb = value;
             }
+            "keyboard_config" => {
+                // only applies to keyboards added from now on
+                state.common.config.keyboard =
+                    get_config::<KeyboardConfig>(&config, "keyboard_config");
+            }
+            "focus_config" => {
+                state.common.config.focus = get_config::<FocusConfig>(&config, "focus_config");
+            }
+            "floating_config" => {
+                state.common.config.floating =
+                    get_config::<FloatingConfig>(&config, "floating_config");
+            }
             "input_default" => {
                 let value = get_config::<InputConfig>(&config, "input_default");
                 state.common.config.input_default = value;
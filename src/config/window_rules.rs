@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::shell::CosmicSurface;
+use regex::Regex;
+use serde::Deserialize;
+use smithay::{
+    wayland::{compositor::with_states, shell::xdg::XdgToplevelSurfaceData},
+    xwayland::xwm::WmWindowType,
+};
+
+/// A regular expression matched anywhere in a string, like `grep` would.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(regex: &str) -> Result<Pattern, regex::Error> {
+        Regex::new(regex).map(Pattern)
+    }
+
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.0.is_match(haystack)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let regex = String::deserialize(deserializer)?;
+        Pattern::new(&regex).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum WindowType {
+    Normal,
+    Dialog,
+    Utility,
+    Toolbar,
+    Splash,
+    Menu,
+    DropdownMenu,
+    PopupMenu,
+    Tooltip,
+    Notification,
+}
+
+impl From<WmWindowType> for WindowType {
+    fn from(value: WmWindowType) -> Self {
+        match value {
+            WmWindowType::Normal => WindowType::Normal,
+            WmWindowType::Dialog => WindowType::Dialog,
+            WmWindowType::Utility => WindowType::Utility,
+            WmWindowType::Toolbar => WindowType::Toolbar,
+            WmWindowType::Splash => WindowType::Splash,
+            WmWindowType::Menu => WindowType::Menu,
+            WmWindowType::DropdownMenu => WindowType::DropdownMenu,
+            WmWindowType::PopupMenu => WindowType::PopupMenu,
+            WmWindowType::Tooltip => WindowType::Tooltip,
+            WmWindowType::Notification => WindowType::Notification,
+        }
+    }
+}
+
+fn window_type(window: &CosmicSurface) -> WindowType {
+    match window {
+        CosmicSurface::Wayland(window) => {
+            let has_parent = with_states(window.toplevel().wl_surface(), |states| {
+                states
+                    .data_map
+                    .get::<XdgToplevelSurfaceData>()
+                    .unwrap()
+                    .lock()
+                    .unwrap()
+                    .parent
+                    .is_some()
+            });
+            if has_parent {
+                WindowType::Dialog
+            } else {
+                WindowType::Normal
+            }
+        }
+        CosmicSurface::X11(surface) => surface
+            .window_type()
+            .map(Into::into)
+            .unwrap_or(WindowType::Normal),
+        _ => unreachable!(),
+    }
+}
+
+/// A window rule as read from the config file.
+///
+/// A rule applies to a window, if all of its given conditions match.
+/// Every action of a matching rule overrides the ones of previous rules,
+/// so user rules take precedence over the default ones.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WindowRule {
+    // conditions
+    pub app_id: Option<Pattern>,
+    pub title: Option<Pattern>,
+    /// X11 `WM_CLASS`, never matches wayland windows
+    pub class: Option<Pattern>,
+    pub window_type: Option<WindowType>,
+
+    // actions
+    /// `true` to always float a window, `false` to always tile it
+    pub floating: Option<bool>,
+    /// initial size of floating windows
+    pub size: Option<(i32, i32)>,
+    /// initial position of floating windows relative to their output
+    pub position: Option<(i32, i32)>,
+    /// 1-based index of the workspace to open on
+    pub workspace: Option<usize>,
+    /// connector name of the output to open on
+    pub output: Option<String>,
+    pub sticky: Option<bool>,
+    pub fullscreen: Option<bool>,
+    pub no_focus: Option<bool>,
+}
+
+/// The combined actions of all rules matching a window.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowRuleActions {
+    pub floating: Option<bool>,
+    pub size: Option<(i32, i32)>,
+    pub position: Option<(i32, i32)>,
+    pub workspace: Option<usize>,
+    pub output: Option<String>,
+    pub sticky: bool,
+    pub fullscreen: bool,
+    pub no_focus: bool,
+}
+
+impl WindowRule {
+    pub fn matches(&self, window: &CosmicSurface) -> bool {
+        if let Some(app_id) = self.app_id.as_ref() {
+            if !app_id.is_match(&window.app_id()) {
+                return false;
+            }
+        }
+        if let Some(title) = self.title.as_ref() {
+            if !title.is_match(&window.title()) {
+                return false;
+            }
+        }
+        if let Some(class) = self.class.as_ref() {
+            match window {
+                CosmicSurface::X11(surface) if class.is_match(&surface.class()) => {}
+                _ => return false,
+            }
+        }
+        if let Some(window_type) = self.window_type {
+            if window_type != self::window_type(window) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn apply(&self, actions: &mut WindowRuleActions) {
+        if self.floating.is_some() {
+            actions.floating = self.floating;
+        }
+        if self.size.is_some() {
+            actions.size = self.size;
+        }
+        if self.position.is_some() {
+            actions.position = self.position;
+        }
+        if self.workspace.is_some() {
+            actions.workspace = self.workspace;
+        }
+        if self.output.is_some() {
+            actions.output = self.output.clone();
+        }
+        if let Some(sticky) = self.sticky {
+            actions.sticky = sticky;
+        }
+        if let Some(fullscreen) = self.fullscreen {
+            actions.fullscreen = fullscreen;
+        }
+        if let Some(no_focus) = self.no_focus {
+            actions.no_focus = no_focus;
+        }
+    }
+}
+
+pub fn window_rule_actions(rules: &[WindowRule], window: &CosmicSurface) -> WindowRuleActions {
+    let mut actions = WindowRuleActions::default();
+    for rule in rules.iter().filter(|rule| rule.matches(window)) {
+        rule.apply(&mut actions);
+    }
+    actions
+}
+
+/// Applications known to misbehave when tiled.
+const FLOATING_EXCEPTIONS: &[(Option<&str>, Option<&str>)] = &[
+    (Some(r"Authy Desktop"), None),
+    (Some(r"Com.github.amezin.ddterm"), None),
+    (Some(r"Com.github.donadigo.eddy"), None),
+    (None, Some(r"Discord Updater")),
+    (Some(r"Enpass"), Some(r"Enpass Assistant")),
+    (Some(r"Gjs"), Some(r"Settings")),
+    (Some(r"Gnome-initial-setup"), None),
+    (Some(r"Gnome-terminal"), Some(r"Preferences – General")),
+    (Some(r"Guake"), None),
+    (Some(r"Io.elementary.sideload"), None),
+    (Some(r"KotatogramDesktop"), Some(r"Media viewer")),
+    (Some(r"Mozilla VPN"), None),
+    (Some(r"update-manager"), Some(r"Software Updater")),
+    (Some(r"Solaar"), None),
+    (Some(r"Steam"), Some(r"^.*?(Guard|Login).*")),
+    (None, Some(r"Steam")),
+    (Some(r"TelegramDesktop"), Some(r"Media viewer")),
+    (Some(r"Zotero"), Some(r"Quick Format Citation")),
+    (Some(r"gjs"), None),
+    (Some(r"gnome-screenshot"), None),
+    (Some(r"ibus-.*"), None),
+    (Some(r"jetbrains-toolbox"), None),
+    (Some(r"jetbrains-webstorm"), Some(r"Customize WebStorm")),
+    (Some(r"jetbrains-webstorm"), Some(r"License Activation")),
+    (Some(r"jetbrains-webstorm"), Some(r"Welcome to WebStorm")),
+    (Some(r"krunner"), None),
+    (Some(r"pritunl"), None),
+    (Some(r"re.sonny.Junction"), None),
+    (Some(r"system76-driver"), None),
+    (Some(r"tilda"), None),
+    (Some(r"zoom"), None),
+    (Some(r"^.*?action=join.*$"), None),
+];
+
+/// The rules shipped by default, applied before any user-defined rules.
+pub fn default_rules() -> Vec<WindowRule> {
+    FLOATING_EXCEPTIONS
+        .iter()
+        .map(|(app_id, title)| WindowRule {
+            app_id: app_id.map(|regex| Pattern::new(regex).unwrap()),
+            title: title.map(|regex| Pattern::new(regex).unwrap()),
+            floating: Some(true),
+            ..Default::default()
+        })
+        .collect()
+}
@@ -0,0 +1,286 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Touchpad gestures handled by the compositor.
+//!
+//! Gestures with an amount of fingers used by any [`Gesture`] binding are never
+//! forwarded to clients. Their direction is determined once they moved past a threshold,
+//! swipes bound to switching workspaces then move the workspaces along with the fingers
+//! and only activate the next workspace once they are lifted far enough.
+
+use std::cell::RefCell;
+
+use smithay::{
+    input::Seat,
+    output::Output,
+    utils::{Logical, Point, SERIAL_COUNTER},
+};
+
+use crate::{
+    config::{Action, Gesture, KeyModifiers, KeyPattern, PinchDirection},
+    shell::Direction,
+    state::State,
+    utils::prelude::*,
+};
+
+/// Distance of a swipe before its direction is determined
+const SWIPE_THRESHOLD: f64 = 16.0;
+/// Distance of a swipe to fully switch workspaces
+const SWIPE_DISTANCE: f64 = 300.0;
+/// Change of scale before a pinch is determined
+const PINCH_THRESHOLD: f64 = 0.15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GestureKind {
+    Swipe,
+    Pinch,
+}
+
+#[derive(Debug)]
+enum GestureProgress {
+    /// The direction is not yet known
+    Pending,
+    /// Switching workspaces of the output, following the fingers
+    WorkspaceSwitch {
+        output: Output,
+        direction: Direction,
+        progress: f64,
+    },
+    /// The gesture triggered its action already (or has none) and is ignored
+    Done,
+}
+
+#[derive(Debug)]
+struct ActiveGesture {
+    kind: GestureKind,
+    fingers: u32,
+    delta: Point<f64, Logical>,
+    progress: GestureProgress,
+}
+
+/// The compositor gesture a seat is currently performing, if any
+#[derive(Debug, Default)]
+pub struct SeatGestureState(RefCell<Option<ActiveGesture>>);
+
+impl State {
+    /// Starts a compositor gesture, returns `false` if the gesture should be forwarded instead.
+    pub(super) fn gesture_begin(
+        &mut self,
+        seat: &Seat<State>,
+        kind: GestureKind,
+        fingers: u32,
+    ) -> bool {
+        if self.common.shell.session_lock.is_some()
+            || seat.get_pointer().unwrap().is_grabbed()
+            || !self
+                .common
+                .config
+                .static_conf
+                .gestures
+                .keys()
+                .any(|gesture| gesture.fingers() == fingers && gesture_kind(gesture) == kind)
+        {
+            return false;
+        }
+
+        *seat
+            .user_data()
+            .get::<SeatGestureState>()
+            .unwrap()
+            .0
+            .borrow_mut() = Some(ActiveGesture {
+            kind,
+            fingers,
+            delta: Point::default(),
+            progress: GestureProgress::Pending,
+        });
+        true
+    }
+
+    /// Returns `false` if no compositor gesture is active.
+    pub(super) fn gesture_swipe_update(
+        &mut self,
+        seat: &Seat<State>,
+        delta: Point<f64, Logical>,
+        time: u32,
+    ) -> bool {
+        let mut state = seat
+            .user_data()
+            .get::<SeatGestureState>()
+            .unwrap()
+            .0
+            .borrow_mut();
+        let Some(gesture) = state
+            .as_mut()
+            .filter(|gesture| gesture.kind == GestureKind::Swipe)
+        else {
+            return false;
+        };
+        gesture.delta += delta;
+
+        match gesture.progress {
+            GestureProgress::Pending => {
+                let delta = gesture.delta;
+                if delta.x.abs().max(delta.y.abs()) < SWIPE_THRESHOLD {
+                    return true;
+                }
+                let direction = if delta.x.abs() > delta.y.abs() {
+                    if delta.x < 0.0 {
+                        Direction::Left
+                    } else {
+                        Direction::Right
+                    }
+                } else if delta.y < 0.0 {
+                    Direction::Up
+                } else {
+                    Direction::Down
+                };
+                let action = self
+                    .common
+                    .config
+                    .static_conf
+                    .gestures
+                    .get(&Gesture::Swipe(gesture.fingers, direction))
+                    .cloned();
+                gesture.progress = GestureProgress::Done;
+
+                match action {
+                    Some(action @ (Action::NextWorkspace | Action::PreviousWorkspace)) => {
+                        let output = seat.active_output();
+                        let active = self.common.shell.workspaces.active_num(&output).1;
+                        let target = if action == Action::NextWorkspace {
+                            active.checked_add(1)
+                        } else {
+                            active.checked_sub(1)
+                        };
+                        if let Some(target) = target {
+                            if self
+                                .common
+                                .shell
+                                .begin_workspace_gesture(&output, target)
+                                .is_ok()
+                            {
+                                gesture.progress = GestureProgress::WorkspaceSwitch {
+                                    output,
+                                    direction,
+                                    progress: 0.0,
+                                };
+                            }
+                        }
+                    }
+                    Some(action) => {
+                        std::mem::drop(state);
+                        self.gesture_action(seat, action, time);
+                    }
+                    None => {}
+                }
+            }
+            GestureProgress::WorkspaceSwitch {
+                direction,
+                ref mut progress,
+                ..
+            } => {
+                let distance = match direction {
+                    Direction::Left => -gesture.delta.x,
+                    Direction::Right => gesture.delta.x,
+                    Direction::Up => -gesture.delta.y,
+                    Direction::Down => gesture.delta.y,
+                };
+                *progress = (distance / SWIPE_DISTANCE).clamp(0.0, 1.0);
+                self.common.shell.update_workspace_gesture(*progress);
+            }
+            GestureProgress::Done => {}
+        }
+
+        true
+    }
+
+    /// Returns `false` if no compositor gesture is active.
+    pub(super) fn gesture_pinch_update(
+        &mut self,
+        seat: &Seat<State>,
+        scale: f64,
+        time: u32,
+    ) -> bool {
+        let mut state = seat
+            .user_data()
+            .get::<SeatGestureState>()
+            .unwrap()
+            .0
+            .borrow_mut();
+        let Some(gesture) = state
+            .as_mut()
+            .filter(|gesture| gesture.kind == GestureKind::Pinch)
+        else {
+            return false;
+        };
+
+        if matches!(gesture.progress, GestureProgress::Pending)
+            && (scale - 1.0).abs() >= PINCH_THRESHOLD
+        {
+            let direction = if scale < 1.0 {
+                PinchDirection::In
+            } else {
+                PinchDirection::Out
+            };
+            let action = self
+                .common
+                .config
+                .static_conf
+                .gestures
+                .get(&Gesture::Pinch(gesture.fingers, direction))
+                .cloned();
+            gesture.progress = GestureProgress::Done;
+
+            std::mem::drop(state);
+            if let Some(action) = action {
+                self.gesture_action(seat, action, time);
+            }
+        }
+
+        true
+    }
+
+    /// Returns `false` if no compositor gesture was active.
+    pub(super) fn gesture_end(&mut self, seat: &Seat<State>, cancelled: bool) -> bool {
+        let Some(gesture) = seat
+            .user_data()
+            .get::<SeatGestureState>()
+            .unwrap()
+            .0
+            .borrow_mut()
+            .take()
+        else {
+            return false;
+        };
+
+        if let GestureProgress::WorkspaceSwitch {
+            output, progress, ..
+        } = gesture.progress
+        {
+            self.common
+                .shell
+                .end_workspace_gesture(&output, !cancelled && progress >= 0.5);
+        }
+
+        true
+    }
+
+    fn gesture_action(&mut self, seat: &Seat<State>, action: Action, time: u32) {
+        self.handle_action(
+            action,
+            seat,
+            SERIAL_COUNTER.next_serial(),
+            time,
+            KeyPattern::new(KeyModifiers::default(), None),
+            None,
+            true,
+        );
+    }
+}
+
+fn gesture_kind(gesture: &Gesture) -> GestureKind {
+    match gesture {
+        Gesture::Swipe(_, _) => GestureKind::Swipe,
+        Gesture::Pinch(_, _) => GestureKind::Pinch,
+    }
+}
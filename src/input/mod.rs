@@ -2,15 +2,23 @@
 
 use crate::{
     backend::render::cursor::CursorState,
-    config::{xkb_config_to_wl, Action, Config, KeyModifiers, KeyPattern},
+    config::{xkb_config_to_wl, Action, Config, KeyModifiers, KeyPattern, MouseButton},
     shell::{
-        focus::{target::PointerFocusTarget, FocusDirection},
-        grabs::{ResizeEdge, SeatMenuGrabState, SeatMoveGrabState},
+        element::CosmicMapped,
+        focus::{
+            target::{KeyboardFocusTarget, PointerFocusTarget},
+            FocusDirection,
+        },
+        grabs::{
+            ResizeEdge, SeatMenuGrabState, SeatMoveGrabState, SeatWindowSwitcherState,
+            WindowSwitcherState,
+        },
         layout::{
             floating::ResizeGrabMarker,
-            tiling::{SwapWindowGrab, TilingLayout},
+            tiling::{Preselection, SwapWindowGrab, TilingLayout},
         },
-        Direction, FocusResult, MoveResult, OverviewMode, ResizeDirection, ResizeMode, Trigger,
+        Direction, ExposeKeyboardGrab, ExposePointerGrab, FocusResult, MoveResult, OverviewMode,
+        ResizeDirection, ResizeMode, Trigger,
     },
     state::Common,
     utils::prelude::*,
@@ -19,7 +27,10 @@ use crate::{
         protocols::screencopy::Session,
     },
 };
-use calloop::{timer::Timer, RegistrationToken};
+use calloop::{
+    timer::{TimeoutAction, Timer},
+    RegistrationToken,
+};
 use cosmic_comp_config::workspace::WorkspaceLayout;
 use cosmic_protocols::screencopy::v1::server::zcosmic_screencopy_session_v1::InputType;
 use smithay::{
@@ -32,18 +43,19 @@ use smithay::{
     },
     desktop::{layer_map_for_output, space::SpaceElement, WindowSurfaceType},
     input::{
-        keyboard::{FilterResult, KeysymHandle, XkbConfig},
+        keyboard::{FilterResult, KeysymHandle, Layout, ModifiersState, XkbConfig},
         pointer::{
-            AxisFrame, ButtonEvent, CursorImageStatus, GestureHoldBeginEvent, GestureHoldEndEvent,
-            GesturePinchBeginEvent, GesturePinchEndEvent, GesturePinchUpdateEvent,
-            GestureSwipeBeginEvent, GestureSwipeEndEvent, GestureSwipeUpdateEvent, MotionEvent,
+            AxisFrame, ButtonEvent, CursorImageStatus, Focus, GestureHoldBeginEvent,
+            GestureHoldEndEvent, GesturePinchBeginEvent, GesturePinchEndEvent,
+            GesturePinchUpdateEvent, GestureSwipeBeginEvent, GestureSwipeEndEvent,
+            GestureSwipeUpdateEvent, GrabStartData as PointerGrabStartData, MotionEvent,
             RelativeMotionEvent,
         },
         Seat, SeatState,
     },
     output::Output,
     reexports::{input::Device as InputDevice, wayland_server::DisplayHandle},
-    utils::{Point, Serial, SERIAL_COUNTER},
+    utils::{IsAlive, Point, Serial, SERIAL_COUNTER},
     wayland::{
         keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitorSeat,
         pointer_constraints::{with_pointer_constraint, PointerConstraint},
@@ -64,8 +76,15 @@ use std::{
     time::{Duration, Instant},
 };
 
+mod gestures;
+
+use self::gestures::{GestureKind, SeatGestureState};
+
 crate::utils::id_gen!(next_seat_id, SEAT_ID, SEAT_IDS);
 
+/// evdev code of the NumLock key, offset by 8 to get the xkb keycode
+const KEY_NUMLOCK: u32 = 69 + 8;
+
 #[repr(transparent)]
 pub struct SeatId(pub usize);
 pub struct ActiveOutput(pub RefCell<Output>);
@@ -73,8 +92,18 @@ pub struct ActiveOutput(pub RefCell<Output>);
 pub struct SupressedKeys(RefCell<Vec<(Keycode, Option<RegistrationToken>)>>);
 #[derive(Default, Debug)]
 pub struct ModifiersShortcutQueue(RefCell<Option<KeyPattern>>);
+/// Release bindings whose key is still held down
+#[derive(Default)]
+pub struct PendingReleaseBindings(RefCell<Vec<(Keycode, KeyPattern, Action)>>);
+#[derive(Default)]
+pub struct SupressedButtons(RefCell<Vec<u32>>);
+#[derive(Default)]
+pub struct ScrollBindingAccumulator(RefCell<(f64, f64)>);
 #[derive(Default)]
 pub struct Devices(RefCell<HashMap<String, Vec<DeviceCapability>>>);
+/// Window under the pointer and the timer focusing it, if focus follows the cursor
+#[derive(Default)]
+pub struct HoveredWindow(RefCell<Option<(CosmicMapped, Option<RegistrationToken>)>>);
 
 impl Default for SeatId {
     fn default() -> SeatId {
@@ -113,6 +142,55 @@ impl SupressedKeys {
     }
 }
 
+impl PendingReleaseBindings {
+    fn add(&self, keysym: &KeysymHandle, binding: KeyPattern, action: Action) {
+        self.0
+            .borrow_mut()
+            .push((keysym.raw_code(), binding, action));
+    }
+
+    fn take(&self, keysym: &KeysymHandle) -> Option<(KeyPattern, Action)> {
+        let mut bindings = self.0.borrow_mut();
+        let idx = bindings
+            .iter()
+            .position(|(key, _, _)| *key == keysym.raw_code())?;
+        let (_, binding, action) = bindings.remove(idx);
+        Some((binding, action))
+    }
+}
+
+impl SupressedButtons {
+    fn add(&self, button: u32) {
+        self.0.borrow_mut().push(button);
+    }
+
+    fn filter(&self, button: u32) -> bool {
+        let mut buttons = self.0.borrow_mut();
+        let len = buttons.len();
+        buttons.retain(|b| *b != button);
+        buttons.len() != len
+    }
+}
+
+impl ScrollBindingAccumulator {
+    /// Adds a scroll amount in v120 units and returns the number of full steps to trigger.
+    fn add(&self, axis: Axis, v120: f64) -> u32 {
+        let mut acc = self.0.borrow_mut();
+        let value = match axis {
+            Axis::Horizontal => &mut acc.0,
+            Axis::Vertical => &mut acc.1,
+        };
+        // changing directions resets the partial step
+        if value.signum() != v120.signum() {
+            *value = 0.0;
+        }
+        *value += v120;
+        let steps = (*value / 120.0).trunc();
+        *value -= steps * 120.0;
+        steps.abs() as u32
+    }
+}
+
 impl ModifiersShortcutQueue {
     pub fn set(&self, binding: KeyPattern) {
         let mut set = self.0.borrow_mut();
@@ -185,8 +263,13 @@ pub fn add_seat(
     userdata.insert_if_missing(Devices::default);
     userdata.insert_if_missing(SupressedKeys::default);
     userdata.insert_if_missing(ModifiersShortcutQueue::default);
+    userdata.insert_if_missing(PendingReleaseBindings::default);
+    userdata.insert_if_missing(SupressedButtons::default);
+    userdata.insert_if_missing(ScrollBindingAccumulator::default);
+    userdata.insert_if_missing(SeatGestureState::default);
     userdata.insert_if_missing(SeatMoveGrabState::default);
     userdata.insert_if_missing(SeatMenuGrabState::default);
+    userdata.insert_if_missing(SeatWindowSwitcherState::default);
     userdata.insert_if_missing(CursorState::default);
     userdata.insert_if_missing(|| ActiveOutput(RefCell::new(output.clone())));
     userdata.insert_if_missing(|| RefCell::new(CursorImageStatus::default_named()));
@@ -201,12 +284,13 @@ pub fn add_seat(
     // So instead of doing the right thing (and initialize these capabilities as matching
     // devices appear), we have to surrender to reality and just always expose a keyboard and pointer.
     let conf = config.xkb_config();
-    if let Err(err) = seat.add_keyboard(xkb_config_to_wl(&conf), 200, 25) {
+    let (delay, rate) = (conf.repeat_delay as i32, conf.repeat_rate as i32);
+    if let Err(err) = seat.add_keyboard(xkb_config_to_wl(&conf), delay, rate) {
         warn!(
             ?err,
             "Failed to load provided xkb config. Trying default...",
         );
-        seat.add_keyboard(XkbConfig::default(), 200, 25)
+        seat.add_keyboard(XkbConfig::default(), delay, rate)
             .expect("Failed to load xkb configuration files");
     }
     seat.add_pointer();
@@ -215,7 +299,39 @@ pub fn add_seat(
     seat
 }
 
+/// Delay and interval of key repetition handled by the compositor
+fn repeat_timing(config: &Config) -> (Duration, Duration) {
+    (
+        Duration::from_millis(config.xkb.repeat_delay as u64),
+        Duration::from_millis(1000 / config.xkb.repeat_rate.max(1) as u64),
+    )
+}
+
 impl State {
+    /// Locks or unlocks NumLock on the keyboard of the seat.
+    pub fn set_numlock(&mut self, seat: &Seat<State>, enabled: bool) {
+        let Some(keyboard) = seat.get_keyboard() else {
+            return;
+        };
+        if keyboard.modifier_state().num_lock == enabled {
+            return;
+        }
+
+        // there is no way to set locked modifiers directly, so press the key instead,
+        // without sending the key events to clients
+        let time = Duration::from(self.common.clock.now()).as_millis() as u32;
+        for state in [KeyState::Pressed, KeyState::Released] {
+            keyboard.input::<(), _>(
+                self,
+                Keycode::new(KEY_NUMLOCK),
+                state,
+                SERIAL_COUNTER.next_serial(),
+                time,
+                |_, _, _| FilterResult::Intercept(()),
+            );
+        }
+    }
+
     pub fn process_input_event<B: InputBackend>(
         &mut self,
         event: InputEvent<B>,
@@ -278,18 +394,7 @@ impl State {
                     let userdata = seat.user_data();
 
                     let current_output = seat.active_output();
-                    let workspace = self.common.shell.active_space_mut(&current_output);
-                    let shortcuts_inhibited = workspace
-                        .focus_stack
-                        .get(&seat)
-                        .last()
-                        .and_then(|window| {
-                            window.wl_surface().and_then(|surface| {
-                                seat.keyboard_shortcuts_inhibitor_for_surface(&surface)
-                            })
-                        })
-                        .map(|inhibitor| inhibitor.is_active())
-                        .unwrap_or(false);
+                    let shortcuts_inhibited = shortcuts_inhibited(&self.common.shell, &seat);
 
                     let keycode = event.key_code();
                     let state = event.state();
@@ -329,7 +434,7 @@ impl State {
                                             || (action_pattern.modifiers.alt && !modifiers.alt)
                                             || (action_pattern.modifiers.logo && !modifiers.logo)
                                             || (action_pattern.modifiers.shift && !modifiers.shift)
-                                            || (action_pattern.key.is_some() && handle.raw_syms().contains(&action_pattern.key.unwrap()) && state == KeyState::Released)
+                                            || (action_pattern.matches_key(&handle) && state == KeyState::Released)
                                         {
                                             data.common.shell.set_overview_mode(None, data.common.event_loop_handle.clone());
 
@@ -386,12 +491,37 @@ impl State {
                                         }
                                     }
 
+                                    // Leave the window switcher, if any modifier was released, or cancel it with escape
+                                    let switcher = userdata.get::<SeatWindowSwitcherState>().unwrap();
+                                    let released = switcher.borrow().as_ref().is_some_and(|switcher| {
+                                        let action_modifiers = switcher.modifiers();
+                                        (action_modifiers.ctrl && !modifiers.ctrl)
+                                            || (action_modifiers.alt && !modifiers.alt)
+                                            || (action_modifiers.logo && !modifiers.logo)
+                                            || (action_modifiers.shift && !modifiers.shift)
+                                    });
+                                    if released {
+                                        let switcher = switcher.take().unwrap();
+                                        let seat = seat.clone();
+                                        data.common.event_loop_handle.insert_idle(move |state| {
+                                            switcher.activate(state, &seat);
+                                        });
+                                    } else if switcher.borrow().is_some()
+                                        && handle.modified_sym() == Keysym::Escape
+                                        && state == KeyState::Pressed
+                                    {
+                                        userdata.get::<SupressedKeys>().unwrap().add(&handle, None);
+                                        return FilterResult::Intercept(Some((
+                                            Action::Escape,
+                                            KeyPattern::new(modifiers.clone(), Some(Keysym::Escape)),
+                                        )));
+                                    }
+
                                     // Leave or update resize mode, if modifiers changed or initial key was released
                                     if let (ResizeMode::Started(action_pattern, _, _), _) =
                                         data.common.shell.resize_mode()
                                     {
-                                        if action_pattern.key.is_some() && state == KeyState::Released
-                                            && handle.raw_syms().contains(&action_pattern.key.unwrap())
+                                        if state == KeyState::Released && action_pattern.matches_key(&handle)
                                         {
                                             data.common.shell.set_resize_mode(None, &data.common.config, data.common.event_loop_handle.clone());
                                         } else if action_pattern.modifiers != *modifiers {
@@ -434,10 +564,10 @@ impl State {
                                                 edge.flip_direction();
                                             }
                                             let action = Action::_ResizingInternal(direction, edge, state);
-                                            let key_pattern = KeyPattern {
-                                                modifiers: modifiers.clone().into(),
-                                                key: Some(Keysym::new(handle.raw_code().raw())),
-                                            };
+                                            let key_pattern = KeyPattern::new(
+                                                modifiers.clone(),
+                                                Some(Keysym::new(handle.raw_code().raw())),
+                                            );
 
                                             if state == KeyState::Released {
                                                 if let Some(tokens) = userdata.get::<SupressedKeys>().unwrap().filter(&handle) {
@@ -451,10 +581,11 @@ impl State {
                                                     let action_clone = action.clone();
                                                     let key_pattern_clone = key_pattern.clone();
                                                     let start = Instant::now();
-                                                    loop_handle.insert_source(Timer::from_duration(Duration::from_millis(200)), move |current, _, state| {
+                                                    let (delay, interval) = repeat_timing(&data.common.config);
+                                                    loop_handle.insert_source(Timer::from_duration(delay), move |current, _, state| {
                                                         let duration = current.duration_since(start).as_millis();
                                                         state.handle_action(action_clone.clone(), &seat_clone, serial, time.overflowing_add(duration as u32).0, key_pattern_clone.clone(), None, true);
-                                                        calloop::timer::TimeoutAction::ToDuration(Duration::from_millis(25))
+                                                        calloop::timer::TimeoutAction::ToDuration(interval)
                                                     }).ok()
                                                 } else { None };
 
@@ -485,13 +616,20 @@ impl State {
                                                 .add(&handle, None);
                                         return FilterResult::Intercept(Some((
                                             Action::Escape,
-                                            KeyPattern {
-                                                modifiers: KeyModifiers::default(),
-                                                key: Some(Keysym::Escape),
-                                            }
+                                            KeyPattern::new(KeyModifiers::default(), Some(Keysym::Escape)),
                                         )));
                                     }
 
+                                    // Trigger release bindings on the release of their key, even if the modifiers changed since
+                                    if state == KeyState::Released {
+                                        if let Some((binding, action)) = userdata.get::<PendingReleaseBindings>().unwrap().take(&handle) {
+                                            for token in userdata.get::<SupressedKeys>().unwrap().filter(&handle).into_iter().flatten() {
+                                                loop_handle.remove(token);
+                                            }
+                                            return FilterResult::Intercept(Some((action, binding)));
+                                        }
+                                    }
+
                                     // Skip released events for initially surpressed keys
                                     if state == KeyState::Released {
                                         if let Some(tokens) = userdata.get::<SupressedKeys>().unwrap().filter(&handle) {
@@ -540,34 +678,73 @@ impl State {
                                     }
 
                                     // handle the rest of the global shortcuts
+                                    let binding_mode = data.common.shell.binding_mode();
+                                    let key_bindings = data.common.config.static_conf.key_bindings(binding_mode);
+
+                                    // leave binding modes, unless they bind escape themselves
+                                    if binding_mode.is_some()
+                                        && handle.modified_sym() == Keysym::Escape
+                                        && state == KeyState::Pressed
+                                        && !key_bindings.keys().any(|binding| binding.key == Some(Keysym::Escape))
+                                    {
+                                        userdata
+                                                .get::<SupressedKeys>()
+                                                .unwrap()
+                                                .add(&handle, None);
+                                        return FilterResult::Intercept(Some((
+                                            Action::ExitMode,
+                                            KeyPattern::new(modifiers.clone(), Some(Keysym::Escape)),
+                                        )));
+                                    }
+
                                     let mut can_clear_modifiers_shortcut = true;
                                     if !shortcuts_inhibited {
                                         let modifiers_queue = userdata.get::<ModifiersShortcutQueue>().unwrap();
-                                        for (binding, action) in
-                                            data.common.config.static_conf.key_bindings.iter()
-                                        {
-                                            let modifiers_bypass = binding.key.is_none()
+                                        // bindings ignoring Shift only apply if no binding matches the exact modifiers
+                                        let exact_match = key_bindings
+                                            .keys()
+                                            .any(|binding| binding.matches_key(&handle) && binding.modifiers == *modifiers);
+                                        for (binding, action) in key_bindings.iter() {
+                                            let modifiers_bypass = binding.is_modifiers_only()
                                                 && state == KeyState::Released
                                                 && binding.modifiers != *modifiers
                                                 && modifiers_queue.take(binding);
 
-                                            if !modifiers_bypass && binding.key.is_none() && state == KeyState::Pressed && binding.modifiers == *modifiers {
+                                            if !modifiers_bypass && binding.is_modifiers_only() && state == KeyState::Pressed && binding.modifiers == *modifiers {
                                                 modifiers_queue.set(binding.clone());
                                                 can_clear_modifiers_shortcut = false;
                                             }
 
-                                            if (
-                                                    binding.key.is_some()
-                                                    && state == KeyState::Pressed
-                                                    && handle.raw_syms().contains(&binding.key.unwrap())
-                                                    && binding.modifiers == *modifiers
-                                                ) || modifiers_bypass
-                                            {
+                                            let modifiers_match = binding.modifiers == *modifiers
+                                                || (!exact_match
+                                                    && action.has_large_step()
+                                                    && modifiers.shift
+                                                    && binding.modifiers == ModifiersState { shift: false, ..*modifiers });
+                                            let key_matches = binding.matches_key(&handle) && modifiers_match;
+
+                                            // don't pass the press of release bindings to clients either,
+                                            // they are triggered once the key is released
+                                            if key_matches && binding.release && state == KeyState::Pressed {
                                                 modifiers_queue.clear();
                                                 userdata
                                                     .get::<SupressedKeys>()
                                                     .unwrap()
                                                     .add(&handle, None);
+                                                userdata
+                                                    .get::<PendingReleaseBindings>()
+                                                    .unwrap()
+                                                    .add(&handle, binding.clone(), action.clone());
+                                                return FilterResult::Intercept(None);
+                                            }
+
+                                            if (key_matches && !binding.release && state == KeyState::Pressed) || modifiers_bypass {
+                                                modifiers_queue.clear();
+                                                if state == KeyState::Pressed || modifiers_bypass {
+                                                    userdata
+                                                        .get::<SupressedKeys>()
+                                                        .unwrap()
+                                                        .add(&handle, None);
+                                                }
                                                 return FilterResult::Intercept(Some((
                                                     action.clone(),
                                                     binding.clone(),
@@ -770,6 +947,7 @@ impl State {
                             session.cursor_info(&seat, InputType::Pointer, geometry, offset);
                         }
                     }
+                    self.update_hover_focus(&seat, position);
                     #[cfg(feature = "debug")]
                     if self.common.seats().position(|x| x == &seat).unwrap() == 0 {
                         if let Some(output) = self.common.shell.outputs().next() {
@@ -816,6 +994,7 @@ impl State {
                         },
                     );
                     ptr.frame(self);
+                    self.update_hover_focus(&seat, position);
                     #[cfg(feature = "debug")]
                     if self.common.seats().position(|x| x == &seat).unwrap() == 0 {
                         if let Some(output) = self.common.shell.outputs().next() {
@@ -846,6 +1025,8 @@ impl State {
 
                     let serial = SERIAL_COUNTER.next_serial();
                     let button = event.button_code();
+
+                    let supressed_buttons = seat.user_data().get::<SupressedButtons>().unwrap();
                     if event.state() == ButtonState::Pressed {
                         // change the keyboard focus unless the pointer or keyboard is grabbed
                         // We test for any matching surface type here but always use the root
@@ -956,8 +1137,31 @@ impl State {
                                 &seat,
                                 Some(serial),
                             );
+
+                            // Handle mouse bindings once the clicked window is focused,
+                            // so they act on it, and skip releases of their buttons
+                            if let Some((action, pattern)) =
+                                mouse_binding(&self.common, &seat, |binding| {
+                                    binding.code() == Some(button)
+                                })
+                            {
+                                supressed_buttons.add(button);
+                                self.handle_action(
+                                    action,
+                                    &seat,
+                                    serial,
+                                    event.time_msec(),
+                                    pattern,
+                                    None,
+                                    true,
+                                );
+                                return;
+                            }
                         }
                     } else {
+                        if supressed_buttons.filter(button) {
+                            return;
+                        }
                         if let OverviewMode::Started(Trigger::Pointer(action_button), _) =
                             self.common.shell.overview_mode().0
                         {
@@ -989,9 +1193,9 @@ impl State {
                         1.0
                     };
 
-                if let Some(seat) = self.common.seat_with_device(&event.device()) {
+                if let Some(seat) = self.common.seat_with_device(&event.device()).cloned() {
                     #[cfg(feature = "debug")]
-                    if self.common.seats().position(|x| x == seat).unwrap() == 0
+                    if self.common.seats().position(|x| x == &seat).unwrap() == 0
                         && self.common.egui.active
                     {
                         if self.common.egui.state.wants_pointer() {
@@ -1015,6 +1219,55 @@ impl State {
                         }
                     }
 
+                    // Handle scroll bindings for every full step of a wheel
+                    if matches!(event.source(), AxisSource::Wheel | AxisSource::WheelTilt)
+                        && !seat.get_pointer().unwrap().is_grabbed()
+                        && !seat.get_keyboard().map(|k| k.is_grabbed()).unwrap_or(false)
+                    {
+                        let mut intercepted = false;
+                        for (axis, negative, positive) in [
+                            (
+                                Axis::Vertical,
+                                MouseButton::ScrollUp,
+                                MouseButton::ScrollDown,
+                            ),
+                            (
+                                Axis::Horizontal,
+                                MouseButton::ScrollLeft,
+                                MouseButton::ScrollRight,
+                            ),
+                        ] {
+                            let Some(v120) = event.amount_v120(axis).filter(|v| *v != 0.0) else {
+                                continue;
+                            };
+                            let direction = if v120 < 0.0 { negative } else { positive };
+                            if let Some((action, pattern)) =
+                                mouse_binding(&self.common, &seat, |binding| binding == direction)
+                            {
+                                intercepted = true;
+                                let steps = seat
+                                    .user_data()
+                                    .get::<ScrollBindingAccumulator>()
+                                    .unwrap()
+                                    .add(axis, v120);
+                                for _ in 0..steps {
+                                    self.handle_action(
+                                        action.clone(),
+                                        &seat,
+                                        SERIAL_COUNTER.next_serial(),
+                                        event.time_msec(),
+                                        pattern.clone(),
+                                        None,
+                                        true,
+                                    );
+                                }
+                            }
+                        }
+                        if intercepted {
+                            return;
+                        }
+                    }
+
                     let mut frame = AxisFrame::new(event.time_msec()).source(event.source());
                     if let Some(horizontal_amount) = event.amount(Axis::Horizontal) {
                         if horizontal_amount != 0.0 {
@@ -1043,7 +1296,10 @@ impl State {
                 }
             }
             InputEvent::GestureSwipeBegin { event, .. } => {
-                if let Some(seat) = self.common.seat_with_device(&event.device()) {
+                if let Some(seat) = self.common.seat_with_device(&event.device()).cloned() {
+                    if self.gesture_begin(&seat, GestureKind::Swipe, event.fingers()) {
+                        return;
+                    }
                     let serial = SERIAL_COUNTER.next_serial();
                     let pointer = seat.get_pointer().unwrap();
                     pointer.gesture_swipe_begin(
@@ -1057,7 +1313,10 @@ impl State {
                 }
             }
             InputEvent::GestureSwipeUpdate { event, .. } => {
-                if let Some(seat) = self.common.seat_with_device(&event.device()) {
+                if let Some(seat) = self.common.seat_with_device(&event.device()).cloned() {
+                    if self.gesture_swipe_update(&seat, event.delta(), event.time_msec()) {
+                        return;
+                    }
                     let pointer = seat.get_pointer().unwrap();
                     pointer.gesture_swipe_update(
                         self,
@@ -1069,7 +1328,10 @@ impl State {
                 }
             }
             InputEvent::GestureSwipeEnd { event, .. } => {
-                if let Some(seat) = self.common.seat_with_device(&event.device()) {
+                if let Some(seat) = self.common.seat_with_device(&event.device()).cloned() {
+                    if self.gesture_end(&seat, event.cancelled()) {
+                        return;
+                    }
                     let serial = SERIAL_COUNTER.next_serial();
                     let pointer = seat.get_pointer().unwrap();
                     pointer.gesture_swipe_end(
@@ -1083,7 +1345,10 @@ impl State {
                 }
             }
             InputEvent::GesturePinchBegin { event, .. } => {
-                if let Some(seat) = self.common.seat_with_device(&event.device()) {
+                if let Some(seat) = self.common.seat_with_device(&event.device()).cloned() {
+                    if self.gesture_begin(&seat, GestureKind::Pinch, event.fingers()) {
+                        return;
+                    }
                     let serial = SERIAL_COUNTER.next_serial();
                     let pointer = seat.get_pointer().unwrap();
                     pointer.gesture_pinch_begin(
@@ -1097,7 +1362,10 @@ impl State {
                 }
             }
             InputEvent::GesturePinchUpdate { event, .. } => {
-                if let Some(seat) = self.common.seat_with_device(&event.device()) {
+                if let Some(seat) = self.common.seat_with_device(&event.device()).cloned() {
+                    if self.gesture_pinch_update(&seat, event.scale(), event.time_msec()) {
+                        return;
+                    }
                     let pointer = seat.get_pointer().unwrap();
                     pointer.gesture_pinch_update(
                         self,
@@ -1111,7 +1379,10 @@ impl State {
                 }
             }
             InputEvent::GesturePinchEnd { event, .. } => {
-                if let Some(seat) = self.common.seat_with_device(&event.device()) {
+                if let Some(seat) = self.common.seat_with_device(&event.device()).cloned() {
+                    if self.gesture_end(&seat, event.cancelled()) {
+                        return;
+                    }
                     let serial = SERIAL_COUNTER.next_serial();
                     let pointer = seat.get_pointer().unwrap();
                     pointer.gesture_pinch_end(
@@ -1445,6 +1716,11 @@ impl State {
                 if keyboard.is_grabbed() {
                     keyboard.unset_grab();
                 }
+                seat.user_data()
+                    .get::<SeatWindowSwitcherState>()
+                    .unwrap()
+                    .take();
+                self.common.shell.close_expose();
             }
             Action::Workspace(key_num) => {
                 let current_output = seat.active_output();
@@ -1452,10 +1728,14 @@ impl State {
                     0 => 9,
                     x => x - 1,
                 };
-                let _ = self
+                if self
                     .common
                     .shell
-                    .activate(&current_output, workspace as usize);
+                    .activate(&current_output, workspace as usize)
+                    .is_ok()
+                {
+                    Common::warp_cursor_to_workspace(self, seat, &current_output);
+                }
             }
             Action::LastWorkspace => {
                 let current_output = seat.active_output();
@@ -1465,7 +1745,14 @@ impl State {
                     .workspaces
                     .len(&current_output)
                     .saturating_sub(1);
-                let _ = self.common.shell.activate(&current_output, workspace);
+                if self
+                    .common
+                    .shell
+                    .activate(&current_output, workspace)
+                    .is_ok()
+                {
+                    Common::warp_cursor_to_workspace(self, seat, &current_output);
+                }
             }
             Action::NextWorkspace => {
                 let current_output = seat.active_output();
@@ -1476,13 +1763,10 @@ impl State {
                     .active_num(&current_output)
                     .1
                     .saturating_add(1);
-                if self
-                    .common
-                    .shell
-                    .activate(&current_output, workspace)
-                    .is_err()
-                    && propagate
-                {
+                let result = self.common.shell.activate(&current_output, workspace);
+                if result.is_ok() {
+                    Common::warp_cursor_to_workspace(self, seat, &current_output);
+                } else if propagate {
                     if let Some(inferred) = pattern.inferred_direction() {
                         self.handle_action(
                             Action::SwitchOutput(inferred),
@@ -1505,13 +1789,10 @@ impl State {
                     .active_num(&current_output)
                     .1
                     .saturating_sub(1);
-                if self
-                    .common
-                    .shell
-                    .activate(&current_output, workspace)
-                    .is_err()
-                    && propagate
-                {
+                let result = self.common.shell.activate(&current_output, workspace);
+                if result.is_ok() {
+                    Common::warp_cursor_to_workspace(self, seat, &current_output);
+                } else if propagate {
                     if let Some(inferred) = pattern.inferred_direction() {
                         self.handle_action(
                             Action::SwitchOutput(inferred),
@@ -1542,6 +1823,39 @@ impl State {
                     None,
                 );
             }
+            Action::WorkspaceByName(name) => {
+                let current_output = seat.active_output();
+                if let Some(workspace) = self.common.shell.workspace_by_name(&current_output, &name)
+                {
+                    if self
+                        .common
+                        .shell
+                        .activate(&current_output, workspace)
+                        .is_ok()
+                    {
+                        Common::warp_cursor_to_workspace(self, seat, &current_output);
+                    }
+                }
+            }
+            x @ Action::MoveToWorkspaceByName(_) | x @ Action::SendToWorkspaceByName(_) => {
+                let current_output = seat.active_output();
+                let (name, follow) = match x {
+                    Action::MoveToWorkspaceByName(name) => (name, true),
+                    Action::SendToWorkspaceByName(name) => (name, false),
+                    _ => unreachable!(),
+                };
+                if let Some(workspace) = self.common.shell.workspace_by_name(&current_output, &name)
+                {
+                    let _ = Shell::move_current_window(
+                        self,
+                        seat,
+                        &current_output,
+                        (&current_output, Some(workspace)),
+                        follow,
+                        None,
+                    );
+                }
+            }
             x @ Action::MoveToLastWorkspace | x @ Action::SendToLastWorkspace => {
                 let current_output = seat.active_output();
                 let workspace = self
@@ -1659,9 +1973,11 @@ impl State {
                                 );
                                 ptr.frame(self);
                             }
+                            Common::warp_cursor_to_workspace(self, seat, &next_output);
                         }
                         Ok(None) => {
                             seat.set_active_output(&next_output);
+                            Common::warp_cursor_to_workspace(self, seat, &next_output);
                         }
                         _ => {}
                     }
@@ -1977,6 +2293,9 @@ impl State {
                     FocusResult::Handled => {}
                     FocusResult::Some(target) => {
                         Common::set_focus(self, Some(&target), seat, None);
+                        if let KeyboardFocusTarget::Element(mapped) = &target {
+                            Common::warp_cursor_to(self, seat, mapped);
+                        }
                     }
                 }
             }
@@ -1998,7 +2317,12 @@ impl State {
                         let current_output = seat.active_output();
                         let workspace = self.common.shell.active_space(&current_output);
                         if let Some(focused_window) = workspace.focus_stack.get(seat).last() {
-                            if workspace.is_tiled(focused_window) {
+                            if workspace.is_tiled(focused_window)
+                                && workspace
+                                    .tiling_layer
+                                    .mapped()
+                                    .any(|(_, m, _)| m == focused_window)
+                            {
                                 self.common.shell.set_overview_mode(
                                     Some(Trigger::KeyboardMove(pattern.modifiers)),
                                     self.common.event_loop_handle.clone(),
@@ -2027,6 +2351,62 @@ impl State {
                     }
                 }
             }
+            x @ Action::WindowSwitcher | x @ Action::WindowSwitcherPrevious => {
+                let switcher = seat.user_data().get::<SeatWindowSwitcherState>().unwrap();
+                let mut guard = switcher.borrow_mut();
+                if guard.is_none() {
+                    *guard = WindowSwitcherState::new(
+                        &self.common.shell,
+                        seat,
+                        pattern.modifiers.clone(),
+                        self.common.event_loop_handle.clone(),
+                        self.common.theme.clone(),
+                    );
+                }
+                if let Some(switcher) = guard.as_ref() {
+                    switcher.cycle(x == Action::WindowSwitcher);
+                }
+                std::mem::drop(guard);
+
+                // without modifiers to hold, switch to the selected window right away
+                if pattern.modifiers == KeyModifiers::default() {
+                    if let Some(switcher) = switcher.take() {
+                        switcher.activate(self, seat);
+                    }
+                }
+            }
+            Action::Expose => {
+                let pointer = seat.get_pointer().unwrap();
+                let keyboard = seat.get_keyboard().unwrap();
+                if self.common.shell.expose_active() {
+                    self.common.shell.close_expose();
+                    if pointer.is_grabbed() {
+                        pointer.unset_grab(self, serial, time);
+                    }
+                    if keyboard.is_grabbed() {
+                        keyboard.unset_grab();
+                    }
+                } else if !pointer.is_grabbed() && !keyboard.is_grabbed() {
+                    self.common.shell.open_expose(seat);
+                    let start_data = PointerGrabStartData {
+                        focus: None,
+                        button: 0x110,
+                        location: pointer.current_location(),
+                    };
+                    let grab = ExposePointerGrab::new(seat, start_data);
+                    pointer.set_grab(self, grab, serial, Focus::Clear);
+                    keyboard.set_grab(ExposeKeyboardGrab::new(seat), serial);
+                }
+            }
+            Action::Minimize => {
+                let current_output = seat.active_output();
+                let workspace = self.common.shell.active_space_mut(&current_output);
+                let focus_stack = workspace.focus_stack.get(seat);
+                let focused_window = focus_stack.last().cloned();
+                if let Some(window) = focused_window {
+                    self.common.shell.minimize_request(&window);
+                }
+            }
             Action::Maximize => {
                 let current_output = seat.active_output();
                 let workspace = self.common.shell.active_space_mut(&current_output);
@@ -2036,6 +2416,78 @@ impl State {
                     self.common.shell.maximize_toggle(&window);
                 }
             }
+            Action::MoveToScratchpad => {
+                let current_output = seat.active_output();
+                let workspace = self.common.shell.active_space_mut(&current_output);
+                let focus_stack = workspace.focus_stack.get(seat);
+                let focused_window = focus_stack.last().cloned();
+                if let Some(window) = focused_window {
+                    let seats = self.common.seats().cloned().collect::<Vec<_>>();
+                    self.common.shell.move_to_scratchpad(seats.iter(), &window);
+                }
+            }
+            x @ Action::MoveFloating(_) | x @ Action::ResizeFloating(_) => {
+                let config = &self.common.config.floating;
+                let shift = seat.get_keyboard().unwrap().modifier_state().shift;
+                let step = if shift {
+                    config.keyboard_step_large
+                } else {
+                    config.keyboard_step
+                } as i32;
+                match x {
+                    Action::MoveFloating(direction) => {
+                        let delta = Point::from(match direction {
+                            Direction::Left => (-step, 0),
+                            Direction::Right => (step, 0),
+                            Direction::Up => (0, -step),
+                            Direction::Down => (0, step),
+                        });
+                        self.common
+                            .shell
+                            .with_focused_floating(seat, |layer, mapped| {
+                                layer.move_element(mapped, delta)
+                            });
+                    }
+                    Action::ResizeFloating(direction) => {
+                        // the top left corner stays in place, right and down grow the window
+                        let delta = match direction {
+                            Direction::Left => (-step, 0),
+                            Direction::Right => (step, 0),
+                            Direction::Up => (0, -step),
+                            Direction::Down => (0, step),
+                        };
+                        self.common
+                            .shell
+                            .with_focused_floating(seat, |layer, mapped| {
+                                layer.resize_element(mapped, delta)
+                            });
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            Action::CenterFloating => {
+                self.common
+                    .shell
+                    .with_focused_floating(seat, |layer, mapped| layer.center_element(mapped));
+            }
+            Action::TileFloating(corner) => {
+                self.common
+                    .shell
+                    .with_focused_floating(seat, |layer, mapped| {
+                        layer.tile_element(mapped, corner)
+                    });
+            }
+            Action::RestoreFloating => {
+                self.common
+                    .shell
+                    .with_focused_floating(seat, |layer, mapped| layer.restore_element(mapped));
+            }
+            Action::ShowScratchpad => {
+                let seats = self.common.seats().cloned().collect::<Vec<_>>();
+                if let Some(mapped) = self.common.shell.toggle_scratchpad(seats.iter(), seat) {
+                    Common::set_focus(self, Some(&mapped.into()), seat, None);
+                }
+            }
             Action::Resizing(direction) => self.common.shell.set_resize_mode(
                 Some((pattern, direction)),
                 &self.common.config,
@@ -2048,6 +2500,19 @@ impl State {
                     self.common.shell.finish_resize(direction, edge);
                 }
             }
+            Action::ResizeTo(dimension, size) => {
+                let Some(focused) = seat.get_keyboard().unwrap().current_focus() else {
+                    return;
+                };
+                let output = seat.active_output();
+                let workspace = self.common.shell.active_space_mut(&output);
+                workspace.tiling_layer.resize_to(&focused, dimension, size);
+            }
+            Action::Equalize(scope) => {
+                let output = seat.active_output();
+                let workspace = self.common.shell.active_space_mut(&output);
+                workspace.tiling_layer.equalize(seat, scope);
+            }
             Action::ToggleOrientation => {
                 let output = seat.active_output();
                 let workspace = self.common.shell.active_space_mut(&output);
@@ -2060,6 +2525,21 @@ impl State {
                     .tiling_layer
                     .update_orientation(Some(orientation), &seat);
             }
+            Action::SetTilingMode(mode) => {
+                let output = seat.active_output();
+                let workspace = self.common.shell.active_space_mut(&output);
+                workspace.tiling_layer.set_mode(mode);
+            }
+            x @ Action::SplitHorizontal | x @ Action::SplitVertical | x @ Action::SplitStacked => {
+                let preselection = match x {
+                    Action::SplitHorizontal => Preselection::Horizontal,
+                    Action::SplitVertical => Preselection::Vertical,
+                    _ => Preselection::Stacked,
+                };
+                let output = seat.active_output();
+                let workspace = self.common.shell.active_space_mut(&output);
+                workspace.preselect(seat, preselection);
+            }
             Action::ToggleStacking => {
                 if let Some(new_focus) = self.common.shell.toggle_stacking_focused(seat) {
                     Common::set_focus(self, Some(&new_focus), seat, Some(serial));
@@ -2070,6 +2550,11 @@ impl State {
                 let workspace = self.common.shell.active_space_mut(&output);
                 workspace.toggle_tiling(seat);
             }
+            Action::ToggleScrolling => {
+                let output = seat.active_output();
+                let workspace = self.common.shell.active_space_mut(&output);
+                workspace.toggle_scrolling(seat);
+            }
             Action::ToggleWindowFloating => {
                 let output = seat.active_output();
                 let workspace = self.common.shell.active_space_mut(&output);
@@ -2079,6 +2564,39 @@ impl State {
                 let seats = self.common.seats().cloned().collect::<Vec<_>>();
                 self.common.shell.toggle_sticky_current(seats.iter(), seat);
             }
+            Action::EnterMode(name) => {
+                if self.common.config.static_conf.modes.contains_key(&name) {
+                    self.common
+                        .shell
+                        .set_binding_mode(Some(name), self.common.event_loop_handle.clone());
+                } else {
+                    warn!(?name, "Unknown binding mode.");
+                }
+            }
+            Action::ExitMode => {
+                self.common
+                    .shell
+                    .set_binding_mode(None, self.common.event_loop_handle.clone());
+            }
+            Action::NextLayout => {
+                let keyboard = seat.get_keyboard().unwrap();
+                keyboard.with_xkb_state(self, |mut context| context.cycle_next_layout());
+            }
+            Action::PreviousLayout => {
+                let keyboard = seat.get_keyboard().unwrap();
+                keyboard.with_xkb_state(self, |mut context| context.cycle_prev_layout());
+            }
+            Action::SetLayout(idx) => {
+                let keyboard = seat.get_keyboard().unwrap();
+                keyboard.with_xkb_state(self, |mut context| {
+                    let layouts = context.xkb().lock().unwrap().layouts().count();
+                    if (idx as usize) < layouts {
+                        context.set_layout(Layout(idx));
+                    } else {
+                        warn!(idx, layouts, "Unknown keyboard layout.");
+                    }
+                });
+            }
             Action::Spawn(command) => {
                 let (token, data) = self
                     .common
@@ -2126,6 +2644,83 @@ impl State {
         }
     }
 
+    /// Focuses the window under the pointer once it rested there for the configured delay
+    fn update_hover_focus(&mut self, seat: &Seat<State>, position: Point<f64, Global>) {
+        if !self.common.config.focus.focus_follows_cursor {
+            return;
+        }
+
+        let output = seat.active_output();
+        let relative_pos = position.to_local(&output).as_logical();
+        let on_layer = {
+            let layers = layer_map_for_output(&output);
+            layers
+                .layer_under(WlrLayer::Overlay, relative_pos)
+                .or_else(|| layers.layer_under(WlrLayer::Top, relative_pos))
+                .is_some()
+        };
+        let keyboard = seat.get_keyboard().unwrap();
+        let mapped = if on_layer
+            || keyboard.is_grabbed()
+            || seat.get_pointer().unwrap().is_grabbed()
+            || self.common.shell.session_lock.is_some()
+            || !matches!(self.common.shell.overview_mode().0, OverviewMode::None)
+        {
+            None
+        } else {
+            match self.common.shell.element_under(position, &output) {
+                Some((PointerFocusTarget::Element(mapped), _)) => Some(mapped),
+                _ => None,
+            }
+        };
+
+        let hovered = seat.user_data().get_or_insert(HoveredWindow::default);
+        let mut hovered = hovered.0.borrow_mut();
+        if hovered.as_ref().map(|(mapped, _)| mapped) == mapped.as_ref() {
+            return;
+        }
+        if let Some((_, Some(token))) = hovered.take() {
+            self.common.event_loop_handle.remove(token);
+        }
+        let Some(mapped) = mapped else {
+            return;
+        };
+        let focused = keyboard.current_focus();
+        if matches!(focused, Some(KeyboardFocusTarget::Element(ref f)) if f == &mapped) {
+            *hovered = Some((mapped, None));
+            return;
+        }
+
+        let delay = Duration::from_millis(self.common.config.focus.focus_follows_cursor_delay);
+        let seat_clone = seat.clone();
+        let target = mapped.clone();
+        let token = self
+            .common
+            .event_loop_handle
+            .insert_source(Timer::from_duration(delay), move |_, _, state| {
+                let still_hovered = match seat_clone
+                    .user_data()
+                    .get::<HoveredWindow>()
+                    .unwrap()
+                    .0
+                    .borrow_mut()
+                    .as_mut()
+                {
+                    Some((mapped, token)) if mapped == &target => {
+                        *token = None;
+                        true
+                    }
+                    _ => false,
+                };
+                if still_hovered && target.alive() {
+                    Common::set_hover_focus(state, &target, &seat_clone);
+                }
+                TimeoutAction::Drop
+            })
+            .ok();
+        *hovered = Some((mapped, token));
+    }
+
     pub fn surface_under(
         global_pos: Point<f64, Global>,
         output: &Output,
@@ -2220,6 +2815,48 @@ impl State {
     }
 }
 
+/// Whether the focused window of the seat inhibits compositor shortcuts.
+fn shortcuts_inhibited(shell: &Shell, seat: &Seat<State>) -> bool {
+    shell
+        .active_space(&seat.active_output())
+        .focus_stack
+        .get(seat)
+        .last()
+        .and_then(|window| {
+            window
+                .wl_surface()
+                .and_then(|surface| seat.keyboard_shortcuts_inhibitor_for_surface(&surface))
+        })
+        .map(|inhibitor| inhibitor.is_active())
+        .unwrap_or(false)
+}
+
+/// Looks up the mouse binding matching the current modifiers of the seat.
+fn mouse_binding(
+    common: &Common,
+    seat: &Seat<State>,
+    matches: impl Fn(MouseButton) -> bool,
+) -> Option<(Action, KeyPattern)> {
+    if shortcuts_inhibited(&common.shell, seat) {
+        return None;
+    }
+    let modifiers = seat.get_keyboard()?.modifier_state();
+    let (pattern, action) = common
+        .config
+        .static_conf
+        .mouse_bindings(common.shell.binding_mode())
+        .find(|(pattern, _)| pattern.modifiers == modifiers && matches(pattern.button))?;
+    // don't trigger modifier-only key bindings on release anymore
+    seat.user_data()
+        .get::<ModifiersShortcutQueue>()
+        .unwrap()
+        .clear();
+    Some((
+        action.clone(),
+        KeyPattern::new(pattern.modifiers.clone(), None),
+    ))
+}
+
 fn sessions_for_output(state: &Common, output: &Output) -> impl Iterator<Item = Session> {
     let workspace = state.shell.active_space(&output);
     let maybe_fullscreen = workspace.get_fullscreen();
@@ -9,11 +9,13 @@ use smithay::{
 };
 
 use anyhow::{Context, Result};
-use std::{ffi::OsString, sync::Arc};
+use std::{
+    ffi::{OsStr, OsString},
+    path::Path,
+    sync::Arc,
+};
 use tracing::{error, info, warn};
 
-use crate::wayland::handlers::compositor::client_compositor_state;
-
 pub mod backend;
 pub mod config;
 #[cfg(feature = "debug")]
@@ -25,12 +27,25 @@ pub mod shell;
 pub mod state;
 #[cfg(feature = "systemd")]
 pub mod systemd;
+#[cfg(test)]
+mod tests;
 pub mod theme;
 pub mod utils;
 pub mod wayland;
 pub mod xwayland;
 
 fn main() -> Result<()> {
+    // validate a config file without starting up
+    let mut args = std::env::args_os().skip(1);
+    if args.next().as_deref() == Some(OsStr::new("--check-config")) {
+        let Some(path) = args.next() else {
+            anyhow::bail!("Usage: cosmic-comp --check-config <path>");
+        };
+        config::StaticConfig::parse(Path::new(&path))?;
+        println!("{}: OK", Path::new(&path).display());
+        return Ok(());
+    }
+
     // setup logger
     logger::init_logger()?;
     info!("Cosmic starting up!");
@@ -71,18 +86,7 @@ fn main() -> Result<()> {
         }
 
         // trigger routines
-        let clients = state.common.shell.update_animations();
-        {
-            let dh = state.common.display_handle.clone();
-            for client in clients.values() {
-                client_compositor_state(&client).blocker_cleared(state, &dh);
-            }
-        }
-        state.common.shell.refresh();
-        state::Common::refresh_focus(state);
-
-        // send out events
-        let _ = state.common.display_handle.flush_clients();
+        state.refresh();
     })?;
 
     // drop eventloop & state before logger
@@ -60,13 +60,14 @@ pub mod stack;
 pub use self::stack::CosmicStack;
 pub mod window;
 pub use self::window::CosmicWindow;
+pub mod mode_indicator;
+pub mod notification;
 pub mod resize_indicator;
 pub mod stack_hover;
 pub mod swap_indicator;
 
 #[cfg(feature = "debug")]
 use egui_plot::{Corner, Legend, Plot, PlotPoints, Polygon};
-#[cfg(feature = "debug")]
 use smithay::backend::renderer::{element::texture::TextureRenderElement, gles::GlesTexture};
 #[cfg(feature = "debug")]
 use tracing::debug;
@@ -791,6 +792,48 @@ impl CosmicMapped {
         )
     }
 
+    /// Renders the window shrunk by `factor` with its geometry starting at `location`.
+    ///
+    /// Popups are left out, e.g. for previews of the window.
+    pub fn scaled_render_elements<R, C>(
+        &self,
+        renderer: &mut R,
+        location: Point<i32, Physical>,
+        scale: Scale<f64>,
+        factor: f64,
+        alpha: f32,
+    ) -> Vec<C>
+    where
+        R: Renderer + ImportAll + ImportMem + AsGlowRenderer,
+        <R as Renderer>::TextureId: 'static,
+        CosmicMappedRenderElement<R>: RenderElement<R>,
+        C: From<CosmicMappedRenderElement<R>>,
+    {
+        let (window_elements, _) = self.split_render_elements::<R, CosmicMappedRenderElement<R>>(
+            renderer,
+            location - self.geometry().loc.to_physical_precise_round(scale),
+            scale,
+            alpha,
+        );
+        window_elements
+            .into_iter()
+            .filter_map(|elem| match elem {
+                CosmicMappedRenderElement::Stack(stack) => {
+                    Some(CosmicMappedRenderElement::GrabbedStack(
+                        RescaleRenderElement::from_element(stack, location, factor),
+                    ))
+                }
+                CosmicMappedRenderElement::Window(window) => {
+                    Some(CosmicMappedRenderElement::GrabbedWindow(
+                        RescaleRenderElement::from_element(window, location, factor),
+                    ))
+                }
+                _ => None,
+            })
+            .map(C::from)
+            .collect()
+    }
+
     pub(crate) fn update_theme(&self, theme: cosmic::Theme) {
         match &self.element {
             CosmicMappedInternal::Window(w) => w.set_theme(theme),
@@ -1176,6 +1219,7 @@ where
     FocusIndicator(PixelShaderElement),
     Overlay(PixelShaderElement),
     StackHoverIndicator(MemoryRenderBufferRenderElement<R>),
+    Thumbnail(TextureRenderElement<GlesTexture>),
     #[cfg(feature = "debug")]
     Egui(TextureRenderElement<GlesTexture>),
 }
@@ -1199,6 +1243,7 @@ where
             CosmicMappedRenderElement::FocusIndicator(elem) => elem.id(),
             CosmicMappedRenderElement::Overlay(elem) => elem.id(),
             CosmicMappedRenderElement::StackHoverIndicator(elem) => elem.id(),
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.id(),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.id(),
         }
@@ -1218,6 +1263,7 @@ where
             CosmicMappedRenderElement::FocusIndicator(elem) => elem.current_commit(),
             CosmicMappedRenderElement::Overlay(elem) => elem.current_commit(),
             CosmicMappedRenderElement::StackHoverIndicator(elem) => elem.current_commit(),
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.current_commit(),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.current_commit(),
         }
@@ -1237,6 +1283,7 @@ where
             CosmicMappedRenderElement::FocusIndicator(elem) => elem.src(),
             CosmicMappedRenderElement::Overlay(elem) => elem.src(),
             CosmicMappedRenderElement::StackHoverIndicator(elem) => elem.src(),
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.src(),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.src(),
         }
@@ -1256,6 +1303,7 @@ where
             CosmicMappedRenderElement::FocusIndicator(elem) => elem.geometry(scale),
             CosmicMappedRenderElement::Overlay(elem) => elem.geometry(scale),
             CosmicMappedRenderElement::StackHoverIndicator(elem) => elem.geometry(scale),
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.geometry(scale),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.geometry(scale),
         }
@@ -1275,6 +1323,7 @@ where
             CosmicMappedRenderElement::FocusIndicator(elem) => elem.location(scale),
             CosmicMappedRenderElement::Overlay(elem) => elem.location(scale),
             CosmicMappedRenderElement::StackHoverIndicator(elem) => elem.location(scale),
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.location(scale),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.location(scale),
         }
@@ -1294,6 +1343,7 @@ where
             CosmicMappedRenderElement::FocusIndicator(elem) => elem.transform(),
             CosmicMappedRenderElement::Overlay(elem) => elem.transform(),
             CosmicMappedRenderElement::StackHoverIndicator(elem) => elem.transform(),
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.transform(),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.transform(),
         }
@@ -1319,6 +1369,7 @@ where
             CosmicMappedRenderElement::StackHoverIndicator(elem) => {
                 elem.damage_since(scale, commit)
             }
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.damage_since(scale, commit),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.damage_since(scale, commit),
         }
@@ -1338,6 +1389,7 @@ where
             CosmicMappedRenderElement::FocusIndicator(elem) => elem.opaque_regions(scale),
             CosmicMappedRenderElement::Overlay(elem) => elem.opaque_regions(scale),
             CosmicMappedRenderElement::StackHoverIndicator(elem) => elem.opaque_regions(scale),
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.opaque_regions(scale),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.opaque_regions(scale),
         }
@@ -1357,6 +1409,7 @@ where
             CosmicMappedRenderElement::FocusIndicator(elem) => elem.alpha(),
             CosmicMappedRenderElement::Overlay(elem) => elem.alpha(),
             CosmicMappedRenderElement::StackHoverIndicator(elem) => elem.alpha(),
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.alpha(),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.alpha(),
         }
@@ -1392,6 +1445,9 @@ impl RenderElement<GlowRenderer> for CosmicMappedRenderElement<GlowRenderer> {
             CosmicMappedRenderElement::StackHoverIndicator(elem) => {
                 RenderElement::<GlowRenderer>::draw(elem, frame, src, dst, damage)
             }
+            CosmicMappedRenderElement::Thumbnail(elem) => {
+                RenderElement::<GlowRenderer>::draw(elem, frame, src, dst, damage)
+            }
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => {
                 RenderElement::<GlowRenderer>::draw(elem, frame, src, dst, damage)
@@ -1415,6 +1471,7 @@ impl RenderElement<GlowRenderer> for CosmicMappedRenderElement<GlowRenderer> {
             CosmicMappedRenderElement::StackHoverIndicator(elem) => {
                 elem.underlying_storage(renderer)
             }
+            CosmicMappedRenderElement::Thumbnail(elem) => elem.underlying_storage(renderer),
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => elem.underlying_storage(renderer),
         }
@@ -1455,6 +1512,11 @@ impl<'a, 'b> RenderElement<GlMultiRenderer<'a, 'b>>
             CosmicMappedRenderElement::StackHoverIndicator(elem) => {
                 elem.draw(frame, src, dst, damage)
             }
+            CosmicMappedRenderElement::Thumbnail(elem) => {
+                let glow_frame = frame.glow_frame_mut();
+                RenderElement::<GlowRenderer>::draw(elem, glow_frame, src, dst, damage)
+                    .map_err(|err| GlMultiError::Render(err))
+            }
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => {
                 let glow_frame = frame.glow_frame_mut();
@@ -1489,6 +1551,15 @@ impl<'a, 'b> RenderElement<GlMultiRenderer<'a, 'b>>
             CosmicMappedRenderElement::StackHoverIndicator(elem) => {
                 elem.underlying_storage(renderer)
             }
+            CosmicMappedRenderElement::Thumbnail(elem) => {
+                let glow_renderer = renderer.glow_renderer_mut();
+                match elem.underlying_storage(glow_renderer) {
+                    Some(UnderlyingStorage::Wayland(buffer)) => {
+                        Some(UnderlyingStorage::Wayland(buffer))
+                    }
+                    _ => None,
+                }
+            }
             #[cfg(feature = "debug")]
             CosmicMappedRenderElement::Egui(elem) => {
                 let glow_renderer = renderer.glow_renderer_mut();
@@ -0,0 +1,68 @@
+use crate::utils::iced::{IcedElement, Program};
+
+use calloop::LoopHandle;
+use cosmic::{
+    iced::widget::{container, horizontal_space, row},
+    iced_core::{Alignment, Background, Color, Length},
+    theme,
+    widget::{icon::from_name, text},
+    Apply,
+};
+use smithay::utils::Size;
+
+pub type ModeIndicator = IcedElement<ModeIndicatorInternal>;
+
+pub fn mode_indicator(
+    name: String,
+    evlh: LoopHandle<'static, crate::state::State>,
+    theme: cosmic::Theme,
+) -> ModeIndicator {
+    ModeIndicator::new(
+        ModeIndicatorInternal { name },
+        Size::from((1, 1)),
+        evlh,
+        theme,
+    )
+}
+
+pub struct ModeIndicatorInternal {
+    pub name: String,
+}
+
+impl Program for ModeIndicatorInternal {
+    type Message = ();
+
+    fn view(&self) -> crate::utils::iced::Element<'_, Self::Message> {
+        row(vec![
+            from_name("input-keyboard-symbolic")
+                .size(20)
+                .prefer_svg(true)
+                .icon()
+                .into(),
+            horizontal_space(8).into(),
+            text(&self.name).font(cosmic::font::FONT).size(16).into(),
+        ])
+        .align_items(Alignment::Center)
+        .apply(container)
+        .center_x()
+        .center_y()
+        .padding(8)
+        .apply(container)
+        .style(theme::Container::custom(|theme| container::Appearance {
+            icon_color: Some(Color::from(theme.cosmic().accent.on)),
+            text_color: Some(Color::from(theme.cosmic().accent.on)),
+            background: Some(Background::Color(theme.cosmic().accent_color().into())),
+            border_radius: 18.0.into(),
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
+        }))
+        .width(Length::Shrink)
+        .height(Length::Shrink)
+        .apply(container)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+    }
+}
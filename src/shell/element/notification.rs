@@ -0,0 +1,78 @@
+use crate::utils::iced::{IcedElement, Program};
+
+use calloop::LoopHandle;
+use cosmic::{
+    iced::widget::{column, container, horizontal_space, row, vertical_space},
+    iced_core::{Alignment, Background, Color, Length},
+    theme,
+    widget::{icon::from_name, text},
+    Apply,
+};
+use smithay::utils::Size;
+
+pub type Notification = IcedElement<NotificationInternal>;
+
+pub fn notification(
+    summary: String,
+    body: String,
+    evlh: LoopHandle<'static, crate::state::State>,
+    theme: cosmic::Theme,
+) -> Notification {
+    Notification::new(
+        NotificationInternal { summary, body },
+        Size::from((1, 1)),
+        evlh,
+        theme,
+    )
+}
+
+pub struct NotificationInternal {
+    pub summary: String,
+    pub body: String,
+}
+
+impl Program for NotificationInternal {
+    type Message = ();
+
+    fn view(&self) -> crate::utils::iced::Element<'_, Self::Message> {
+        row(vec![
+            from_name("dialog-warning-symbolic")
+                .size(24)
+                .prefer_svg(true)
+                .icon()
+                .into(),
+            horizontal_space(12).into(),
+            column(vec![
+                text(&self.summary)
+                    .font(cosmic::font::FONT_SEMIBOLD)
+                    .size(16)
+                    .into(),
+                vertical_space(4).into(),
+                text(&self.body).font(cosmic::font::FONT).size(14).into(),
+            ])
+            .into(),
+        ])
+        .align_items(Alignment::Center)
+        .apply(container)
+        .padding(16)
+        .style(theme::Container::custom(|theme| {
+            let component = &theme.cosmic().background.component;
+            container::Appearance {
+                icon_color: Some(Color::from(theme.cosmic().warning.base)),
+                text_color: Some(Color::from(component.on)),
+                background: Some(Background::Color(component.base.into())),
+                border_radius: 8.0.into(),
+                border_width: 1.0,
+                border_color: component.divider.into(),
+            }
+        }))
+        .width(Length::Shrink)
+        .height(Length::Shrink)
+        .apply(container)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+    }
+}
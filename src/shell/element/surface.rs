@@ -1,4 +1,7 @@
-use std::time::Duration;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
 use smithay::{
     backend::renderer::{
@@ -55,6 +58,10 @@ use crate::{
     wayland::handlers::decoration::PreferredDecorationMode,
 };
 
+/// Xdg toplevels have no minimized state, so the compositor keeps track of it
+#[derive(Debug, Default)]
+struct MinimizedFlag(AtomicBool);
+
 space_elements! {
     #[derive(Debug, Clone, PartialEq)]
     pub CosmicSurface;
@@ -383,6 +390,25 @@ impl CosmicSurface {
         }
     }
 
+    pub fn is_minimized(&self) -> bool {
+        self.user_data()
+            .get::<MinimizedFlag>()
+            .is_some_and(|flag| flag.0.load(Ordering::SeqCst))
+    }
+
+    pub fn set_minimized(&self, minimized: bool) {
+        self.user_data()
+            .insert_if_missing_threadsafe(MinimizedFlag::default);
+        self.user_data()
+            .get::<MinimizedFlag>()
+            .unwrap()
+            .0
+            .store(minimized, Ordering::SeqCst);
+        if let CosmicSurface::X11(surface) = self {
+            let _ = surface.set_minimized(minimized);
+        }
+    }
+
     pub fn min_size(&self) -> Option<Size<i32, Logical>> {
         match self {
             CosmicSurface::Wayland(window) => {
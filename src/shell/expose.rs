@@ -0,0 +1,868 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{collections::HashMap, time::Instant};
+
+use calloop::LoopHandle;
+use cosmic::{
+    iced::widget::container,
+    iced_core::{alignment::Horizontal, Background, Color, Length},
+    theme,
+    widget::text,
+    Apply,
+};
+use keyframe::{ease, functions::EaseInOutCubic};
+use smithay::{
+    backend::{
+        input::{ButtonState, KeyState},
+        renderer::{
+            element::{AsRenderElements, RenderElement},
+            ImportAll, ImportMem, Renderer,
+        },
+    },
+    desktop::{layer_map_for_output, space::SpaceElement},
+    input::{
+        keyboard::{
+            GrabStartData as KeyboardGrabStartData, KeyboardGrab, KeyboardInnerHandle,
+            ModifiersState,
+        },
+        pointer::{
+            AxisFrame, ButtonEvent, GestureHoldBeginEvent, GestureHoldEndEvent,
+            GesturePinchBeginEvent, GesturePinchEndEvent, GesturePinchUpdateEvent,
+            GestureSwipeBeginEvent, GestureSwipeEndEvent, GestureSwipeUpdateEvent,
+            GrabStartData as PointerGrabStartData, MotionEvent, PointerGrab, PointerInnerHandle,
+            RelativeMotionEvent,
+        },
+        Seat, SeatHandler,
+    },
+    output::Output,
+    utils::{IsAlive, Logical, Point, Rectangle, Serial, Size},
+};
+use xkbcommon::xkb::Keysym;
+
+use crate::{
+    backend::render::{element::AsGlowRenderer, IndicatorShader, Key, Usage},
+    state::{Common, State},
+    utils::{
+        iced::{IcedElement, Program},
+        prelude::*,
+    },
+};
+
+use super::{
+    focus::target::PointerFocusTarget, CosmicMapped, CosmicMappedRenderElement, Direction, Shell,
+    ANIMATION_DURATION,
+};
+
+/// Space between the windows and around the grid
+const GAP: i32 = 32;
+const TITLE_HEIGHT: i32 = 28;
+/// Distance the pointer has to travel, before a press on a window starts dragging it
+const DRAG_THRESHOLD: f64 = 8.0;
+
+/// Overview temporarily laying out all windows of the active workspaces side by side
+#[derive(Debug)]
+pub struct Expose {
+    started: Instant,
+    ended: Option<Instant>,
+    /// Typed text, windows not matching it are dimmed
+    filter: String,
+    selected: Option<CosmicMapped>,
+    drag: Option<ExposeDrag>,
+    titles: HashMap<CosmicMapped, IcedElement<ExposeTitle>>,
+}
+
+#[derive(Debug)]
+struct ExposeDrag {
+    window: CosmicMapped,
+    start: Point<f64, Global>,
+    location: Point<f64, Global>,
+    /// Position of the pointer relative to the dragged preview
+    offset: Point<f64, Global>,
+    factor: f64,
+    moved: bool,
+}
+
+/// Place of a window in the overview
+#[derive(Debug, Clone)]
+pub struct ExposeSlot {
+    pub window: CosmicMapped,
+    /// Geometry of the window outside of the overview
+    pub geometry: Rectangle<i32, Local>,
+    /// Geometry of the window in the overview
+    pub slot: Rectangle<i32, Local>,
+}
+
+impl ExposeSlot {
+    fn factor(&self) -> f64 {
+        self.slot.size.w as f64 / self.geometry.size.w.max(1) as f64
+    }
+}
+
+/// How a press on a window in the overview ended
+pub enum ExposeRelease {
+    Click(CosmicMapped),
+    /// The window was dropped onto the given output
+    Drop(CosmicMapped, Output),
+}
+
+impl Expose {
+    fn progress(&self) -> f32 {
+        let percentage = |since: Instant| {
+            (Instant::now().duration_since(since).as_millis() as f32
+                / ANIMATION_DURATION.as_millis() as f32)
+                .min(1.0)
+        };
+        match self.ended {
+            None => ease(EaseInOutCubic, 0.0, 1.0, percentage(self.started)),
+            Some(ended) => ease(EaseInOutCubic, 1.0, 0.0, percentage(ended)),
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        self.ended.is_some()
+            || Instant::now().duration_since(self.started) < ANIMATION_DURATION
+            || self.drag.is_some()
+    }
+
+    fn matches(&self, window: &CosmicMapped) -> bool {
+        if self.filter.is_empty() {
+            return true;
+        }
+        let filter = self.filter.to_lowercase();
+        let window = window.active_window();
+        window.title().to_lowercase().contains(&filter)
+            || window.app_id().to_lowercase().contains(&filter)
+    }
+}
+
+impl Shell {
+    /// Shows the overview, starting with the focused window of `seat` selected
+    pub fn open_expose(&mut self, seat: &Seat<State>) {
+        if self.expose_active() {
+            return;
+        }
+        let selected = self
+            .active_space(&seat.active_output())
+            .focus_stack
+            .get(seat)
+            .last()
+            .cloned();
+        self.expose = Some(Expose {
+            started: Instant::now(),
+            ended: None,
+            filter: String::new(),
+            selected,
+            drag: None,
+            titles: HashMap::new(),
+        });
+    }
+
+    /// Animates the windows back into place
+    pub fn close_expose(&mut self) {
+        if let Some(expose) = self.expose.as_mut().filter(|expose| expose.ended.is_none()) {
+            let reverse_duration = ANIMATION_DURATION
+                - Instant::now()
+                    .duration_since(expose.started)
+                    .min(ANIMATION_DURATION);
+            expose.ended = Some(Instant::now() - reverse_duration);
+            expose.drag = None;
+        }
+    }
+
+    pub fn expose_active(&self) -> bool {
+        self.expose
+            .as_ref()
+            .is_some_and(|expose| expose.ended.is_none())
+    }
+
+    pub(super) fn expose_animating(&self) -> bool {
+        self.expose.as_ref().is_some_and(Expose::is_animating)
+    }
+
+    /// Progress of showing the overview, `None` if it is hidden
+    pub fn expose_progress(&mut self) -> Option<f32> {
+        if let Some(ended) = self.expose.as_ref().and_then(|expose| expose.ended) {
+            if Instant::now().duration_since(ended) > ANIMATION_DURATION {
+                self.expose = None;
+            }
+        }
+        self.expose.as_ref().map(Expose::progress)
+    }
+
+    pub fn expose_selected(&self) -> Option<CosmicMapped> {
+        self.expose
+            .as_ref()
+            .and_then(|expose| expose.selected.clone())
+    }
+
+    /// Windows of the active workspace of `output`, including sticky ones, and their place in the overview
+    pub fn expose_layout(&self, output: &Output) -> Vec<ExposeSlot> {
+        let Some(set) = self.workspaces.sets.get(output) else {
+            return Vec::new();
+        };
+        let workspace = &set.workspaces[set.active];
+
+        let mut windows = workspace
+            .mapped()
+            .filter_map(|mapped| Some((mapped.clone(), workspace.element_geometry(mapped)?)))
+            .chain(set.sticky_layer.mapped().filter_map(|mapped| {
+                Some((mapped.clone(), set.sticky_layer.element_geometry(mapped)?))
+            }))
+            .collect::<Vec<_>>();
+        // keep the rough arrangement of the windows
+        windows.sort_by_key(|(_, geometry)| (geometry.loc.y, geometry.loc.x));
+
+        let area = layer_map_for_output(output).non_exclusive_zone().as_local();
+        let sizes = windows
+            .iter()
+            .map(|(_, geometry)| geometry.size)
+            .collect::<Vec<_>>();
+        windows
+            .into_iter()
+            .zip(grid(&sizes, area))
+            .map(|((window, geometry), slot)| ExposeSlot {
+                window,
+                geometry,
+                slot,
+            })
+            .collect()
+    }
+
+    /// Window of the overview at `location`, its slot and how much it is shrunk
+    pub fn expose_window_under(
+        &self,
+        location: Point<f64, Global>,
+    ) -> Option<(CosmicMapped, Rectangle<i32, Global>, f64)> {
+        let output = self
+            .outputs()
+            .find(|output| output.geometry().to_f64().contains(location))?;
+        self.expose_layout(output).into_iter().find_map(|slot| {
+            let global = slot.slot.to_global(output);
+            global
+                .to_f64()
+                .contains(location)
+                .then(|| (slot.window.clone(), global, slot.factor()))
+        })
+    }
+
+    /// Adds a typed character to the filter and selects the first matching window
+    pub fn expose_filter_push(&mut self, c: char) {
+        if let Some(expose) = self.expose.as_mut() {
+            expose.filter.push(c);
+        }
+        self.expose_select_match();
+    }
+
+    pub fn expose_filter_pop(&mut self) {
+        if let Some(expose) = self.expose.as_mut() {
+            expose.filter.pop();
+        }
+        self.expose_select_match();
+    }
+
+    fn expose_select_match(&mut self) {
+        let slots = self
+            .outputs()
+            .flat_map(|output| self.expose_layout(output))
+            .collect::<Vec<_>>();
+        if let Some(expose) = self.expose.as_mut() {
+            if let Some(slot) = slots.into_iter().find(|slot| expose.matches(&slot.window)) {
+                expose.selected = Some(slot.window);
+            }
+        }
+    }
+
+    /// Moves the selection to the closest window in `direction`, across outputs
+    pub fn expose_select_direction(&mut self, direction: Direction) {
+        let slots = self
+            .outputs()
+            .flat_map(|output| {
+                self.expose_layout(output)
+                    .into_iter()
+                    .map(|slot| (slot.window, slot.slot.to_global(output)))
+            })
+            .collect::<Vec<_>>();
+        let Some(expose) = self.expose.as_mut() else {
+            return;
+        };
+
+        let center = |rect: &Rectangle<i32, Global>| rect.loc + rect.size.downscale(2).to_point();
+        let Some(current) = expose
+            .selected
+            .as_ref()
+            .and_then(|selected| slots.iter().find(|(window, _)| window == selected))
+            .map(|(_, rect)| center(rect))
+        else {
+            expose.selected = slots.into_iter().next().map(|(window, _)| window);
+            return;
+        };
+
+        let next = slots
+            .into_iter()
+            .filter_map(|(window, rect)| {
+                let delta = center(&rect) - current;
+                let (along, across) = match direction {
+                    Direction::Left => (-delta.x, delta.y),
+                    Direction::Right => (delta.x, delta.y),
+                    Direction::Up => (-delta.y, delta.x),
+                    Direction::Down => (delta.y, delta.x),
+                };
+                (along > 0).then_some((window, along + across.abs() * 2))
+            })
+            .min_by_key(|(_, distance)| *distance);
+        if let Some((window, _)) = next {
+            expose.selected = Some(window);
+        }
+    }
+
+    /// Starts dragging the window at `location`, returns `false` if there is none
+    pub fn expose_press(&mut self, location: Point<f64, Global>) -> bool {
+        let Some((window, slot, factor)) = self.expose_window_under(location) else {
+            return false;
+        };
+        if let Some(expose) = self.expose.as_mut() {
+            expose.selected = Some(window.clone());
+            expose.drag = Some(ExposeDrag {
+                window,
+                start: location,
+                location,
+                offset: location - slot.loc.to_f64(),
+                factor,
+                moved: false,
+            });
+        }
+        true
+    }
+
+    pub fn expose_motion(&mut self, location: Point<f64, Global>) {
+        let hovered = self
+            .expose_window_under(location)
+            .map(|(window, _, _)| window);
+        let Some(expose) = self.expose.as_mut() else {
+            return;
+        };
+        if let Some(drag) = expose.drag.as_mut() {
+            drag.location = location;
+            let delta = location - drag.start;
+            drag.moved |= delta.x.abs() + delta.y.abs() > DRAG_THRESHOLD;
+        } else if hovered.is_some() {
+            expose.selected = hovered;
+        }
+    }
+
+    pub fn expose_release(&mut self, location: Point<f64, Global>) -> Option<ExposeRelease> {
+        let drag = self.expose.as_mut()?.drag.take()?;
+        if !drag.moved {
+            return Some(ExposeRelease::Click(drag.window));
+        }
+        let output = self
+            .outputs()
+            .find(|output| output.geometry().to_f64().contains(location))?
+            .clone();
+        Some(ExposeRelease::Drop(drag.window, output))
+    }
+
+    pub fn expose_elements<R>(
+        &mut self,
+        renderer: &mut R,
+        output: &Output,
+        progress: f32,
+        evlh: &LoopHandle<'static, State>,
+    ) -> Vec<CosmicMappedRenderElement<R>>
+    where
+        R: Renderer + ImportAll + ImportMem + AsGlowRenderer,
+        <R as Renderer>::TextureId: 'static,
+        CosmicMappedRenderElement<R>: RenderElement<R>,
+    {
+        let slots = self.expose_layout(output);
+        let theme = self.theme.clone();
+        let Some(expose) = self.expose.as_mut() else {
+            return Vec::new();
+        };
+
+        expose.titles.retain(|window, _| window.alive());
+
+        let scale = output.current_scale().fractional_scale();
+        let active_window_hint = crate::theme::active_window_hint(theme.cosmic());
+        let dragged = expose
+            .drag
+            .as_ref()
+            .filter(|drag| drag.moved)
+            .map(|drag| drag.window.clone());
+
+        let mut elements = Vec::new();
+        if let Some(drag) = expose.drag.as_ref().filter(|drag| drag.moved) {
+            if output.geometry().to_f64().contains(drag.location) {
+                let location = (drag.location - drag.offset)
+                    .to_local(output)
+                    .to_i32_round();
+                elements.extend(
+                    drag.window
+                        .scaled_render_elements::<R, CosmicMappedRenderElement<R>>(
+                            renderer,
+                            location.as_logical().to_physical_precise_round(scale),
+                            scale.into(),
+                            drag.factor,
+                            0.8,
+                        ),
+                );
+            }
+        }
+
+        let mut titles = Vec::new();
+        let mut indicators = Vec::new();
+        let mut windows = Vec::new();
+        for slot in slots {
+            if dragged.as_ref() == Some(&slot.window) {
+                continue;
+            }
+
+            let rect = interpolate(slot.geometry, slot.slot, progress);
+            let factor = rect.size.w as f64 / slot.geometry.size.w.max(1) as f64;
+            let alpha = if expose.matches(&slot.window) {
+                1.0
+            } else {
+                1.0 - 0.6 * progress
+            };
+            windows.extend(
+                slot.window
+                    .scaled_render_elements::<R, CosmicMappedRenderElement<R>>(
+                        renderer,
+                        rect.loc.as_logical().to_physical_precise_round(scale),
+                        scale.into(),
+                        factor,
+                        alpha,
+                    ),
+            );
+
+            if expose.selected.as_ref() == Some(&slot.window) {
+                indicators.push(CosmicMappedRenderElement::from(
+                    IndicatorShader::focus_element(
+                        renderer,
+                        Key::Window(Usage::FocusIndicator, slot.window.clone()),
+                        rect,
+                        4,
+                        scale,
+                        progress,
+                        [
+                            active_window_hint.red,
+                            active_window_hint.green,
+                            active_window_hint.blue,
+                        ],
+                    ),
+                ));
+            }
+
+            let title = slot.window.active_window().title();
+            let element = expose.titles.entry(slot.window.clone()).or_insert_with(|| {
+                IcedElement::new(
+                    ExposeTitle {
+                        title: title.clone(),
+                    },
+                    Size::default(),
+                    evlh.clone(),
+                    theme.clone(),
+                )
+            });
+            if element.with_program(|program| program.title != title) {
+                *element = IcedElement::new(
+                    ExposeTitle { title },
+                    Size::default(),
+                    evlh.clone(),
+                    theme.clone(),
+                );
+            }
+            element.resize(Size::from((slot.slot.size.w.max(1), TITLE_HEIGHT)));
+            element.output_enter(output, element.bbox());
+            let location = Point::<i32, Local>::from((
+                slot.slot.loc.x,
+                slot.slot.loc.y + slot.slot.size.h + GAP / 4,
+            ));
+            titles.extend(element.render_elements::<CosmicMappedRenderElement<R>>(
+                renderer,
+                location.as_logical().to_physical_precise_round(scale),
+                scale.into(),
+                progress,
+            ));
+        }
+
+        elements.extend(titles);
+        elements.extend(indicators);
+        elements.extend(windows);
+        elements
+    }
+}
+
+/// Places windows of the given sizes into a grid inside `area`, shrinking them as little as possible
+fn grid(sizes: &[Size<i32, Local>], area: Rectangle<i32, Local>) -> Vec<Rectangle<i32, Local>> {
+    let count = sizes.len();
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let rows = |columns: usize| (count + columns - 1) / columns;
+    let cell = |columns: usize| -> Size<i32, Local> {
+        let rows = rows(columns) as i32;
+        let columns = columns as i32;
+        Size::from((
+            (area.size.w - GAP * (columns + 1)) / columns,
+            (area.size.h - GAP * (rows + 1)) / rows - TITLE_HEIGHT,
+        ))
+    };
+    let fit = |size: &Size<i32, Local>, cell: Size<i32, Local>| -> f64 {
+        (cell.w as f64 / size.w.max(1) as f64)
+            .min(cell.h as f64 / size.h.max(1) as f64)
+            .clamp(0.0, 1.0)
+    };
+
+    // the amount of columns, that leaves the windows the most space
+    let columns = (1..=count)
+        .max_by_key(|columns| {
+            let cell = cell(*columns);
+            sizes
+                .iter()
+                .map(|size| {
+                    let factor = fit(size, cell);
+                    (size.w as f64 * factor * size.h as f64 * factor) as i64
+                })
+                .sum::<i64>()
+        })
+        .unwrap();
+    let rows = rows(columns);
+    let cell = cell(columns);
+    let grid_height = rows as i32 * (cell.h + TITLE_HEIGHT + GAP) - GAP;
+
+    sizes
+        .iter()
+        .enumerate()
+        .map(|(idx, size)| {
+            let (row, column) = (idx / columns, idx % columns);
+            // center the last row, if it isn't full
+            let in_row = (count - row * columns).min(columns);
+            let row_offset = (columns - in_row) as i32 * (cell.w + GAP) / 2;
+
+            let size = size.to_f64().upscale(fit(size, cell)).to_i32_round();
+            Rectangle::from_loc_and_size(
+                (
+                    area.loc.x
+                        + GAP
+                        + row_offset
+                        + column as i32 * (cell.w + GAP)
+                        + (cell.w - size.w) / 2,
+                    area.loc.y
+                        + (area.size.h - grid_height) / 2
+                        + row as i32 * (cell.h + TITLE_HEIGHT + GAP)
+                        + (cell.h - size.h) / 2,
+                ),
+                size,
+            )
+        })
+        .collect()
+}
+
+fn interpolate(
+    from: Rectangle<i32, Local>,
+    to: Rectangle<i32, Local>,
+    progress: f32,
+) -> Rectangle<i32, Local> {
+    let lerp = |from: i32, to: i32| (from as f32 + (to - from) as f32 * progress).round() as i32;
+    Rectangle::from_loc_and_size(
+        (lerp(from.loc.x, to.loc.x), lerp(from.loc.y, to.loc.y)),
+        (lerp(from.size.w, to.size.w), lerp(from.size.h, to.size.h)),
+    )
+}
+
+/// Focuses `window` once the current input event is handled
+fn focus_window(state: &State, seat: &Seat<State>, window: CosmicMapped) {
+    let seat = seat.clone();
+    state.common.event_loop_handle.insert_idle(move |state| {
+        if let Some(output) = state
+            .common
+            .shell
+            .space_for(&window)
+            .map(|workspace| workspace.output.clone())
+        {
+            seat.set_active_output(&output);
+        }
+        Common::set_focus(state, Some(&window.into()), &seat, None);
+    });
+}
+
+/// Title shown below a window in the overview
+#[derive(Debug)]
+pub struct ExposeTitle {
+    title: String,
+}
+
+impl Program for ExposeTitle {
+    type Message = ();
+
+    fn view(&self) -> crate::utils::iced::Element<'_, Self::Message> {
+        text(&self.title)
+            .size(14)
+            .horizontal_alignment(Horizontal::Center)
+            .apply(container)
+            .padding([4, 12])
+            .style(theme::Container::custom(|theme| {
+                let component = &theme.cosmic().background.component;
+                container::Appearance {
+                    icon_color: None,
+                    text_color: Some(component.on.into()),
+                    background: Some(Background::Color(component.base.into())),
+                    border_radius: 14.0.into(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                }
+            }))
+            .apply(container)
+            .center_x()
+            .width(Length::Fill)
+            .into()
+    }
+}
+
+/// Clicks focus a window, dragging moves it onto the workspace of another output
+pub struct ExposePointerGrab {
+    seat: Seat<State>,
+    start_data: PointerGrabStartData<State>,
+}
+
+impl ExposePointerGrab {
+    pub fn new(seat: &Seat<State>, start_data: PointerGrabStartData<State>) -> Self {
+        ExposePointerGrab {
+            seat: seat.clone(),
+            start_data,
+        }
+    }
+}
+
+impl PointerGrab<State> for ExposePointerGrab {
+    fn motion(
+        &mut self,
+        state: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        _focus: Option<(PointerFocusTarget, Point<i32, Logical>)>,
+        event: &MotionEvent,
+    ) {
+        state.common.shell.expose_motion(event.location.as_global());
+        // While the grab is active, no client has pointer focus
+        handle.motion(state, None, event);
+    }
+
+    fn relative_motion(
+        &mut self,
+        state: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        _focus: Option<(PointerFocusTarget, Point<i32, Logical>)>,
+        event: &RelativeMotionEvent,
+    ) {
+        handle.relative_motion(state, None, event);
+    }
+
+    fn button(
+        &mut self,
+        state: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &ButtonEvent,
+    ) {
+        if event.button != self.start_data.button {
+            return;
+        }
+
+        let location = handle.current_location().as_global();
+        match event.state {
+            ButtonState::Pressed => {
+                if !state.common.shell.expose_press(location) {
+                    state.common.shell.close_expose();
+                    self.seat.get_keyboard().unwrap().unset_grab();
+                    handle.unset_grab(state, event.serial, event.time, true);
+                }
+            }
+            ButtonState::Released => match state.common.shell.expose_release(location) {
+                Some(ExposeRelease::Click(window)) => {
+                    state.common.shell.close_expose();
+                    focus_window(state, &self.seat, window);
+                    self.seat.get_keyboard().unwrap().unset_grab();
+                    handle.unset_grab(state, event.serial, event.time, false);
+                }
+                Some(ExposeRelease::Drop(window, output)) => {
+                    let from = state
+                        .common
+                        .shell
+                        .space_for(&window)
+                        .map(|workspace| workspace.handle);
+                    let to = state.common.shell.active_space(&output).handle;
+                    if let Some(from) = from.filter(|from| from != &to) {
+                        Shell::move_window(
+                            state,
+                            Some(&self.seat),
+                            &window,
+                            &from,
+                            &to,
+                            false,
+                            None,
+                        );
+                    }
+                }
+                None => {}
+            },
+        }
+    }
+
+    fn axis(
+        &mut self,
+        state: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        details: AxisFrame,
+    ) {
+        handle.axis(state, details);
+    }
+
+    fn frame(&mut self, data: &mut State, handle: &mut PointerInnerHandle<'_, State>) {
+        handle.frame(data)
+    }
+
+    fn gesture_swipe_begin(
+        &mut self,
+        data: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &GestureSwipeBeginEvent,
+    ) {
+        handle.gesture_swipe_begin(data, event)
+    }
+
+    fn gesture_swipe_update(
+        &mut self,
+        data: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &GestureSwipeUpdateEvent,
+    ) {
+        handle.gesture_swipe_update(data, event)
+    }
+
+    fn gesture_swipe_end(
+        &mut self,
+        data: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &GestureSwipeEndEvent,
+    ) {
+        handle.gesture_swipe_end(data, event)
+    }
+
+    fn gesture_pinch_begin(
+        &mut self,
+        data: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &GesturePinchBeginEvent,
+    ) {
+        handle.gesture_pinch_begin(data, event)
+    }
+
+    fn gesture_pinch_update(
+        &mut self,
+        data: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &GesturePinchUpdateEvent,
+    ) {
+        handle.gesture_pinch_update(data, event)
+    }
+
+    fn gesture_pinch_end(
+        &mut self,
+        data: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &GesturePinchEndEvent,
+    ) {
+        handle.gesture_pinch_end(data, event)
+    }
+
+    fn gesture_hold_begin(
+        &mut self,
+        data: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &GestureHoldBeginEvent,
+    ) {
+        handle.gesture_hold_begin(data, event)
+    }
+
+    fn gesture_hold_end(
+        &mut self,
+        data: &mut State,
+        handle: &mut PointerInnerHandle<'_, State>,
+        event: &GestureHoldEndEvent,
+    ) {
+        handle.gesture_hold_end(data, event)
+    }
+
+    fn start_data(&self) -> &PointerGrabStartData<State> {
+        &self.start_data
+    }
+}
+
+/// Typing filters the windows, arrow keys select and enter focuses a window
+pub struct ExposeKeyboardGrab {
+    seat: Seat<State>,
+}
+
+impl ExposeKeyboardGrab {
+    pub fn new(seat: &Seat<State>) -> Self {
+        ExposeKeyboardGrab { seat: seat.clone() }
+    }
+}
+
+impl KeyboardGrab<State> for ExposeKeyboardGrab {
+    fn input(
+        &mut self,
+        data: &mut State,
+        handle: &mut KeyboardInnerHandle<'_, State>,
+        keycode: u32,
+        state: KeyState,
+        _modifiers: Option<ModifiersState>,
+        serial: Serial,
+        time: u32,
+    ) {
+        if state != KeyState::Pressed {
+            return;
+        }
+
+        let keysym = handle.keysym_handle(keycode).modified_sym();
+        match keysym {
+            Keysym::Return | Keysym::KP_Enter => {
+                let selected = data.common.shell.expose_selected();
+                data.common.shell.close_expose();
+                if let Some(window) = selected {
+                    focus_window(data, &self.seat, window);
+                }
+                self.seat
+                    .get_pointer()
+                    .unwrap()
+                    .unset_grab(data, serial, time);
+                handle.unset_grab(data, serial, false);
+            }
+            Keysym::Left => data.common.shell.expose_select_direction(Direction::Left),
+            Keysym::Right => data.common.shell.expose_select_direction(Direction::Right),
+            Keysym::Up => data.common.shell.expose_select_direction(Direction::Up),
+            Keysym::Down => data.common.shell.expose_select_direction(Direction::Down),
+            Keysym::BackSpace => data.common.shell.expose_filter_pop(),
+            _ => {
+                if let Some(c) = keysym.key_char().filter(|c| !c.is_control()) {
+                    data.common.shell.expose_filter_push(c);
+                }
+            }
+        }
+    }
+
+    fn set_focus(
+        &mut self,
+        data: &mut State,
+        handle: &mut KeyboardInnerHandle<'_, State>,
+        focus: Option<<State as SeatHandler>::KeyboardFocus>,
+        serial: Serial,
+    ) {
+        handle.set_focus(data, focus, serial)
+    }
+
+    fn start_data(&self) -> &KeyboardGrabStartData<State> {
+        &KeyboardGrabStartData { focus: None }
+    }
+}
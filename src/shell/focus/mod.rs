@@ -4,18 +4,21 @@ use crate::{
     utils::prelude::*,
     wayland::handlers::xdg_shell::PopupGrabData,
 };
+use cosmic_comp_config::LayoutMemory;
 use indexmap::IndexSet;
 use smithay::{
     desktop::{layer_map_for_output, PopupUngrabStrategy},
-    input::Seat,
+    input::{keyboard::Layout, pointer::MotionEvent, Seat},
     output::Output,
+    reexports::wayland_server::protocol::wl_surface::WlSurface,
     utils::{IsAlive, Serial, SERIAL_COUNTER},
     wayland::{
+        compositor::with_states,
         seat::WaylandFocus,
         shell::wlr_layer::{KeyboardInteractivity, Layer},
     },
 };
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use tracing::{debug, trace};
 
 use self::target::{KeyboardFocusTarget, WindowGroup};
@@ -24,6 +27,18 @@ use super::{layout::floating::FloatingLayout, CosmicSurface};
 
 pub mod target;
 
+/// Keyboard layout last active in a toplevel, see [`LayoutMemory::PerWindow`]
+#[derive(Debug, Default)]
+struct WindowLayout(Cell<u32>);
+
+/// The surface a keyboard layout is remembered for, when focusing `target`
+fn layout_surface(target: &KeyboardFocusTarget) -> Option<WlSurface> {
+    match target {
+        KeyboardFocusTarget::Fullscreen(surface) => surface.wl_surface(),
+        target => target.toplevel(),
+    }
+}
+
 #[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum FocusDirection {
     Left,
@@ -71,6 +86,26 @@ impl<'a> FocusStackMut<'a> {
     }
 }
 
+/// Window focused by hovering it, which isn't raised until focused otherwise
+#[derive(Default)]
+struct HoverFocus(RefCell<Option<CosmicMapped>>);
+
+impl HoverFocus {
+    fn set(seat: &Seat<State>, mapped: Option<CosmicMapped>) {
+        *seat
+            .user_data()
+            .get_or_insert(HoverFocus::default)
+            .0
+            .borrow_mut() = mapped;
+    }
+
+    fn get(seat: &Seat<State>) -> Option<CosmicMapped> {
+        seat.user_data()
+            .get::<HoverFocus>()
+            .and_then(|hover| hover.0.borrow().clone())
+    }
+}
+
 pub struct ActiveFocus(RefCell<Option<KeyboardFocusTarget>>);
 
 impl ActiveFocus {
@@ -136,6 +171,7 @@ impl Shell {
                     }
                 }
             }
+            workspace.scrolling_layer.scroll_to_element(&mapped);
         }
     }
 
@@ -145,22 +181,63 @@ impl Shell {
         active_seat: &Seat<State>,
         serial: Option<Serial>,
     ) {
+        HoverFocus::set(active_seat, None);
         Self::append_focus_stack(state, target, active_seat);
 
         // update keyboard focus
         if let Some(keyboard) = active_seat.get_keyboard() {
+            let per_window_layout =
+                state.common.config.keyboard.layout_memory == LayoutMemory::PerWindow;
+            if per_window_layout {
+                if let Some(surface) = keyboard.current_focus().as_ref().and_then(layout_surface) {
+                    let layout = keyboard.with_xkb_state(state, |context| {
+                        context.xkb().lock().unwrap().active_layout()
+                    });
+                    with_states(&surface, |states| {
+                        states.data_map.insert_if_missing(WindowLayout::default);
+                        states
+                            .data_map
+                            .get::<WindowLayout>()
+                            .unwrap()
+                            .0
+                            .set(layout.0);
+                    });
+                }
+            }
+
             ActiveFocus::set(active_seat, target.cloned());
             keyboard.set_focus(
                 state,
                 target.cloned(),
                 serial.unwrap_or_else(|| SERIAL_COUNTER.next_serial()),
             );
+
+            if per_window_layout {
+                if let Some(surface) = target.and_then(layout_surface) {
+                    let layout = with_states(&surface, |states| {
+                        states
+                            .data_map
+                            .get::<WindowLayout>()
+                            .map_or(0, |layout| layout.0.get())
+                    });
+                    keyboard
+                        .with_xkb_state(state, |mut context| context.set_layout(Layout(layout)));
+                }
+            }
         }
     }
 
     fn update_active<'a, 'b>(&mut self, seats: impl Iterator<Item = &'a Seat<State>>) {
+        let seats = seats.collect::<Vec<_>>();
+        // windows focused by hovering keep their stacking order
+        let unraised = seats
+            .iter()
+            .filter_map(|seat| HoverFocus::get(seat))
+            .collect::<Vec<_>>();
+
         // update activate status
         let focused_windows = seats
+            .iter()
             .flat_map(|seat| {
                 if matches!(
                     seat.get_keyboard().unwrap().current_focus(),
@@ -180,7 +257,7 @@ impl Shell {
 
         for output in self.outputs().cloned().collect::<Vec<_>>().into_iter() {
             let set = self.workspaces.sets.get_mut(&output).unwrap();
-            for focused in focused_windows.iter() {
+            for focused in focused_windows.iter().filter(|w| !unraised.contains(w)) {
                 raise_with_children(&mut set.sticky_layer, focused);
             }
             for window in set.sticky_layer.mapped() {
@@ -189,7 +266,7 @@ impl Shell {
             }
 
             let workspace = self.workspaces.active_mut(&output);
-            for focused in focused_windows.iter() {
+            for focused in focused_windows.iter().filter(|w| !unraised.contains(w)) {
                 raise_with_children(&mut workspace.floating_layer, focused);
             }
             for window in workspace.mapped() {
@@ -235,6 +312,81 @@ impl Common {
         state.common.shell.update_active(seats.iter());
     }
 
+    /// Focuses a window the pointer entered, without raising it
+    pub fn set_hover_focus(state: &mut State, mapped: &CosmicMapped, active_seat: &Seat<State>) {
+        Shell::set_focus(state, Some(&mapped.clone().into()), active_seat, None);
+        HoverFocus::set(active_seat, Some(mapped.clone()));
+        let seats = state.common.seats().cloned().collect::<Vec<_>>();
+        state.common.shell.update_active(seats.iter());
+    }
+
+    /// Moves the pointer to the last focused window of the active workspace of `output`,
+    /// if the cursor should follow the focus
+    pub fn warp_cursor_to_workspace(state: &mut State, seat: &Seat<State>, output: &Output) {
+        let workspace = state.common.shell.active_space(output);
+        if let Some(mapped) = workspace.focus_stack.get(seat).last().cloned() {
+            Common::warp_cursor_to(state, seat, &mapped);
+        }
+    }
+
+    /// Moves the pointer to the center of `mapped`, if the cursor should follow the focus
+    pub fn warp_cursor_to(state: &mut State, seat: &Seat<State>, mapped: &CosmicMapped) {
+        if !state.common.config.focus.cursor_follows_focus {
+            return;
+        }
+        let Some(ptr) = seat.get_pointer() else {
+            return;
+        };
+
+        let shell = &state.common.shell;
+        let geometry = shell
+            .space_for(mapped)
+            .and_then(|workspace| {
+                Some(
+                    workspace
+                        .element_geometry(mapped)?
+                        .to_global(&workspace.output),
+                )
+            })
+            .or_else(|| {
+                shell.workspaces.sets.iter().find_map(|(output, set)| {
+                    Some(set.sticky_layer.element_geometry(mapped)?.to_global(output))
+                })
+            });
+        let Some(geometry) = geometry else {
+            return;
+        };
+        // leave the pointer alone, if it already is on the window
+        if geometry
+            .to_f64()
+            .contains(ptr.current_location().as_global())
+        {
+            return;
+        }
+
+        let location = geometry.loc.to_f64() + geometry.size.to_f64().downscale(2.0).to_point();
+        let Some(output) = shell
+            .outputs()
+            .find(|output| output.geometry().to_f64().contains(location))
+            .cloned()
+        else {
+            return;
+        };
+        seat.set_active_output(&output);
+        let under = State::surface_under(location, &output, &mut state.common.shell)
+            .map(|(target, pos)| (target, pos.as_logical()));
+        ptr.motion(
+            state,
+            under,
+            &MotionEvent {
+                location: location.as_logical(),
+                serial: SERIAL_COUNTER.next_serial(),
+                time: 0,
+            },
+        );
+        ptr.frame(state);
+    }
+
     pub fn refresh_focus(state: &mut State) {
         let seats = state.common.seats().cloned().collect::<Vec<_>>();
         for seat in seats {
@@ -121,10 +121,7 @@ pub fn tab_items(
                     {
                         workspace.unmaximize_request(&mapped);
                     }
-                    let focus_stack = workspace.focus_stack.get(&seat);
-                    workspace
-                        .tiling_layer
-                        .map(mapped, Some(focus_stack.iter()), None, false);
+                    workspace.map_tiled(mapped, Some(&seat), None, false);
                 } else {
                     workspace.floating_layer.map(mapped, None)
                 }
@@ -153,6 +150,7 @@ pub fn window_items(
     possible_resizes: ResizeEdge,
     config: &StaticConfig,
 ) -> impl Iterator<Item = Item> {
+    let minimize_clone = window.clone();
     let maximize_clone = window.clone();
     let tile_clone = window.clone();
     let move_prev_clone = window.clone();
@@ -186,7 +184,14 @@ pub fn window_items(
             .shortcut(config.get_shortcut_for_action(&Action::ToggleStacking)),
         ),
         Some(Item::Separator),
-        //Some(Item::new(fl!("window-menu-minimize"), |handle| {})),
+        Some(
+            Item::new(fl!("window-menu-minimize"), move |handle| {
+                let mapped = minimize_clone.clone();
+                let _ =
+                    handle.insert_idle(move |state| state.common.shell.minimize_request(&mapped));
+            })
+            .shortcut(config.get_shortcut_for_action(&Action::Minimize)),
+        ),
         Some(
             Item::new(fl!("window-menu-maximize"), move |handle| {
                 let mapped = maximize_clone.clone();
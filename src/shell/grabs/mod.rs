@@ -27,6 +27,8 @@ mod menu;
 pub use self::menu::*;
 mod moving;
 pub use self::moving::*;
+mod switcher;
+pub use self::switcher::*;
 
 bitflags::bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
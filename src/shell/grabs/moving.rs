@@ -27,7 +27,7 @@ use smithay::{
             ImportAll, ImportMem, Renderer,
         },
     },
-    desktop::space::SpaceElement,
+    desktop::{layer_map_for_output, space::SpaceElement},
     input::{
         pointer::{
             AxisFrame, ButtonEvent, GestureHoldBeginEvent, GestureHoldEndEvent,
@@ -63,6 +63,8 @@ pub struct MoveGrabState {
     start: Instant,
     previous: ManagedLayer,
     stacking_indicator: Option<(StackHover, Point<i32, Logical>)>,
+    /// Offset pulling a floating window onto a nearby edge, see [`snap_offset`]
+    snap: Point<i32, Logical>,
 }
 
 impl MoveGrabState {
@@ -100,7 +102,7 @@ impl MoveGrabState {
         let cursor_at = seat.get_pointer().unwrap().current_location();
 
         let mut window_geo = self.window.geometry();
-        window_geo.loc += cursor_at.to_i32_round() + self.window_offset;
+        window_geo.loc += cursor_at.to_i32_round() + self.window_offset + self.snap;
         if !output
             .geometry()
             .as_logical()
@@ -115,6 +117,7 @@ impl MoveGrabState {
             self.window_offset - self.window_offset.to_f64().upscale(scale).to_i32_round();
         let render_location = cursor_at.to_i32_round() - output.geometry().loc.as_logical()
             + self.window_offset
+            + self.snap
             - scaling_offset;
 
         let active_window_hint = crate::theme::active_window_hint(theme);
@@ -226,6 +229,8 @@ pub struct MoveGrab {
     cursor_output: Output,
     window_outputs: HashSet<Output>,
     previous: ManagedLayer,
+    /// Window was dragged out of a scrolling layout and is dropped back into one
+    scrolled: bool,
     release: ReleaseMode,
     // SAFETY: This is only used on drop which will always be on the main thread
     evlh: NotSend<LoopHandle<'static, State>>,
@@ -275,6 +280,12 @@ impl PointerGrab<State> for MoveGrab {
         if let Some(grab_state) = borrow.as_mut().and_then(|s| s.as_mut()) {
             let mut window_geo = self.window.geometry();
             window_geo.loc += event.location.to_i32_round() + grab_state.window_offset;
+            grab_state.snap = if self.previous != ManagedLayer::Tiling && !self.snap_disabled() {
+                self.snap(state, window_geo)
+            } else {
+                Point::default()
+            };
+            window_geo.loc += grab_state.snap;
             for output in state.common.shell.outputs() {
                 if let Some(overlap) = output.geometry().as_logical().intersection(window_geo) {
                     if self.window_outputs.insert(output.clone()) {
@@ -462,6 +473,7 @@ impl MoveGrab {
         initial_window_location: Point<i32, Global>,
         indicator_thickness: u8,
         previous_layer: ManagedLayer,
+        scrolled: bool,
         release: ReleaseMode,
         evlh: LoopHandle<'static, State>,
     ) -> MoveGrab {
@@ -479,6 +491,7 @@ impl MoveGrab {
             start: Instant::now(),
             stacking_indicator: None,
             previous: previous_layer,
+            snap: Point::default(),
         };
 
         *seat
@@ -499,6 +512,7 @@ impl MoveGrab {
             window_outputs: outputs,
             cursor_output: output,
             previous: previous_layer,
+            scrolled,
             release,
             evlh: NotSend(evlh),
         }
@@ -507,6 +521,118 @@ impl MoveGrab {
     pub fn is_tiling_grab(&self) -> bool {
         self.previous == ManagedLayer::Tiling
     }
+
+    /// Holding shift moves the window freely
+    fn snap_disabled(&self) -> bool {
+        self.seat
+            .get_keyboard()
+            .is_some_and(|keyboard| keyboard.modifier_state().shift)
+    }
+
+    fn snap(&self, state: &State, geometry: Rectangle<i32, Logical>) -> Point<i32, Logical> {
+        let config = &state.common.config.floating;
+        let shell = &state.common.shell;
+
+        let mut areas = Vec::new();
+        let mut windows = Vec::new();
+        for output in shell.outputs() {
+            areas.push(output.geometry().as_logical());
+            areas.push(
+                layer_map_for_output(output)
+                    .non_exclusive_zone()
+                    .as_local()
+                    .to_global(output)
+                    .as_logical(),
+            );
+
+            let workspace = shell.active_space(output);
+            let sticky_layer = &shell.workspaces.sets[output].sticky_layer;
+            for layer in [&workspace.floating_layer, sticky_layer] {
+                windows.extend(
+                    layer
+                        .mapped()
+                        .filter(|mapped| *mapped != &self.window)
+                        .filter_map(|mapped| layer.element_geometry(mapped))
+                        .map(|geometry| geometry.to_global(output).as_logical()),
+                );
+            }
+        }
+
+        let grid = (config.snap_grid > 0).then(|| {
+            (
+                self.cursor_output.geometry().loc.as_logical(),
+                config.snap_grid as i32,
+            )
+        });
+        snap_offset(
+            geometry,
+            &areas,
+            &windows,
+            config.snap_threshold as i32,
+            grid,
+        )
+    }
+}
+
+/// Offset moving `geometry` onto the closest edge of `areas` or `windows`, that is within `threshold`.
+///
+/// Edges of `windows` only count, if they are next to `geometry`. On axes without a close edge,
+/// the location of `geometry` is rounded to the grid given by its origin and size instead.
+pub fn snap_offset(
+    geometry: Rectangle<i32, Logical>,
+    areas: &[Rectangle<i32, Logical>],
+    windows: &[Rectangle<i32, Logical>],
+    threshold: i32,
+    grid: Option<(Point<i32, Logical>, i32)>,
+) -> Point<i32, Logical> {
+    let closest = |start: i32, end: i32, lines: Vec<i32>| {
+        lines
+            .into_iter()
+            .flat_map(|line| [line - start, line - end])
+            .filter(|delta| delta.abs() <= threshold)
+            .min_by_key(|delta| delta.abs())
+    };
+    let overlaps = |start: i32, end: i32, other_start: i32, other_end: i32| {
+        start < other_end + threshold && other_start < end + threshold
+    };
+    let to_grid = |loc: i32, origin: i32, size: i32| {
+        let offset = (loc - origin).rem_euclid(size);
+        if offset * 2 < size {
+            -offset
+        } else {
+            size - offset
+        }
+    };
+
+    let (left, right) = (geometry.loc.x, geometry.loc.x + geometry.size.w);
+    let (top, bottom) = (geometry.loc.y, geometry.loc.y + geometry.size.h);
+
+    let x_lines = areas
+        .iter()
+        .chain(
+            windows
+                .iter()
+                .filter(|other| overlaps(top, bottom, other.loc.y, other.loc.y + other.size.h)),
+        )
+        .flat_map(|rect| [rect.loc.x, rect.loc.x + rect.size.w])
+        .collect();
+    let y_lines = areas
+        .iter()
+        .chain(
+            windows
+                .iter()
+                .filter(|other| overlaps(left, right, other.loc.x, other.loc.x + other.size.w)),
+        )
+        .flat_map(|rect| [rect.loc.y, rect.loc.y + rect.size.h])
+        .collect();
+
+    let x = closest(left, right, x_lines)
+        .or_else(|| grid.map(|(origin, size)| to_grid(left, origin.x, size)))
+        .unwrap_or(0);
+    let y = closest(top, bottom, y_lines)
+        .or_else(|| grid.map(|(origin, size)| to_grid(top, origin.y, size)))
+        .unwrap_or(0);
+    (x, y).into()
 }
 
 impl Drop for MoveGrab {
@@ -516,6 +642,7 @@ impl Drop for MoveGrab {
         let seat = self.seat.clone();
         let window_outputs = self.window_outputs.drain().collect::<HashSet<_>>();
         let previous = self.previous;
+        let scrolled = self.scrolled;
         let window = self.window.clone();
 
         let _ = self.evlh.0.insert_idle(move |state| {
@@ -528,7 +655,8 @@ impl Drop for MoveGrab {
             {
                 if grab_state.window.alive() {
                     let window_location = (pointer.current_location().to_i32_round()
-                        + grab_state.window_offset)
+                        + grab_state.window_offset
+                        + grab_state.snap)
                         .as_global();
 
                     let workspace_handle = state.common.shell.active_space(&output).handle;
@@ -551,6 +679,19 @@ impl Drop for MoveGrab {
                     }
 
                     match previous {
+                        ManagedLayer::Floating
+                            if scrolled
+                                && state.common.shell.active_space(&output).scrolling_enabled =>
+                        {
+                            let cursor = pointer.current_location().to_i32_round().as_global();
+                            let (window, location) = state
+                                .common
+                                .shell
+                                .active_space_mut(&output)
+                                .scrolling_layer
+                                .drop_window(grab_state.window, cursor.to_local(&output));
+                            Some((window, location.to_global(&output)))
+                        }
                         ManagedLayer::Tiling => {
                             let (window, location) = state
                                 .common
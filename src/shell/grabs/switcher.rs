@@ -0,0 +1,526 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use calloop::LoopHandle;
+use cosmic::{
+    iced::widget::{column, container, row, vertical_space},
+    iced_core::{alignment::Horizontal, Background, Color, Length},
+    theme,
+    widget::text,
+    Apply,
+};
+use smithay::{
+    backend::{
+        allocator::Fourcc,
+        renderer::{
+            damage::{Error as DTError, OutputDamageTracker},
+            element::{
+                texture::{TextureBuffer, TextureRenderElement},
+                AsRenderElements, Kind, RenderElement,
+            },
+            gles::GlesTexture,
+            glow::GlowRenderer,
+            Bind, ImportAll, ImportMem, Offscreen, Renderer,
+        },
+    },
+    desktop::space::SpaceElement,
+    input::Seat,
+    output::Output,
+    reexports::wayland_server::protocol::wl_surface::WlSurface,
+    utils::{IsAlive, Logical, Physical, Point, Rectangle, Scale, Size, Transform},
+    wayland::compositor::SurfaceData,
+};
+use tracing::warn;
+
+use crate::{
+    backend::render::element::AsGlowRenderer,
+    config::KeyModifiers,
+    shell::{element::CosmicMappedRenderElement, CosmicMapped, Shell},
+    state::{Common, State},
+    utils::{
+        iced::{IcedElement, Program},
+        prelude::*,
+    },
+};
+
+const THUMBNAIL_SIZE: (i32, i32) = (240, 160);
+const MIN_THUMBNAIL_WIDTH: i32 = 96;
+const TITLE_LENGTH: usize = 28;
+const PADDING: u16 = 16;
+const CARD_PADDING: u16 = 8;
+const SPACING: u16 = 8;
+
+/// Open window switcher of a seat, cycling through windows by how recently they were focused
+pub struct WindowSwitcherState {
+    windows: Vec<CosmicMapped>,
+    /// Offscreen renders of the windows, drawing them directly would duplicate their elements
+    thumbnails: RefCell<Vec<Option<Thumbnail>>>,
+    element: IcedElement<WindowSwitcher>,
+    output: Output,
+    position: Point<i32, Global>,
+    /// Height of a card including its title, cards wrap into rows of [`WindowSwitcher::per_row`]
+    card_height: i32,
+    modifiers: KeyModifiers,
+}
+
+struct Thumbnail {
+    texture: GlesTexture,
+    buffer: TextureBuffer<GlesTexture>,
+    damage_tracker: OutputDamageTracker,
+    size: Size<i32, Physical>,
+    age: usize,
+}
+pub type SeatWindowSwitcherState = RefCell<Option<WindowSwitcherState>>;
+
+impl WindowSwitcherState {
+    /// Opens the switcher on the active output of `seat`, it stays open until one of `modifiers` is released.
+    ///
+    /// Returns `None`, if there are no windows to switch between.
+    pub fn new(
+        shell: &Shell,
+        seat: &Seat<State>,
+        modifiers: KeyModifiers,
+        loop_handle: LoopHandle<'static, State>,
+        theme: cosmic::Theme,
+    ) -> Option<WindowSwitcherState> {
+        let windows = switcher_windows(shell, seat);
+        if windows.is_empty() {
+            return None;
+        }
+
+        let output = seat.active_output();
+        let output_geometry = output.geometry();
+        let count = windows.len() as i32;
+        let max_width = output_geometry.size.w * 9 / 10 - 2 * PADDING as i32;
+        // as many cards as fit at their minimum size, the rest wraps into further rows
+        let per_row = ((max_width + SPACING as i32)
+            / (MIN_THUMBNAIL_WIDTH + 2 * CARD_PADDING as i32 + SPACING as i32))
+            .clamp(1, count);
+        let rows = (count + per_row - 1) / per_row;
+        let available = max_width - (per_row - 1) * SPACING as i32;
+        let width = (available / per_row - 2 * CARD_PADDING as i32)
+            .clamp(MIN_THUMBNAIL_WIDTH, THUMBNAIL_SIZE.0);
+        let thumbnail_size = Size::from((width, width * THUMBNAIL_SIZE.1 / THUMBNAIL_SIZE.0));
+
+        let element = IcedElement::new(
+            WindowSwitcher {
+                titles: windows.iter().map(title).collect(),
+                selected: AtomicUsize::new(0),
+                thumbnail_size,
+                per_row: per_row as usize,
+            },
+            Size::default(),
+            loop_handle,
+            theme,
+        );
+        let size = element.minimum_size();
+        element.resize(size);
+        element.output_enter(&output, element.bbox());
+        let card_height = (size.h - 2 * PADDING as i32 - (rows - 1) * SPACING as i32) / rows;
+
+        let position = output_geometry.loc
+            + Point::from((
+                (output_geometry.size.w - size.w) / 2,
+                (output_geometry.size.h - size.h) / 2,
+            ));
+
+        Some(WindowSwitcherState {
+            thumbnails: RefCell::new(windows.iter().map(|_| None).collect()),
+            windows,
+            element,
+            output,
+            position,
+            card_height,
+            modifiers,
+        })
+    }
+
+    /// Modifiers of the binding, that opened the switcher
+    pub fn modifiers(&self) -> &KeyModifiers {
+        &self.modifiers
+    }
+
+    /// Selects the next or previous window, wrapping around at the ends
+    pub fn cycle(&self, forward: bool) {
+        let len = self.windows.len();
+        self.element.with_program(|switcher| {
+            let selected = switcher.selected.load(Ordering::SeqCst);
+            let selected = if forward {
+                (selected + 1) % len
+            } else {
+                (selected + len - 1) % len
+            };
+            switcher.selected.store(selected, Ordering::SeqCst);
+        });
+        self.element.force_redraw();
+    }
+
+    pub fn selected(&self) -> Option<&CosmicMapped> {
+        let selected = self
+            .element
+            .with_program(|switcher| switcher.selected.load(Ordering::SeqCst));
+        self.windows.get(selected).filter(|mapped| mapped.alive())
+    }
+
+    /// Closes the switcher and focuses the selected window, switching to its workspace if necessary
+    pub fn activate(self, state: &mut State, seat: &Seat<State>) {
+        let Some(mapped) = self.selected().cloned() else {
+            return;
+        };
+
+        let workspace = state
+            .common
+            .shell
+            .outputs()
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .find_map(|output| {
+                let idx = state
+                    .common
+                    .shell
+                    .workspaces
+                    .spaces_for_output(&output)
+                    .position(|w| w.mapped().any(|m| m == &mapped))?;
+                Some((output, idx))
+            });
+        if let Some((output, idx)) = workspace {
+            let _ = state.common.shell.activate(&output, idx);
+        }
+
+        Common::set_focus(state, Some(&mapped.clone().into()), seat, None);
+        Common::warp_cursor_to(state, seat, &mapped);
+    }
+
+    pub fn render<I, R>(&self, renderer: &mut R, output: &Output) -> Vec<I>
+    where
+        R: Renderer + ImportAll + ImportMem + AsGlowRenderer,
+        <R as Renderer>::TextureId: 'static,
+        CosmicMappedRenderElement<R>: RenderElement<R>,
+        I: From<CosmicMappedRenderElement<R>>,
+    {
+        if output != &self.output {
+            return Vec::new();
+        }
+
+        let scale = output.current_scale().fractional_scale();
+        let position = self.position.to_local(output);
+        let (thumbnail_size, per_row) = self
+            .element
+            .with_program(|switcher| (switcher.thumbnail_size, switcher.per_row));
+        let mut thumbnails = self.thumbnails.borrow_mut();
+
+        let mut elements = Vec::new();
+        for (idx, (mapped, thumbnail)) in self.windows.iter().zip(thumbnails.iter_mut()).enumerate()
+        {
+            if !mapped.alive() {
+                continue;
+            }
+
+            let (column, row) = ((idx % per_row) as i32, (idx / per_row) as i32);
+            let slot = Rectangle::<i32, Local>::from_loc_and_size(
+                position
+                    + Point::from((
+                        (PADDING + CARD_PADDING) as i32
+                            + column
+                                * (thumbnail_size.w + 2 * CARD_PADDING as i32 + SPACING as i32),
+                        (PADDING + CARD_PADDING) as i32 + row * (self.card_height + SPACING as i32),
+                    )),
+                thumbnail_size.as_local(),
+            );
+            let geometry = mapped.geometry();
+            if geometry.size.w <= 0 || geometry.size.h <= 0 {
+                continue;
+            }
+            let factor = (slot.size.w as f64 / geometry.size.w as f64)
+                .min(slot.size.h as f64 / geometry.size.h as f64)
+                .min(1.0);
+            let size = geometry.size.to_f64().upscale(factor).to_i32_round();
+            let location =
+                slot.loc + Point::from(((slot.size.w - size.w) / 2, (slot.size.h - size.h) / 2));
+
+            let Some(buffer) = render_thumbnail(
+                renderer.glow_renderer_mut(),
+                thumbnail,
+                mapped,
+                size.to_f64().to_physical(scale).to_i32_round(),
+                scale.into(),
+                factor,
+            ) else {
+                continue;
+            };
+            elements.push(CosmicMappedRenderElement::Thumbnail(
+                TextureRenderElement::from_texture_buffer(
+                    location.as_logical().to_f64().to_physical(scale),
+                    &buffer,
+                    None,
+                    None,
+                    Some(size),
+                    Kind::Unspecified,
+                ),
+            ));
+        }
+
+        elements.extend(
+            self.element
+                .render_elements::<CosmicMappedRenderElement<R>>(
+                    renderer,
+                    position.as_logical().to_physical_precise_round(scale),
+                    scale.into(),
+                    1.0,
+                ),
+        );
+        elements.into_iter().map(I::from).collect()
+    }
+
+    /// Keeps the thumbnails of windows on hidden workspaces updated
+    pub fn send_frames(
+        &self,
+        output: &Output,
+        time: impl Into<Duration>,
+        throttle: Option<Duration>,
+        primary_scan_out_output: impl FnMut(&WlSurface, &SurfaceData) -> Option<Output> + Copy,
+    ) {
+        if output != &self.output {
+            return;
+        }
+
+        let time = time.into();
+        for mapped in &self.windows {
+            mapped
+                .active_window()
+                .send_frame(output, time, throttle, primary_scan_out_output);
+        }
+    }
+}
+
+/// Renders `mapped` into the offscreen texture of its thumbnail, only redrawing damaged regions.
+///
+/// Returns the buffer to draw the thumbnail with, its id only changes with its contents.
+fn render_thumbnail(
+    renderer: &mut GlowRenderer,
+    thumbnail: &mut Option<Thumbnail>,
+    mapped: &CosmicMapped,
+    size: Size<i32, Physical>,
+    scale: Scale<f64>,
+    factor: f64,
+) -> Option<TextureBuffer<GlesTexture>> {
+    if thumbnail
+        .as_ref()
+        .map_or(true, |thumbnail| thumbnail.size != size)
+    {
+        let texture = match Offscreen::<GlesTexture>::create_buffer(
+            renderer,
+            Fourcc::Abgr8888,
+            (size.w, size.h).into(),
+        ) {
+            Ok(texture) => texture,
+            Err(err) => {
+                warn!(?err, "Failed to allocate window thumbnail");
+                return None;
+            }
+        };
+        *thumbnail = Some(Thumbnail {
+            buffer: TextureBuffer::from_texture(
+                renderer,
+                texture.clone(),
+                1,
+                Transform::Normal,
+                None,
+            ),
+            texture,
+            damage_tracker: OutputDamageTracker::new(size, scale, Transform::Normal),
+            size,
+            age: 0,
+        });
+    }
+    let thumbnail = thumbnail.as_mut().unwrap();
+
+    if let Err(err) = renderer.bind(thumbnail.texture.clone()) {
+        warn!(?err, "Failed to bind window thumbnail");
+        return None;
+    }
+    let elements = mapped
+        .scaled_render_elements::<GlowRenderer, CosmicMappedRenderElement<GlowRenderer>>(
+            renderer,
+            Point::default(),
+            scale,
+            factor,
+            1.0,
+        );
+    match thumbnail.damage_tracker.render_output(
+        renderer,
+        thumbnail.age,
+        &elements,
+        [0.0, 0.0, 0.0, 0.0],
+    ) {
+        Ok(result) => {
+            // the same texture is drawn into every time
+            thumbnail.age = 1;
+            if result.damage.is_some() {
+                thumbnail.buffer = TextureBuffer::from_texture(
+                    renderer,
+                    thumbnail.texture.clone(),
+                    1,
+                    Transform::Normal,
+                    None,
+                );
+            }
+        }
+        Err(DTError::Rendering(err)) => warn!(?err, "Failed to render window thumbnail"),
+        Err(DTError::OutputNoMode(_)) => unreachable!(),
+    }
+    Some(thumbnail.buffer.clone())
+}
+
+/// Windows of all workspaces, ordered by how recently `seat` focused them.
+///
+/// The focus stack of the active workspace comes first, windows never focused last.
+fn switcher_windows(shell: &Shell, seat: &Seat<State>) -> Vec<CosmicMapped> {
+    let active = shell.active_space(&seat.active_output());
+    let workspaces = std::iter::once(active)
+        .chain(
+            shell
+                .workspaces
+                .spaces()
+                .filter(|w| w.handle != active.handle),
+        )
+        .collect::<Vec<_>>();
+
+    let mut windows = Vec::new();
+    let focused = workspaces.iter().flat_map(|workspace| {
+        workspace
+            .focus_stack
+            .get(seat)
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+    let unfocused = workspaces
+        .iter()
+        .flat_map(|workspace| workspace.mapped())
+        .chain(
+            shell
+                .workspaces
+                .iter()
+                .flat_map(|(_, set)| set.sticky_layer.mapped()),
+        )
+        .cloned();
+    for mapped in focused.chain(unfocused) {
+        if !windows.contains(&mapped) {
+            windows.push(mapped);
+        }
+    }
+    windows
+}
+
+fn title(mapped: &CosmicMapped) -> String {
+    let window = mapped.active_window();
+    let title = window.title();
+    let title = if title.is_empty() {
+        window.app_id()
+    } else {
+        title
+    };
+    if title.chars().count() > TITLE_LENGTH {
+        title
+            .chars()
+            .take(TITLE_LENGTH - 1)
+            .chain(Some('…'))
+            .collect()
+    } else {
+        title
+    }
+}
+
+pub struct WindowSwitcher {
+    titles: Vec<String>,
+    selected: AtomicUsize,
+    thumbnail_size: Size<i32, Logical>,
+    /// Cards per row
+    per_row: usize,
+}
+
+impl Program for WindowSwitcher {
+    type Message = ();
+
+    fn view(&self) -> crate::utils::iced::Element<'_, Self::Message> {
+        let selected = self.selected.load(Ordering::SeqCst);
+        let width = Length::Fixed(self.thumbnail_size.w as f32);
+
+        let card = |idx: usize, title: &String| {
+            column(vec![
+                // the thumbnail is rendered below this space
+                vertical_space(Length::Fixed(self.thumbnail_size.h as f32))
+                    .apply(container)
+                    .width(width)
+                    .into(),
+                text(title)
+                    .size(14)
+                    .width(width)
+                    .horizontal_alignment(Horizontal::Center)
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .apply(container)
+            .padding(CARD_PADDING)
+            .style(if idx == selected {
+                theme::Container::custom(|theme| container::Appearance {
+                    icon_color: None,
+                    text_color: Some(Color::from(theme.cosmic().accent.on)),
+                    background: Some(Background::Color(theme.cosmic().accent_color().into())),
+                    border_radius: 8.0.into(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                })
+            } else {
+                theme::Container::custom(|_| container::Appearance {
+                    icon_color: None,
+                    text_color: None,
+                    background: None,
+                    border_radius: 8.0.into(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                })
+            })
+            .into()
+        };
+
+        column(
+            self.titles
+                .chunks(self.per_row)
+                .enumerate()
+                .map(|(row_idx, titles)| {
+                    row(titles
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, title)| card(row_idx * self.per_row + idx, title))
+                        .collect())
+                    .spacing(SPACING)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(SPACING)
+        .apply(container)
+        .padding(PADDING)
+        .style(theme::Container::custom(|theme| {
+            let cosmic = theme.cosmic();
+            let component = &cosmic.background.component;
+            container::Appearance {
+                icon_color: Some(cosmic.accent.base.into()),
+                text_color: Some(component.on.into()),
+                background: Some(Background::Color(component.base.into())),
+                border_radius: 16.0.into(),
+                border_width: 1.0,
+                border_color: component.divider.into(),
+            }
+        }))
+        .into()
+    }
+}
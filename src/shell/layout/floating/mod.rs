@@ -54,7 +54,7 @@ pub struct FloatingLayout {
     pub theme: cosmic::Theme,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize)]
 pub enum TiledCorners {
     Top,
     TopRight,
@@ -814,6 +814,134 @@ impl FloatingLayout {
         }
     }
 
+    /// Moves `mapped` by `delta`, as long as it stays on the output
+    pub fn move_element(&mut self, mapped: &CosmicMapped, delta: Point<i32, Local>) -> bool {
+        if mapped.is_maximized(false) {
+            return false;
+        }
+        let Some(mut geometry) = self.element_geometry(mapped) else {
+            return false;
+        };
+        geometry.loc += delta;
+
+        let output = self.space.outputs().next().unwrap();
+        if !output.geometry().to_local(output).overlaps(geometry) {
+            return false;
+        }
+        self.place_element(mapped, geometry);
+        true
+    }
+
+    /// Grows or shrinks `mapped` by `delta`, keeping its top left corner in place
+    pub fn resize_element(&mut self, mapped: &CosmicMapped, delta: (i32, i32)) -> bool {
+        if mapped.is_maximized(false) {
+            return false;
+        }
+        let Some(mut geometry) = self.element_geometry(mapped) else {
+            return false;
+        };
+
+        let min_size = mapped.min_size().unwrap_or_default();
+        let max_size = mapped.max_size().unwrap_or_default();
+        // a maximum of 0 leaves the dimension unbounded
+        let clamp = |size: i32, min: i32, max: i32| {
+            size.max(min.max(1))
+                .min(if max > 0 { max } else { i32::max_value() })
+        };
+        geometry.size.w = clamp(geometry.size.w + delta.0, min_size.w, max_size.w);
+        geometry.size.h = clamp(geometry.size.h + delta.1, min_size.h, max_size.h);
+
+        self.place_element(mapped, geometry);
+        true
+    }
+
+    /// Centers `mapped` in the non-exclusive zone, with the size it had before being tiled
+    pub fn center_element(&mut self, mapped: &CosmicMapped) -> bool {
+        if mapped.is_maximized(false) {
+            return false;
+        }
+        let Some(geometry) = self.element_geometry(mapped) else {
+            return false;
+        };
+        let tiled = mapped.floating_tiled.lock().unwrap().is_some();
+        let size = if !tiled {
+            *mapped.last_geometry.lock().unwrap() = Some(geometry);
+            geometry.size
+        } else {
+            mapped
+                .last_geometry
+                .lock()
+                .unwrap()
+                .map(|geo| geo.size)
+                .unwrap_or(geometry.size)
+        };
+
+        let output = self.space.outputs().next().unwrap();
+        let zone = layer_map_for_output(output).non_exclusive_zone().as_local();
+        let loc = zone.loc
+            + Point::from((
+                (zone.size.w - size.w).max(0) / 2,
+                (zone.size.h - size.h).max(0) / 2,
+            ));
+        self.place_element(mapped, Rectangle::from_loc_and_size(loc, size));
+        true
+    }
+
+    /// Tiles `mapped` into the given half or quarter of the output
+    pub fn tile_element(&mut self, mapped: &CosmicMapped, corner: TiledCorners) -> bool {
+        if mapped.is_maximized(false) {
+            return false;
+        }
+        let Some(geometry) = self.element_geometry(mapped) else {
+            return false;
+        };
+        let mut tiled_state = mapped.floating_tiled.lock().unwrap();
+        if tiled_state.is_none() {
+            *mapped.last_geometry.lock().unwrap() = Some(geometry);
+        }
+        *tiled_state = Some(corner);
+        std::mem::drop(tiled_state);
+
+        let output = self.space.outputs().next().unwrap().clone();
+        let zone = layer_map_for_output(&output).non_exclusive_zone();
+        let new_geo = corner.relative_geometry(zone);
+
+        self.tiling_animations
+            .insert(mapped.clone(), (Instant::now(), geometry));
+        mapped.set_tiled(true);
+        mapped.moved_since_mapped.store(true, Ordering::SeqCst);
+        self.map_internal(
+            mapped.clone(),
+            Some(new_geo.loc),
+            Some(new_geo.size.as_logical()),
+        );
+        true
+    }
+
+    /// Puts `mapped` back to where it was before it was tiled or centered
+    pub fn restore_element(&mut self, mapped: &CosmicMapped) -> bool {
+        if mapped.is_maximized(false) || !self.space.elements().any(|m| m == mapped) {
+            return false;
+        }
+        let Some(geometry) = *mapped.last_geometry.lock().unwrap() else {
+            return false;
+        };
+        self.place_element(mapped, geometry);
+        true
+    }
+
+    /// Places `mapped` freely at `geometry`, leaving any tiled state
+    fn place_element(&mut self, mapped: &CosmicMapped, geometry: Rectangle<i32, Local>) {
+        *mapped.floating_tiled.lock().unwrap() = None;
+        self.tiling_animations.remove(mapped);
+        mapped.moved_since_mapped.store(true, Ordering::SeqCst);
+        self.map_internal(
+            mapped.clone(),
+            Some(geometry.loc),
+            Some(geometry.size.as_logical()),
+        );
+    }
+
     pub fn mapped(&self) -> impl Iterator<Item = &CosmicMapped> {
         self.space.elements().rev()
     }
@@ -1,6 +1,5 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use regex::RegexSet;
 use smithay::{
     wayland::{compositor::with_states, shell::xdg::XdgToplevelSurfaceData},
     xwayland::xwm::WmWindowType,
@@ -9,9 +8,11 @@ use smithay::{
 use super::CosmicSurface;
 
 pub mod floating;
+pub mod saved;
+pub mod scrolling;
 pub mod tiling;
 
-#[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Orientation {
     Horizontal,
     Vertical,
@@ -27,75 +28,18 @@ impl std::ops::Not for Orientation {
     }
 }
 
-lazy_static::lazy_static! {
-    static ref EXCEPTIONS_APPID: RegexSet = RegexSet::new(&[
-        r"Authy Desktop",
-        r"Com.github.amezin.ddterm",
-        r"Com.github.donadigo.eddy",
-        r".*",
-        r"Enpass",
-        r"Gjs",
-        r"Gnome-initial-setup",
-        r"Gnome-terminal",
-        r"Guake",
-        r"Io.elementary.sideload",
-        r"KotatogramDesktop",
-        r"Mozilla VPN",
-        r"update-manager",
-        r"Solaar",
-        r"Steam",
-        r"",
-        r"TelegramDesktop",
-        r"Zotero",
-        r"gjs",
-        r"gnome-screenshot",
-        r"ibus-.*",
-        r"jetbrains-toolbox",
-        r"jetbrains-webstorm",
-        r"jetbrains-webstorm",
-        r"jetbrains-webstorm",
-        r"krunner",
-        r"pritunl",
-        r"re.sonny.Junction",
-        r"system76-driver",
-        r"tilda",
-        r"zoom",
-        r"^.*?action=join.*$",
-    ]).unwrap();
-    static ref EXCEPTIONS_TITLE: RegexSet = RegexSet::new(&[
-        r".*",
-        r".*",
-        r".*",
-        r"Discord Updater",
-        r"Enpass Assistant",
-        r"Settings",
-        r".*",
-        r"Preferences – General",
-        r".*",
-        r".*",
-        r"Media viewer",
-        r".*",
-        r"Software Updater",
-        r".*",
-        r"^.*?(Guard|Login).*",
-        r"Steam",
-        r"Media viewer",
-        r"Quick Format Citation",
-        r".*",
-        r".*",
-        r".*",
-        r".*",
-        r"Customize WebStorm",
-        r"License Activation",
-        r"Welcome to WebStorm",
-        r".*",
-        r".*",
-        r".*",
-        r".*",
-        r".*",
-        r".*",
-        r".*",
-    ]).unwrap();
+/// Automatic arrangement of the tiled windows of a workspace
+#[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TilingMode {
+    /// Windows are split manually next to the focused window
+    #[default]
+    Manual,
+    /// `masters` windows on the left taking `ratio` percent of the width, the rest stacked on the right
+    MasterStack { masters: u32, ratio: u8 },
+    /// Every window takes half of the remaining space, alternating the split direction
+    Dwindle,
+    /// Equally sized columns
+    Columns,
 }
 
 pub fn should_be_floating(window: &CosmicSurface) -> bool {
@@ -132,18 +76,5 @@ pub fn should_be_floating(window: &CosmicSurface) -> bool {
     let max_size = window.max_size();
     let min_size = window.min_size();
 
-    if min_size.is_some() && min_size == max_size {
-        return true;
-    }
-
-    // else take a look at our exceptions
-    let appid_matches = EXCEPTIONS_APPID.matches(&window.app_id());
-    let title_matches = EXCEPTIONS_TITLE.matches(&window.title());
-    for idx in appid_matches.into_iter() {
-        if title_matches.matched(idx) {
-            return true;
-        }
-    }
-
-    false
+    min_size.is_some() && min_size == max_size
 }
@@ -0,0 +1,293 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use smithay::utils::{IsAlive, Rectangle};
+
+use super::Orientation;
+use crate::{shell::CosmicSurface, utils::prelude::*};
+
+/// Identifies a window across compositor restarts
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WindowKey {
+    pub app_id: String,
+    pub title: String,
+}
+
+impl WindowKey {
+    pub fn new(window: &CosmicSurface) -> WindowKey {
+        WindowKey {
+            app_id: window.app_id(),
+            title: window.title(),
+        }
+    }
+}
+
+/// Node of a saved tiling tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SavedNode {
+    /// A single window or the windows of a stack
+    Windows(Vec<WindowKey>),
+    Group {
+        orientation: Orientation,
+        /// Share of each child of the groups length
+        ratios: Vec<f64>,
+        children: Vec<SavedNode>,
+    },
+}
+
+impl SavedNode {
+    /// All windows of the tree in pre-order
+    pub fn keys(&self) -> Vec<&WindowKey> {
+        match self {
+            SavedNode::Windows(keys) => keys.iter().collect(),
+            SavedNode::Group { children, .. } => children.iter().flat_map(Self::keys).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedWorkspace {
+    /// Connector of the output the workspace was shown on
+    pub output: String,
+    /// Position of the workspace on that output
+    pub idx: usize,
+    pub tiling: Option<SavedNode>,
+    /// Floating windows with their geometry relative to the output as `(x, y, w, h)`
+    pub floating: Vec<(WindowKey, (i32, i32, i32, i32))>,
+}
+
+/// Window layouts of all workspaces, persisted next to the output configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedLayouts {
+    pub workspaces: Vec<SavedWorkspace>,
+}
+
+/// Place of a window of the previous session
+#[derive(Debug, Clone, PartialEq)]
+pub enum SavedSlot {
+    Tiled {
+        output: String,
+        idx: usize,
+    },
+    Floating {
+        output: String,
+        idx: usize,
+        geometry: Rectangle<i32, Local>,
+    },
+}
+
+impl SavedSlot {
+    /// Output connector and position of the workspace the window was on
+    pub fn workspace(&self) -> (&str, usize) {
+        match self {
+            SavedSlot::Tiled { output, idx } | SavedSlot::Floating { output, idx, .. } => {
+                (output, *idx)
+            }
+        }
+    }
+
+    pub fn is_floating(&self) -> bool {
+        matches!(self, SavedSlot::Floating { .. })
+    }
+}
+
+/// How long after startup windows are placed into the layouts of the previous session
+pub const RESTORE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Layouts of the previous session, restored as their windows are mapped again
+#[derive(Debug)]
+pub struct LayoutRestore {
+    workspaces: Vec<RestoringWorkspace>,
+    started: Instant,
+}
+
+#[derive(Debug)]
+struct RestoringWorkspace {
+    saved: SavedWorkspace,
+    /// Windows mapped into the tiling tree so far, in the order of [`SavedNode::keys`]
+    tiled: Vec<Option<CosmicSurface>>,
+    /// Which floating windows were mapped already
+    floating: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Entry {
+    Tiled(usize),
+    Floating(usize),
+}
+
+impl LayoutRestore {
+    pub fn new(saved: SavedLayouts) -> LayoutRestore {
+        LayoutRestore {
+            workspaces: saved
+                .workspaces
+                .into_iter()
+                .map(|saved| RestoringWorkspace {
+                    tiled: vec![
+                        None;
+                        saved
+                            .tiling
+                            .as_ref()
+                            .map(|node| node.keys().len())
+                            .unwrap_or(0)
+                    ],
+                    floating: vec![false; saved.floating.len()],
+                    saved,
+                })
+                .collect(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Whether windows are still placed into the previous session.
+    ///
+    /// This ends once every saved window was mapped again or after [`RESTORE_TIMEOUT`].
+    pub fn is_active(&self) -> bool {
+        self.started.elapsed() < RESTORE_TIMEOUT
+            && self.workspaces.iter().any(|workspace| {
+                workspace
+                    .tiled
+                    .iter()
+                    .any(|surface| !surface.as_ref().is_some_and(|s| s.alive()))
+                    || workspace.floating.iter().any(|claimed| !claimed)
+            })
+    }
+
+    /// Stops restoring, the previous session is discarded
+    pub fn expire(&mut self) {
+        self.workspaces.clear();
+    }
+
+    /// A free slot of a window matching `window`, without claiming it.
+    ///
+    /// Windows with the same app_id and title are preferred, titles often change during a session.
+    pub fn find(&self, window: &CosmicSurface) -> Option<SavedSlot> {
+        self.find_entry(window)
+            .map(|(workspace, entry)| self.slot(workspace, entry))
+    }
+
+    /// Claims the slot returned by [`LayoutRestore::find`], once `window` was actually placed into it
+    pub fn claim(&mut self, window: &CosmicSurface) {
+        match self.find_entry(window) {
+            Some((workspace, Entry::Tiled(i))) => {
+                self.workspaces[workspace].tiled[i] = Some(window.clone())
+            }
+            Some((workspace, Entry::Floating(i))) => self.workspaces[workspace].floating[i] = true,
+            None => {}
+        }
+    }
+
+    fn find_entry(&self, window: &CosmicSurface) -> Option<(usize, Entry)> {
+        if !self.is_active() {
+            return None;
+        }
+
+        let key = WindowKey::new(window);
+        let exact = |other: &WindowKey| other == &key;
+        let app_id = |other: &WindowKey| other.app_id == key.app_id;
+        self.find_matching(exact)
+            .or_else(|| self.find_matching(app_id))
+    }
+
+    fn find_matching(&self, matches: impl Fn(&WindowKey) -> bool) -> Option<(usize, Entry)> {
+        for (idx, workspace) in self.workspaces.iter().enumerate() {
+            if let Some(tiling) = workspace.saved.tiling.as_ref() {
+                let free = tiling.keys().into_iter().enumerate().position(|(i, key)| {
+                    matches(key)
+                        && !workspace.tiled[i]
+                            .as_ref()
+                            .is_some_and(|surface| surface.alive())
+                });
+                if let Some(i) = free {
+                    return Some((idx, Entry::Tiled(i)));
+                }
+            }
+
+            let free = workspace
+                .saved
+                .floating
+                .iter()
+                .zip(workspace.floating.iter())
+                .position(|((key, _), claimed)| !claimed && matches(key));
+            if let Some(i) = free {
+                return Some((idx, Entry::Floating(i)));
+            }
+        }
+
+        None
+    }
+
+    fn slot(&self, workspace: usize, entry: Entry) -> SavedSlot {
+        let saved = &self.workspaces[workspace].saved;
+        let output = saved.output.clone();
+        let idx = saved.idx;
+        match entry {
+            Entry::Tiled(_) => SavedSlot::Tiled { output, idx },
+            Entry::Floating(i) => {
+                let (x, y, w, h) = saved.floating[i].1;
+                SavedSlot::Floating {
+                    output,
+                    idx,
+                    geometry: Rectangle::from_loc_and_size((x, y), (w, h)),
+                }
+            }
+        }
+    }
+
+    /// The saved tiling tree of a workspace and the windows mapped into it so far
+    pub fn tiling(
+        &self,
+        output: &str,
+        idx: usize,
+    ) -> Option<(&SavedNode, &[Option<CosmicSurface>])> {
+        self.workspaces
+            .iter()
+            .find(|w| w.saved.output == output && w.saved.idx == idx)
+            .and_then(|w| Some((w.saved.tiling.as_ref()?, &w.tiled[..])))
+    }
+
+    /// Windows of the saved tiling tree of a workspace, that share a stack with `window`
+    pub fn stack_mates(
+        &self,
+        output: &str,
+        idx: usize,
+        window: &CosmicSurface,
+    ) -> Vec<CosmicSurface> {
+        let Some((tiling, windows)) = self.tiling(output, idx) else {
+            return Vec::new();
+        };
+
+        let mut offset = 0;
+        let mut stacks = Vec::new();
+        collect_stacks(tiling, &mut offset, &mut stacks);
+        stacks
+            .into_iter()
+            .map(|range| &windows[range])
+            .find(|stack| stack.iter().flatten().any(|w| w == window))
+            .map(|stack| {
+                stack
+                    .iter()
+                    .flatten()
+                    .filter(|w| *w != window && w.alive())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn collect_stacks(node: &SavedNode, offset: &mut usize, stacks: &mut Vec<std::ops::Range<usize>>) {
+    match node {
+        SavedNode::Windows(keys) => {
+            stacks.push(*offset..*offset + keys.len());
+            *offset += keys.len();
+        }
+        SavedNode::Group { children, .. } => {
+            for child in children {
+                collect_stacks(child, offset, stacks);
+            }
+        }
+    }
+}
@@ -0,0 +1,606 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::time::{Duration, Instant};
+
+use keyframe::{ease, functions::EaseInOutCubic};
+use smithay::{
+    backend::renderer::{
+        element::{AsRenderElements, RenderElement},
+        ImportAll, ImportMem, Renderer,
+    },
+    desktop::{layer_map_for_output, space::SpaceElement, PopupKind, WindowSurfaceType},
+    input::Seat,
+    output::Output,
+    utils::{IsAlive, Point, Rectangle},
+    wayland::seat::WaylandFocus,
+};
+
+use crate::{
+    backend::render::{element::AsGlowRenderer, IndicatorShader, Key, Usage},
+    shell::{
+        element::{
+            resize_indicator::ResizeIndicator,
+            stack::{CosmicStackRenderElement, MoveResult as StackMoveResult},
+            window::CosmicWindowRenderElement,
+            CosmicMapped, CosmicMappedRenderElement, CosmicWindow,
+        },
+        focus::{
+            target::{KeyboardFocusTarget, PointerFocusTarget},
+            FocusDirection, FocusStackMut,
+        },
+        grabs::ResizeEdge,
+        CosmicSurface, Direction, FocusResult, MoveResult, ResizeDirection, ResizeMode,
+    },
+    state::State,
+    utils::{prelude::*, tween::EaseRectangle},
+    wayland::handlers::xdg_shell::popup::get_popup_toplevel,
+};
+
+pub const ANIMATION_DURATION: Duration = Duration::from_millis(200);
+/// Width of new columns, two of them fill the output
+const DEFAULT_COLUMN_RATIO: f64 = 0.5;
+const MIN_COLUMN_WIDTH: i32 = 240;
+
+#[derive(Debug, Clone)]
+struct Column {
+    mapped: CosmicMapped,
+    width: i32,
+    geometry: Option<Rectangle<i32, Local>>,
+    /// Geometry the column is animated from
+    previous_geometry: Option<Rectangle<i32, Local>>,
+}
+
+/// Windows arranged in a horizontally scrollable strip of full-height columns.
+///
+/// Only the viewport into the strip is visible, it follows the keyboard focus.
+#[derive(Debug, Clone)]
+pub struct ScrollingLayout {
+    output: Output,
+    columns: Vec<Column>,
+    /// Position of the viewport inside the strip
+    offset: i32,
+    animation_start: Option<Instant>,
+    pub theme: cosmic::Theme,
+}
+
+impl ScrollingLayout {
+    pub fn new(theme: cosmic::Theme, output: &Output) -> ScrollingLayout {
+        ScrollingLayout {
+            output: output.clone(),
+            columns: Vec::new(),
+            offset: 0,
+            animation_start: None,
+            theme,
+        }
+    }
+
+    pub fn set_output(&mut self, output: &Output) {
+        for column in &self.columns {
+            column.mapped.output_leave(&self.output);
+            column.mapped.output_enter(output, column.mapped.bbox());
+        }
+        self.output = output.clone();
+        self.arrange();
+    }
+
+    /// Maps a new column right of the most recently focused one
+    pub fn map<'a>(
+        &mut self,
+        mapped: CosmicMapped,
+        focus_stack: Option<impl Iterator<Item = &'a CosmicMapped> + 'a>,
+    ) {
+        let idx = focus_stack
+            .and_then(|mut focus_stack| {
+                focus_stack.find_map(|focused| {
+                    self.columns
+                        .iter()
+                        .position(|column| &column.mapped == focused)
+                })
+            })
+            .map(|idx| idx + 1)
+            .unwrap_or(self.columns.len());
+        self.insert_column(idx, mapped);
+    }
+
+    fn insert_column(&mut self, idx: usize, mapped: CosmicMapped) {
+        let zone = self.zone();
+        let (_, inner) = self.gaps();
+        mapped.output_enter(&self.output, mapped.bbox());
+        mapped.set_bounds(zone.size.as_logical());
+
+        let width = ((zone.size.w - inner) as f64 * DEFAULT_COLUMN_RATIO).round() as i32;
+        self.columns.insert(
+            idx,
+            Column {
+                mapped,
+                width: width.max(MIN_COLUMN_WIDTH),
+                geometry: None,
+                previous_geometry: None,
+            },
+        );
+        self.scroll_to(idx);
+        self.arrange();
+    }
+
+    pub fn unmap(&mut self, mapped: &CosmicMapped) -> bool {
+        let Some(idx) = self.columns.iter().position(|c| &c.mapped == mapped) else {
+            return false;
+        };
+        self.columns.remove(idx);
+        mapped.output_leave(&self.output);
+        mapped.set_tiled(false);
+        self.arrange();
+        true
+    }
+
+    /// Inserts a dragged window as a new column at the strip position under `location`
+    pub fn drop_window(
+        &mut self,
+        mapped: CosmicMapped,
+        location: Point<i32, Local>,
+    ) -> (CosmicMapped, Point<i32, Local>) {
+        let idx = self
+            .columns
+            .iter()
+            .position(|column| {
+                column
+                    .geometry
+                    .is_some_and(|geo| location.x < geo.loc.x + geo.size.w / 2)
+            })
+            .unwrap_or(self.columns.len());
+        self.insert_column(idx, mapped.clone());
+        let location = self.columns[idx].geometry.unwrap().loc;
+        (mapped, location)
+    }
+
+    pub fn element_geometry(&self, elem: &CosmicMapped) -> Option<Rectangle<i32, Local>> {
+        self.columns
+            .iter()
+            .find(|column| &column.mapped == elem)
+            .and_then(|column| column.geometry)
+    }
+
+    pub fn element_under(
+        &self,
+        location: Point<f64, Local>,
+    ) -> Option<(PointerFocusTarget, Point<i32, Local>)> {
+        self.columns.iter().find_map(|column| {
+            let geometry = column.geometry?;
+            if !geometry.contains(location.to_i32_round()) {
+                return None;
+            }
+            let mapped = &column.mapped;
+            let test_point = (location - geometry.loc.to_f64()
+                + mapped.geometry().loc.to_f64().as_local())
+            .as_logical();
+            mapped.is_in_input_region(&test_point).then(|| {
+                (
+                    mapped.clone().into(),
+                    geometry.loc - mapped.geometry().loc.as_local(),
+                )
+            })
+        })
+    }
+
+    /// Scrolls the viewport until the column of `elem` is fully visible
+    pub fn scroll_to_element(&mut self, elem: &CosmicMapped) {
+        if let Some(idx) = self.columns.iter().position(|c| &c.mapped == elem) {
+            let offset = self.offset;
+            self.scroll_to(idx);
+            if offset != self.offset {
+                self.arrange();
+            }
+        }
+    }
+
+    pub fn next_focus(&mut self, direction: FocusDirection, seat: &Seat<State>) -> FocusResult {
+        let Some(idx) = self.focused_column(seat) else {
+            return FocusResult::None;
+        };
+        let next = match direction {
+            FocusDirection::Left => idx.checked_sub(1),
+            FocusDirection::Right => Some(idx + 1).filter(|idx| *idx < self.columns.len()),
+            _ => None,
+        };
+        let Some(next) = next else {
+            return FocusResult::None;
+        };
+
+        self.scroll_to(next);
+        self.arrange();
+        FocusResult::Some(KeyboardFocusTarget::Element(
+            self.columns[next].mapped.clone(),
+        ))
+    }
+
+    pub fn move_current_element(&mut self, direction: Direction, seat: &Seat<State>) -> MoveResult {
+        let Some(idx) = self.focused_column(seat) else {
+            return MoveResult::None;
+        };
+
+        let focused = self.columns[idx].mapped.clone();
+        match focused.handle_move(direction) {
+            StackMoveResult::Handled => MoveResult::Done,
+            StackMoveResult::MoveOut(surface, loop_handle) => {
+                let mapped: CosmicMapped =
+                    CosmicWindow::new(surface, loop_handle, self.theme.clone()).into();
+                let idx = match direction {
+                    Direction::Left | Direction::Up => idx,
+                    Direction::Right | Direction::Down => idx + 1,
+                };
+                self.insert_column(idx, mapped.clone());
+                MoveResult::ShiftFocus(mapped.into())
+            }
+            StackMoveResult::Default => {
+                let other = match direction {
+                    Direction::Left => idx.checked_sub(1),
+                    Direction::Right => Some(idx + 1).filter(|idx| *idx < self.columns.len()),
+                    Direction::Up | Direction::Down => None,
+                };
+                let Some(other) = other else {
+                    return MoveResult::MoveFurther(focused.into());
+                };
+
+                self.columns.swap(idx, other);
+                self.scroll_to(other);
+                self.arrange();
+                MoveResult::Done
+            }
+        }
+    }
+
+    pub fn toggle_stacking(&mut self, mapped: &CosmicMapped) -> Option<KeyboardFocusTarget> {
+        let idx = self.columns.iter().position(|c| &c.mapped == mapped)?;
+
+        let output = self.output.clone();
+        let theme = self.theme.clone();
+        let column = &mut self.columns[idx];
+        if column.mapped.is_window() {
+            // if it is just a window
+            column
+                .mapped
+                .convert_to_stack((&output, column.mapped.bbox()), theme);
+            let mapped = column.mapped.clone();
+            self.arrange();
+            Some(KeyboardFocusTarget::Element(mapped))
+        } else {
+            // if we have a stack
+            let mut surfaces = column.mapped.windows().map(|(s, _)| s).collect::<Vec<_>>();
+            let first = surfaces.remove(0);
+            let handle = column.mapped.loop_handle();
+            column
+                .mapped
+                .convert_to_surface(first, (&output, column.mapped.bbox()), theme);
+            let mapped = column.mapped.clone();
+
+            // the rest become columns right of it
+            for (i, other) in surfaces.into_iter().enumerate() {
+                other.try_force_undecorated(false);
+                let window = CosmicMapped::from(CosmicWindow::new(
+                    other,
+                    handle.clone(),
+                    self.theme.clone(),
+                ));
+                self.insert_column(idx + 1 + i, window);
+            }
+            self.scroll_to(idx);
+            self.arrange();
+            Some(KeyboardFocusTarget::Element(mapped))
+        }
+    }
+
+    pub fn toggle_stacking_focused(
+        &mut self,
+        seat: &Seat<State>,
+        mut focus_stack: FocusStackMut,
+    ) -> Option<KeyboardFocusTarget> {
+        let idx = self.focused_column(seat)?;
+        let elem = self.columns[idx].mapped.clone();
+        let res = self.toggle_stacking(&elem);
+        focus_stack.append(&elem);
+        res
+    }
+
+    /// Changes the width of the focused column, the height always matches the output
+    pub fn resize(
+        &mut self,
+        focused: &KeyboardFocusTarget,
+        direction: ResizeDirection,
+        edge: ResizeEdge,
+        amount: i32,
+    ) -> bool {
+        let Some(toplevel) = focused.toplevel() else {
+            return false;
+        };
+        let Some(idx) = self.columns.iter().position(|column| {
+            column
+                .mapped
+                .has_surface(&toplevel, WindowSurfaceType::TOPLEVEL)
+        }) else {
+            return false;
+        };
+        if !(edge.contains(ResizeEdge::LEFT) || edge.contains(ResizeEdge::RIGHT)) {
+            return false;
+        }
+
+        let max_width = self.zone().size.w;
+        let column = &mut self.columns[idx];
+        column.width = match direction {
+            ResizeDirection::Outwards => column.width + amount,
+            ResizeDirection::Inwards => column.width - amount,
+        }
+        .clamp(MIN_COLUMN_WIDTH, max_width.max(MIN_COLUMN_WIDTH));
+
+        self.scroll_to(idx);
+        self.arrange();
+        true
+    }
+
+    pub fn recalculate(&mut self) {
+        self.arrange();
+    }
+
+    pub fn refresh(&mut self) {
+        let dead_windows = self
+            .columns
+            .iter()
+            .map(|column| column.mapped.clone())
+            .filter(|mapped| !mapped.alive())
+            .collect::<Vec<_>>();
+        for dead_window in dead_windows.iter() {
+            self.unmap(dead_window);
+        }
+
+        for column in &self.columns {
+            column.mapped.refresh();
+        }
+    }
+
+    pub fn merge(&mut self, other: ScrollingLayout) {
+        for column in other.columns {
+            column.mapped.output_leave(&other.output);
+            self.insert_column(self.columns.len(), column.mapped);
+        }
+    }
+
+    pub fn mapped(&self) -> impl Iterator<Item = &CosmicMapped> {
+        self.columns.iter().map(|column| &column.mapped)
+    }
+
+    pub fn windows(&self) -> impl Iterator<Item = CosmicSurface> + '_ {
+        self.mapped()
+            .flat_map(|mapped| mapped.windows().map(|(w, _)| w))
+    }
+
+    pub fn animations_going(&self) -> bool {
+        self.animation_start.is_some()
+    }
+
+    pub fn update_animation_state(&mut self) {
+        if self
+            .animation_start
+            .is_some_and(|start| Instant::now().duration_since(start) >= ANIMATION_DURATION)
+        {
+            self.animation_start = None;
+            for column in &mut self.columns {
+                column.previous_geometry = None;
+            }
+        }
+    }
+
+    pub fn render<R>(
+        &self,
+        renderer: &mut R,
+        focused: Option<&CosmicMapped>,
+        mut resize_indicator: Option<(ResizeMode, ResizeIndicator)>,
+        indicator_thickness: u8,
+        alpha: f32,
+        theme: &cosmic::theme::CosmicTheme,
+    ) -> (
+        Vec<CosmicMappedRenderElement<R>>,
+        Vec<CosmicMappedRenderElement<R>>,
+    )
+    where
+        R: Renderer + ImportAll + ImportMem + AsGlowRenderer,
+        <R as Renderer>::TextureId: 'static,
+        CosmicMappedRenderElement<R>: RenderElement<R>,
+        CosmicWindowRenderElement<R>: RenderElement<R>,
+        CosmicStackRenderElement<R>: RenderElement<R>,
+    {
+        #[cfg(feature = "debug")]
+        puffin::profile_function!();
+
+        let output_scale = self.output.current_scale().fractional_scale();
+        let output_geometry =
+            Rectangle::from_loc_and_size((0, 0), self.output.geometry().size.as_local());
+
+        let mut window_elements = Vec::new();
+        let mut popup_elements = Vec::new();
+
+        for column in &self.columns {
+            let Some(geometry) = self.render_geometry(column) else {
+                continue;
+            };
+            if !geometry.overlaps(output_geometry) {
+                continue;
+            }
+            let elem = &column.mapped;
+
+            let render_location = geometry.loc - elem.geometry().loc.as_local();
+            let (w_elements, p_elements) = elem.split_render_elements(
+                renderer,
+                render_location
+                    .as_logical()
+                    .to_physical_precise_round(output_scale),
+                output_scale.into(),
+                alpha,
+            );
+
+            if focused == Some(elem) && !elem.is_maximized(false) {
+                if let Some((mode, resize)) = resize_indicator.as_mut() {
+                    let mut resize_geometry = geometry.clone();
+                    resize_geometry.loc -= (18, 18).into();
+                    resize_geometry.size += (36, 36).into();
+
+                    resize.resize(resize_geometry.size.as_logical());
+                    resize.output_enter(&self.output, Rectangle::default() /* unused */);
+                    window_elements.extend(
+                        resize
+                            .render_elements::<CosmicWindowRenderElement<R>>(
+                                renderer,
+                                resize_geometry
+                                    .loc
+                                    .as_logical()
+                                    .to_physical_precise_round(output_scale),
+                                output_scale.into(),
+                                alpha * mode.alpha().unwrap_or(1.0),
+                            )
+                            .into_iter()
+                            .map(CosmicMappedRenderElement::Window),
+                    );
+                }
+
+                let active_window_hint = crate::theme::active_window_hint(theme);
+
+                if indicator_thickness > 0 {
+                    let element = IndicatorShader::focus_element(
+                        renderer,
+                        Key::Window(Usage::FocusIndicator, elem.clone()),
+                        geometry,
+                        indicator_thickness,
+                        output_scale,
+                        alpha,
+                        [
+                            active_window_hint.red,
+                            active_window_hint.green,
+                            active_window_hint.blue,
+                        ],
+                    );
+                    window_elements.push(element.into());
+                }
+            }
+
+            window_elements.extend(w_elements);
+            if !elem.is_maximized(false) {
+                popup_elements.extend(p_elements);
+            }
+        }
+
+        (window_elements, popup_elements)
+    }
+
+    fn focused_column(&self, seat: &Seat<State>) -> Option<usize> {
+        match seat.get_keyboard().unwrap().current_focus()? {
+            KeyboardFocusTarget::Element(mapped) => self
+                .columns
+                .iter()
+                .position(|column| column.mapped == mapped),
+            KeyboardFocusTarget::Popup(popup) => {
+                let toplevel_surface = match popup {
+                    PopupKind::Xdg(xdg) => get_popup_toplevel(&xdg),
+                    PopupKind::InputMethod(_) => unreachable!(),
+                }?;
+                self.columns.iter().position(|column| {
+                    column.mapped.wl_surface().as_ref() == Some(&toplevel_surface)
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Moves the viewport as little as possible to show the column at `idx`
+    fn scroll_to(&mut self, idx: usize) {
+        let (_, inner) = self.gaps();
+        let width = self.zone().size.w;
+        let start: i32 = self.columns[..idx]
+            .iter()
+            .map(|column| column.width + inner)
+            .sum();
+        let end = start + self.columns[idx].width;
+
+        if start < self.offset {
+            self.offset = start;
+        } else if end > self.offset + width {
+            self.offset = end - width;
+        }
+    }
+
+    fn arrange(&mut self) {
+        let zone = self.zone();
+        let (_, inner) = self.gaps();
+        let strip_width = self.columns.iter().map(|column| column.width).sum::<i32>()
+            + inner * (self.columns.len() as i32 - 1).max(0);
+        self.offset = self.offset.min(strip_width - zone.size.w).max(0);
+
+        let current_geometries = self
+            .columns
+            .iter()
+            .map(|column| self.render_geometry(column))
+            .collect::<Vec<_>>();
+        let mut animate = false;
+        let mut x = zone.loc.x - self.offset;
+        for (column, current_geometry) in self.columns.iter_mut().zip(current_geometries) {
+            let geometry =
+                Rectangle::from_loc_and_size((x, zone.loc.y), (column.width, zone.size.h));
+            x += column.width + inner;
+
+            if column.geometry != Some(geometry) {
+                animate |= current_geometry.is_some();
+                column.previous_geometry = current_geometry;
+                column.geometry = Some(geometry);
+            }
+
+            let mapped = &column.mapped;
+            if !(mapped.is_fullscreen(true) || mapped.is_maximized(true)) {
+                mapped.set_tiled(true);
+                mapped.set_geometry(geometry.to_global(&self.output));
+                mapped.configure();
+            }
+        }
+
+        if animate {
+            self.animation_start = Some(Instant::now());
+        }
+    }
+
+    fn render_geometry(&self, column: &Column) -> Option<Rectangle<i32, Local>> {
+        let geometry = column.geometry?;
+        match (column.previous_geometry, self.animation_start) {
+            (Some(previous), Some(start)) => {
+                let percentage = Instant::now()
+                    .duration_since(start)
+                    .min(ANIMATION_DURATION)
+                    .as_millis() as f32
+                    / ANIMATION_DURATION.as_millis() as f32;
+                let mut animated = ease(
+                    EaseInOutCubic,
+                    EaseRectangle(previous),
+                    EaseRectangle(geometry),
+                    percentage,
+                )
+                .0;
+                // only the position is animated, clients draw at their own size
+                animated.size = geometry.size;
+                Some(animated)
+            }
+            _ => Some(geometry),
+        }
+    }
+
+    fn zone(&self) -> Rectangle<i32, Local> {
+        let (outer, _) = self.gaps();
+        let mut zone = layer_map_for_output(&self.output)
+            .non_exclusive_zone()
+            .as_local();
+        zone.loc.x += outer;
+        zone.loc.y += outer;
+        zone.size.w -= outer * 2;
+        zone.size.h -= outer * 2;
+        zone
+    }
+
+    fn gaps(&self) -> (i32, i32) {
+        let g = self.theme.cosmic().gaps;
+        (g.0 as i32, g.1 as i32)
+    }
+}
@@ -12,7 +12,7 @@ use smithay::{
 use xkbcommon::xkb::Keysym;
 
 use crate::{
-    config::{Action, KeyPattern},
+    config::{Action, KeyModifiers, KeyPattern},
     shell::{layout::tiling::NodeDesc, OverviewMode, Trigger},
     state::State,
 };
@@ -49,30 +49,44 @@ impl KeyboardGrab<State> for SwapWindowGrab {
             return;
         }
 
-        let syms = Vec::from(handle.keysym_handle(keycode).raw_syms());
-        let focus_bindings = &data
+        // the tiling mode may change, as long as the swapped node survives rearranging
+        let is_window = data
+            .common
+            .shell
+            .workspaces
+            .space_for_handle(&self.desc.handle)
+            .and_then(|workspace| workspace.tiling_layer.tree().get(&self.desc.node).ok())
+            .is_some_and(|node| !node.data().is_group());
+
+        let keysym_handle = handle.keysym_handle(keycode);
+        let Some(action) = data
             .common
             .config
             .static_conf
             .key_bindings
             .iter()
-            .filter(|(_, action)| matches!(action, Action::Focus(_)))
-            .map(|(pattern, action)| {
-                let Action::Focus(direction) = action else { unreachable!() };
-                (pattern.key, *direction)
+            .find_map(|(pattern, action)| match action {
+                Action::Focus(_) if pattern.matches_key(&keysym_handle) => Some(action.clone()),
+                Action::SetTilingMode(_) if is_window && pattern.matches_key(&keysym_handle) => {
+                    Some(action.clone())
+                }
+                _ => None,
             })
-            .collect::<Vec<_>>();
-        let Some(direction) = syms.iter().find_map(|sym| focus_bindings.iter().find_map(|(key, direction)| (key.is_some() && sym == key.as_ref().unwrap()).then_some(*direction))) else { return };
+        else {
+            return;
+        };
 
         data.handle_action(
-            Action::Focus(direction),
+            action,
             &self.seat,
             serial,
             time,
-            KeyPattern {
-                modifiers: modifiers.map(Into::into).unwrap_or_default(),
-                key: Some(Keysym::new(keycode)),
-            },
+            KeyPattern::new(
+                modifiers
+                    .map(Into::<KeyModifiers>::into)
+                    .unwrap_or_default(),
+                Some(Keysym::new(keycode)),
+            ),
             None,
             true,
         );
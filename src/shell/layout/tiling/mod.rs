@@ -18,9 +18,12 @@ use crate::{
             FocusDirection, FocusStackMut,
         },
         grabs::ResizeEdge,
-        layout::Orientation,
-        CosmicSurface, Direction, FocusResult, MoveResult, OutputNotMapped, OverviewMode,
-        ResizeDirection, ResizeMode, Trigger,
+        layout::{
+            saved::{SavedNode, WindowKey},
+            Orientation, TilingMode,
+        },
+        CosmicSurface, Direction, EqualizeScope, FocusResult, MoveResult, OutputNotMapped,
+        OverviewMode, ResizeDimension, ResizeDirection, ResizeMode, ResizeSize, Trigger,
     },
     theme::group_color,
     utils::{prelude::*, tween::EaseRectangle},
@@ -120,9 +123,23 @@ pub struct TilingLayout {
     placeholder_id: Id,
     swapping_stack_surface_id: Id,
     last_overview_hover: Option<(Option<Instant>, TargetZone)>,
+    mode: TilingMode,
+    preselection: Option<(CosmicMapped, Preselection)>,
+    preselection_id: Id,
     pub theme: cosmic::Theme,
 }
 
+/// Where the next tiled window is placed, relative to a preselected window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preselection {
+    /// Right of the window
+    Horizontal,
+    /// Below the window
+    Vertical,
+    /// As a new tab, turning the window into a stack
+    Stacked,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PillIndicator {
     Outer(Direction),
@@ -165,6 +182,25 @@ impl Data {
         }
     }
 
+    fn new_split(orientation: Orientation, ratios: &[f64]) -> Data {
+        // sizes are relative to this geometry until `update_positions` scales them
+        let length = 1000;
+        let mut sizes = ratios
+            .iter()
+            .map(|ratio| (ratio * length as f64).round() as i32)
+            .collect::<Vec<_>>();
+        let sum: i32 = sizes.iter().sum();
+        *sizes.last_mut().unwrap() += length - sum;
+
+        Data::Group {
+            orientation,
+            sizes,
+            last_geometry: Rectangle::from_loc_and_size((0, 0), (length, length)),
+            alive: Arc::new(()),
+            pill_indicator: None,
+        }
+    }
+
     fn is_group(&self) -> bool {
         matches!(self, Data::Group { .. })
     }
@@ -311,6 +347,82 @@ impl Data {
     }
 }
 
+/// Target structure of a tree arranged by a `TilingMode`
+#[derive(Debug)]
+enum Shape {
+    Leaf(NodeId),
+    Split(Orientation, Vec<(f64, Shape)>),
+}
+
+impl Shape {
+    fn leaves(&self, leaves: &mut Vec<NodeId>) {
+        match self {
+            Shape::Leaf(id) => leaves.push(id.clone()),
+            Shape::Split(_, children) => {
+                for (_, child) in children {
+                    child.leaves(leaves);
+                }
+            }
+        }
+    }
+
+    fn new(mode: TilingMode, mut leaves: Vec<NodeId>, landscape: bool) -> Shape {
+        let equal = |orientation: Orientation, leaves: Vec<NodeId>| {
+            let ratio = 1.0 / leaves.len() as f64;
+            match leaves.len() {
+                1 => Shape::Leaf(leaves.into_iter().next().unwrap()),
+                _ => Shape::Split(
+                    orientation,
+                    leaves
+                        .into_iter()
+                        .map(|id| (ratio, Shape::Leaf(id)))
+                        .collect(),
+                ),
+            }
+        };
+
+        match mode {
+            TilingMode::Manual => unreachable!("Manual trees aren't arranged"),
+            TilingMode::Columns => equal(Orientation::Vertical, leaves),
+            TilingMode::MasterStack { masters, ratio } => {
+                let masters = (masters as usize).min(leaves.len());
+                if masters == 0 || masters == leaves.len() {
+                    return equal(Orientation::Horizontal, leaves);
+                }
+                let ratio = ratio.clamp(10, 90) as f64 / 100.0;
+                let stack = leaves.split_off(masters);
+                Shape::Split(
+                    Orientation::Vertical,
+                    vec![
+                        (ratio, equal(Orientation::Horizontal, leaves)),
+                        (1.0 - ratio, equal(Orientation::Horizontal, stack)),
+                    ],
+                )
+            }
+            TilingMode::Dwindle => {
+                let orientation = if landscape {
+                    Orientation::Vertical
+                } else {
+                    Orientation::Horizontal
+                };
+                let mut shape = Shape::Leaf(leaves.pop().unwrap());
+                // build from the innermost split outwards
+                let splits = leaves.len();
+                for (i, id) in leaves.into_iter().rev().enumerate() {
+                    let depth = splits - 1 - i;
+                    let orientation = if depth % 2 == 0 {
+                        orientation
+                    } else {
+                        !orientation
+                    };
+                    shape = Shape::Split(orientation, vec![(0.5, Shape::Leaf(id)), (0.5, shape)]);
+                }
+                shape
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum FocusedNodeData {
     Group(Vec<NodeId>, Weak<()>),
@@ -333,6 +445,9 @@ impl TilingLayout {
             placeholder_id: Id::new(),
             swapping_stack_surface_id: Id::new(),
             last_overview_hover: None,
+            mode: TilingMode::default(),
+            preselection: None,
+            preselection_id: Id::new(),
             theme,
         }
     }
@@ -384,25 +499,106 @@ impl TilingLayout {
         let gaps = self.gaps();
 
         let mut tree = self.queue.trees.back().unwrap().0.copy_clone();
-        let last_active = focus_stack
-            .and_then(|focus_stack| TilingLayout::last_active_window(&mut tree, focus_stack));
+        let mut add_to_stack = add_to_stack;
+        let mut orientation = None;
+        let preselected = self
+            .preselection
+            .take()
+            .filter(|_| direction.is_none())
+            .and_then(|(target, preselection)| {
+                let (node_id, _) =
+                    TilingLayout::last_active_window(&tree, std::iter::once(&target))?;
+                let Data::Mapped { mapped, .. } = tree.get_mut(&node_id).unwrap().data_mut() else {
+                    unreachable!()
+                };
+                match preselection {
+                    Preselection::Horizontal => orientation = Some(Orientation::Vertical),
+                    Preselection::Vertical => orientation = Some(Orientation::Horizontal),
+                    Preselection::Stacked => {
+                        if mapped.is_window() {
+                            mapped.convert_to_stack(
+                                (&self.output, mapped.bbox()),
+                                self.theme.clone(),
+                            );
+                        }
+                        add_to_stack = true;
+                    }
+                }
+                Some((node_id, mapped.clone()))
+            });
+        let last_active = preselected.or_else(|| {
+            focus_stack
+                .and_then(|focus_stack| TilingLayout::last_active_window(&mut tree, focus_stack))
+        });
         TilingLayout::map_to_tree(
             &mut tree,
             window,
             &self.output,
             last_active,
+            orientation,
             direction,
             add_to_stack,
         );
+        let leaves = TilingLayout::leaves(&tree);
+        TilingLayout::arrange(&mut tree, self.mode, &self.output, leaves);
         let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
         self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
     }
 
+    /// Adds `window` to the stack containing one of `stack_mates`, turning a lone window into a stack.
+    ///
+    /// Returns the stack, if any of `stack_mates` is tiled here.
+    pub fn map_to_stack(
+        &mut self,
+        window: CosmicMapped,
+        stack_mates: &[CosmicSurface],
+    ) -> Option<CosmicMapped> {
+        let find_stack = |this: &Self| {
+            this.mapped()
+                .map(|(_, mapped, _)| mapped.clone())
+                .find(|mapped| mapped.windows().any(|(w, _)| stack_mates.contains(&w)))
+        };
+        let mut stack = find_stack(self)?;
+        if stack.is_window() {
+            self.toggle_stacking(&stack)?;
+            // converting replaces the element in the tree
+            stack = find_stack(self)?;
+        }
+
+        self.map(window, Some(std::iter::once(&stack)), None, true);
+        Some(stack)
+    }
+
+    /// Preselects where the next window is mapped, relative to the focused window.
+    ///
+    /// Preselecting the same split again cancels it.
+    pub fn preselect(&mut self, seat: &Seat<State>, preselection: Preselection) {
+        if self.mode != TilingMode::Manual {
+            return;
+        }
+        let Some(target) = seat.get_keyboard().unwrap().current_focus() else {
+            return;
+        };
+
+        let tree = &self.queue.trees.back().unwrap().0;
+        if let Some((_, FocusedNodeData::Window(mapped))) =
+            TilingLayout::currently_focused_node(tree, target)
+        {
+            let current = (mapped, preselection);
+            if self.preselection.as_ref() == Some(&current) {
+                self.preselection = None;
+            } else {
+                self.preselection = Some(current);
+            }
+        }
+    }
+
     fn map_to_tree<'a>(
         mut tree: &mut Tree<Data>,
         window: impl Into<CosmicMapped>,
         output: &Output,
         node: Option<(NodeId, CosmicMapped)>,
+        orientation: Option<Orientation>,
         direction: Option<Direction>,
         add_to_stack: bool,
     ) {
@@ -444,14 +640,14 @@ impl TilingLayout {
                     return;
                 }
 
-                let orientation = {
+                let orientation = orientation.unwrap_or_else(|| {
                     let window_size = tree.get(node_id).unwrap().data().geometry().size;
                     if window_size.w > window_size.h {
                         Orientation::Vertical
                     } else {
                         Orientation::Horizontal
                     }
-                };
+                });
                 let new_id = tree.insert(new_window, InsertBehavior::AsRoot).unwrap();
                 TilingLayout::new_group(&mut tree, &node_id, &new_id, orientation).unwrap();
                 new_id
@@ -1137,6 +1333,13 @@ impl TilingLayout {
     }
 
     pub fn unmap(&mut self, window: &CosmicMapped) -> bool {
+        if self
+            .preselection
+            .as_ref()
+            .is_some_and(|(target, _)| target == window)
+        {
+            self.preselection = None;
+        }
         if self.unmap_window_internal(window) {
             window.output_leave(&self.output);
             window.set_tiled(false);
@@ -1187,6 +1390,8 @@ impl TilingLayout {
                 let mut tree = self.queue.trees.back().unwrap().0.copy_clone();
 
                 TilingLayout::unmap_internal(&mut tree, &node_id);
+                let leaves = TilingLayout::leaves(&tree);
+                TilingLayout::arrange(&mut tree, self.mode, &self.output, leaves);
 
                 let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
                 self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
@@ -1311,6 +1516,8 @@ impl TilingLayout {
                     )
                     .unwrap();
                     *mapped.tiling_node_id.lock().unwrap() = Some(new_id);
+                    let leaves = TilingLayout::leaves(&tree);
+                    TilingLayout::arrange(&mut tree, self.mode, &self.output, leaves);
 
                     let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
                     self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
@@ -1320,6 +1527,28 @@ impl TilingLayout {
             }
         }
 
+        // arranged trees only change the order of their windows
+        if self.mode != TilingMode::Manual {
+            let FocusedNodeData::Window(window) = data else {
+                return MoveResult::None;
+            };
+            let mut leaves = TilingLayout::leaves(&tree);
+            let idx = leaves.iter().position(|id| id == &node_id).unwrap();
+            let other_idx = match direction {
+                Direction::Left | Direction::Up => idx.checked_sub(1),
+                Direction::Right | Direction::Down => Some(idx + 1).filter(|i| *i < leaves.len()),
+            };
+            let Some(other_idx) = other_idx else {
+                return MoveResult::MoveFurther(window.into());
+            };
+            leaves.swap(idx, other_idx);
+            TilingLayout::arrange(&mut tree, self.mode, &self.output, leaves);
+
+            let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
+            self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
+            return MoveResult::Done;
+        }
+
         let mut child_id = node_id.clone();
         // Without a parent to start with, just return
         let Some(og_parent) = tree.get(&node_id).unwrap().parent().cloned() else {
@@ -1862,6 +2091,153 @@ impl TilingLayout {
         FocusResult::None
     }
 
+    pub fn mode(&self) -> TilingMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: TilingMode) {
+        if self.mode == mode {
+            return;
+        }
+        self.mode = mode;
+
+        let gaps = self.gaps();
+        let mut tree = self.queue.trees.back().unwrap().0.copy_clone();
+        let leaves = TilingLayout::leaves(&tree);
+        TilingLayout::arrange(&mut tree, self.mode, &self.output, leaves);
+        let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
+        self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
+    }
+
+    /// Shape of the tree with its windows identified by app_id and title
+    pub fn save(&self) -> Option<SavedNode> {
+        let tree = &self.queue.trees.back().unwrap().0;
+        let root_id = tree.root_node_id()?;
+        TilingLayout::save_node(tree, root_id)
+    }
+
+    fn save_node(tree: &Tree<Data>, id: &NodeId) -> Option<SavedNode> {
+        let node = tree.get(id).unwrap();
+        match node.data() {
+            Data::Group {
+                orientation, sizes, ..
+            } => {
+                let total = sizes.iter().sum::<i32>().max(1) as f64;
+                let (ratios, children) = node
+                    .children()
+                    .iter()
+                    .zip(sizes.iter())
+                    .filter_map(|(child_id, size)| {
+                        let child = TilingLayout::save_node(tree, child_id)?;
+                        Some((*size as f64 / total, child))
+                    })
+                    .unzip::<_, _, Vec<_>, Vec<_>>();
+                match children.len() {
+                    0 => None,
+                    1 => children.into_iter().next(),
+                    _ => Some(SavedNode::Group {
+                        orientation: *orientation,
+                        ratios,
+                        children,
+                    }),
+                }
+            }
+            Data::Mapped { mapped, .. } => Some(SavedNode::Windows(
+                mapped.windows().map(|(w, _)| WindowKey::new(&w)).collect(),
+            )),
+            Data::Placeholder { .. } => None,
+        }
+    }
+
+    /// Rearranges the tree like the saved one, as far as its windows are mapped.
+    ///
+    /// `windows` are the surfaces mapped into the saved tree, in the order of [`SavedNode::keys`].
+    /// Trees containing other windows are left alone.
+    pub fn restore(&mut self, saved: &SavedNode, windows: &[Option<CosmicSurface>]) {
+        if self.mode != TilingMode::Manual {
+            return;
+        }
+
+        let gaps = self.gaps();
+        let mut tree = self.queue.trees.back().unwrap().0.copy_clone();
+        let leaves = TilingLayout::leaves(&tree);
+        if leaves.len() < 2
+            || leaves
+                .iter()
+                .any(|id| tree.get(id).unwrap().data().is_placeholder())
+        {
+            return;
+        }
+
+        let mut offset = 0;
+        let Some(shape) = TilingLayout::saved_shape(&tree, &leaves, saved, windows, &mut offset)
+        else {
+            return;
+        };
+        let mut shape_leaves = Vec::new();
+        shape.leaves(&mut shape_leaves);
+        if shape_leaves.len() != leaves.len() || leaves.iter().any(|id| !shape_leaves.contains(id))
+        {
+            return;
+        }
+
+        TilingLayout::apply_shape(&mut tree, shape);
+        let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
+        self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
+    }
+
+    fn saved_shape(
+        tree: &Tree<Data>,
+        leaves: &[NodeId],
+        saved: &SavedNode,
+        windows: &[Option<CosmicSurface>],
+        offset: &mut usize,
+    ) -> Option<Shape> {
+        match saved {
+            SavedNode::Windows(keys) => {
+                let range = *offset..*offset + keys.len();
+                *offset += keys.len();
+                // stacks are restored while mapping, the first window present stands in for it
+                windows[range].iter().flatten().find_map(|surface| {
+                    leaves
+                        .iter()
+                        .find(|id| match tree.get(id).unwrap().data() {
+                            Data::Mapped { mapped, .. } => {
+                                mapped.windows().any(|(w, _)| &w == surface)
+                            }
+                            _ => false,
+                        })
+                        .map(|id| Shape::Leaf(id.clone()))
+                })
+            }
+            SavedNode::Group {
+                orientation,
+                ratios,
+                children,
+            } => {
+                let mut children = ratios
+                    .iter()
+                    .zip(children.iter())
+                    .filter_map(|(ratio, child)| {
+                        let shape = TilingLayout::saved_shape(tree, leaves, child, windows, offset);
+                        Some((*ratio, shape?))
+                    })
+                    .collect::<Vec<_>>();
+                match children.len() {
+                    0 => None,
+                    1 => children.pop().map(|(_, shape)| shape),
+                    _ => {
+                        let total = children.iter().map(|(ratio, _)| ratio).sum::<f64>();
+                        for (ratio, _) in children.iter_mut() {
+                            *ratio /= total;
+                        }
+                        Some(Shape::Split(*orientation, children))
+                    }
+                }
+            }
+        }
+    }
+
     pub fn update_orientation<'a>(
         &mut self,
         new_orientation: Option<Orientation>,
@@ -1974,6 +2350,7 @@ impl TilingLayout {
                     &self.output,
                     Some(current_node),
                     None,
+                    None,
                     false,
                 );
 
@@ -2267,21 +2644,9 @@ impl TilingLayout {
         let gaps = self.gaps();
 
         let mut tree = self.queue.trees.back().unwrap().0.copy_clone();
-        let Some(root_id) = tree.root_node_id() else { return false };
-        let Some(mut node_id) =
-            (match TilingLayout::currently_focused_node(&tree, focused.clone()) {
-                Some((_id, FocusedNodeData::Window(mapped))) =>
-                // we need to make sure the id belongs to this tree..
-                {
-                    tree.traverse_pre_order_ids(root_id)
-                        .unwrap()
-                        .find(|id| tree.get(id).unwrap().data().is_mapped(Some(&mapped)))
-                }
-                Some((id, FocusedNodeData::Group(_, _))) => Some(id), // in this case the workspace handle was already matched, so the id is to be trusted
-                _ => None,
-            }) else {
-                return false
-            };
+        let Some(mut node_id) = TilingLayout::focused_node_id(&tree, focused) else {
+            return false;
+        };
 
         while let Some(group_id) = tree.get(&node_id).unwrap().parent().cloned() {
             let orientation = tree.get(&group_id).unwrap().data().orientation();
@@ -2353,6 +2718,121 @@ impl TilingLayout {
         true
     }
 
+    /// Sets the width or height of the focused node, taking the space from its siblings.
+    ///
+    /// Returns `false` if the node isn't tiled here or has no siblings along `dimension`.
+    pub fn resize_to(
+        &mut self,
+        focused: &KeyboardFocusTarget,
+        dimension: ResizeDimension,
+        size: ResizeSize,
+    ) -> bool {
+        let gaps = self.gaps();
+
+        let mut tree = self.queue.trees.back().unwrap().0.copy_clone();
+        let Some(mut node_id) = TilingLayout::focused_node_id(&tree, focused) else {
+            return false;
+        };
+        let (orientation, min_size) = match dimension {
+            ResizeDimension::Width => (Orientation::Vertical, 360),
+            ResizeDimension::Height => (Orientation::Horizontal, 240),
+        };
+
+        while let Some(group_id) = tree.get(&node_id).unwrap().parent().cloned() {
+            if tree.get(&group_id).unwrap().data().orientation() != orientation {
+                node_id = group_id;
+                continue;
+            }
+
+            let node_idx = tree
+                .children_ids(&group_id)
+                .unwrap()
+                .position(|id| id == &node_id)
+                .unwrap();
+            let Data::Group { sizes, .. } = tree.get_mut(&group_id).unwrap().data_mut() else {
+                unreachable!()
+            };
+
+            let total: i32 = sizes.iter().sum();
+            let max_size = total - (sizes.len() as i32 - 1) * min_size;
+            if max_size < min_size {
+                return true;
+            }
+            let new_size = match size {
+                ResizeSize::Percent(percent) => total * percent.min(100) as i32 / 100,
+                ResizeSize::Pixels(pixels) => pixels,
+            }
+            .clamp(min_size, max_size);
+
+            // siblings keep their relative sizes
+            let others = (total - sizes[node_idx]).max(1) as f64;
+            let remaining = (total - new_size) as f64;
+            for (idx, len) in sizes.iter_mut().enumerate() {
+                *len = if idx == node_idx {
+                    new_size
+                } else {
+                    (*len as f64 / others * remaining).round() as i32
+                };
+            }
+            let sum: i32 = sizes.iter().sum();
+            let fixup_idx = if node_idx == sizes.len() - 1 {
+                node_idx - 1
+            } else {
+                sizes.len() - 1
+            };
+            sizes[fixup_idx] += total - sum;
+
+            let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
+            self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Gives the children of groups equal sizes, either of the focused group or of every group.
+    pub fn equalize(&mut self, seat: &Seat<State>, scope: EqualizeScope) {
+        let gaps = self.gaps();
+
+        let mut tree = self.queue.trees.back().unwrap().0.copy_clone();
+        let groups = match scope {
+            EqualizeScope::Group => {
+                let Some(target) = seat.get_keyboard().unwrap().current_focus() else {
+                    return;
+                };
+                TilingLayout::focused_node_id(&tree, &target)
+                    .and_then(|node_id| tree.get(&node_id).unwrap().parent().cloned())
+                    .into_iter()
+                    .collect::<Vec<_>>()
+            }
+            EqualizeScope::All => tree
+                .root_node_id()
+                .map(|root_id| {
+                    tree.traverse_pre_order_ids(root_id)
+                        .unwrap()
+                        .filter(|id| tree.get(id).unwrap().data().is_group())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default(),
+        };
+        if groups.is_empty() {
+            return;
+        }
+
+        for group_id in groups {
+            if let Data::Group { sizes, .. } = tree.get_mut(&group_id).unwrap().data_mut() {
+                let total: i32 = sizes.iter().sum();
+                let len = sizes.len() as i32;
+                sizes.iter_mut().for_each(|size| *size = total / len);
+                *sizes.last_mut().unwrap() += total % len;
+            }
+        }
+
+        let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
+        self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
+    }
+
     pub fn stacking_indicator(&self) -> Option<Rectangle<i32, Local>> {
         if let Some(TargetZone::WindowStack(_, geo)) =
             self.last_overview_hover.as_ref().map(|(_, zone)| zone)
@@ -2511,6 +2991,7 @@ impl TilingLayout {
                     &self.output,
                     None,
                     None,
+                    None,
                     false,
                 );
                 window
@@ -2533,6 +3014,8 @@ impl TilingLayout {
                 }
             }
         }
+        let leaves = TilingLayout::leaves(&tree);
+        TilingLayout::arrange(&mut tree, self.mode, &self.output, leaves);
 
         let blocker = TilingLayout::update_positions(&self.output, &mut tree, gaps);
         self.queue.push_tree(tree, ANIMATION_DURATION, blocker);
@@ -2553,6 +3036,20 @@ impl TilingLayout {
             )
     }
 
+    /// Id of the focused window or group, if it is part of `tree`
+    fn focused_node_id(tree: &Tree<Data>, focused: &KeyboardFocusTarget) -> Option<NodeId> {
+        let root_id = tree.root_node_id()?;
+        match TilingLayout::currently_focused_node(tree, focused.clone())? {
+            // we need to make sure the id belongs to this tree..
+            (_, FocusedNodeData::Window(mapped)) => tree
+                .traverse_pre_order_ids(root_id)
+                .unwrap()
+                .find(|id| tree.get(id).unwrap().data().is_mapped(Some(&mapped))),
+            // in this case the workspace handle was already matched, so the id is to be trusted
+            (id, FocusedNodeData::Group(_, _)) => Some(id),
+        }
+    }
+
     fn currently_focused_node(
         tree: &Tree<Data>,
         mut target: KeyboardFocusTarget,
@@ -2645,6 +3142,79 @@ impl TilingLayout {
         Ok(group_id)
     }
 
+    fn leaves(tree: &Tree<Data>) -> Vec<NodeId> {
+        tree.root_node_id()
+            .map(|root_id| {
+                tree.traverse_pre_order_ids(root_id)
+                    .unwrap()
+                    .filter(|id| !tree.get(id).unwrap().data().is_group())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Rebuilds the groups of the tree to match the given mode, keeping the leaves in the given order.
+    ///
+    /// Leaves keep their ids, so the change is animated like any other.
+    fn arrange(tree: &mut Tree<Data>, mode: TilingMode, output: &Output, leaves: Vec<NodeId>) {
+        // don't touch the tree while a window is dragged around
+        if mode == TilingMode::Manual
+            || leaves.len() < 2
+            || leaves
+                .iter()
+                .any(|id| tree.get(id).unwrap().data().is_placeholder())
+        {
+            return;
+        }
+
+        let output_size = output.geometry().size;
+        let shape = Shape::new(mode, leaves, output_size.w > output_size.h);
+        TilingLayout::apply_shape(tree, shape);
+    }
+
+    /// Replaces the groups of the tree with the ones of `shape`, which has to contain every leaf.
+    fn apply_shape(tree: &mut Tree<Data>, shape: Shape) {
+        let old_root_id = tree.root_node_id().cloned().unwrap();
+        let Shape::Split(orientation, children) = shape else {
+            unreachable!("Multiple leaves always need a group")
+        };
+        let ratios = children.iter().map(|(ratio, _)| *ratio).collect::<Vec<_>>();
+        let root_id = tree
+            .insert(
+                Node::new(Data::new_split(orientation, &ratios)),
+                InsertBehavior::AsRoot,
+            )
+            .unwrap();
+        TilingLayout::arrange_children(tree, &root_id, children);
+        // only the old groups are left
+        tree.remove_node(old_root_id, RemoveBehavior::DropChildren)
+            .unwrap();
+    }
+
+    fn arrange_children(tree: &mut Tree<Data>, group_id: &NodeId, children: Vec<(f64, Shape)>) {
+        for (idx, (_, shape)) in children.into_iter().enumerate() {
+            let id = match shape {
+                Shape::Leaf(id) => {
+                    tree.move_node(&id, MoveBehavior::ToParent(group_id))
+                        .unwrap();
+                    id
+                }
+                Shape::Split(orientation, children) => {
+                    let ratios = children.iter().map(|(ratio, _)| *ratio).collect::<Vec<_>>();
+                    let id = tree
+                        .insert(
+                            Node::new(Data::new_split(orientation, &ratios)),
+                            InsertBehavior::UnderNode(group_id),
+                        )
+                        .unwrap();
+                    TilingLayout::arrange_children(tree, &id, children);
+                    id
+                }
+            };
+            tree.make_nth_sibling(&id, idx).unwrap();
+        }
+    }
+
     fn has_adjacent_node(tree: &Tree<Data>, node: &NodeId, direction: Direction) -> bool {
         let mut search_node = node;
         match tree.ancestor_ids(node) {
@@ -3518,6 +4088,8 @@ impl TilingLayout {
             _ => Orientation::Horizontal,
         };
         TilingLayout::merge_trees(src, &mut dst, orientation);
+        let leaves = TilingLayout::leaves(&dst);
+        TilingLayout::arrange(&mut dst, self.mode, &self.output, leaves);
 
         let blocker = TilingLayout::update_positions(&self.output, &mut dst, gaps);
         self.queue.push_tree(dst, ANIMATION_DURATION, blocker);
@@ -3724,6 +4296,39 @@ impl TilingLayout {
             window_elements.extend(group_elements);
         }
 
+        // where the next window goes
+        if let Some((node_id, preselection)) = self.preselection.as_ref().and_then(|(target, p)| {
+            TilingLayout::last_active_window(target_tree, std::iter::once(target))
+                .map(|(id, _)| (id, *p))
+        }) {
+            let mut geo = target_tree.get(&node_id).unwrap().data().geometry().clone();
+            match preselection {
+                Preselection::Horizontal => {
+                    geo.loc.x += geo.size.w / 2;
+                    geo.size.w -= geo.size.w / 2;
+                }
+                Preselection::Vertical => {
+                    geo.loc.y += geo.size.h / 2;
+                    geo.size.h -= geo.size.h / 2;
+                }
+                Preselection::Stacked => {}
+            }
+            window_elements.insert(
+                0,
+                IndicatorShader::element(
+                    renderer,
+                    Key::Static(self.preselection_id.clone()),
+                    geo,
+                    4,
+                    8,
+                    0.6,
+                    output_scale,
+                    group_color(theme),
+                )
+                .into(),
+            );
+        }
+
         Ok((window_elements, popup_elements))
     }
 
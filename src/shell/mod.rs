@@ -1,10 +1,14 @@
-use calloop::LoopHandle;
+use calloop::{
+    timer::{TimeoutAction, Timer},
+    LoopHandle, RegistrationToken,
+};
 use indexmap::IndexMap;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::atomic::Ordering,
     time::{Duration, Instant},
 };
+use tracing::error;
 use wayland_backend::server::ClientId;
 
 use cosmic_comp_config::workspace::{WorkspaceAmount, WorkspaceMode};
@@ -53,8 +57,8 @@ use crate::{
             toplevel_info::ToplevelInfoState,
             toplevel_management::{ManagementCapabilities, ToplevelManagementState},
             workspace::{
-                WorkspaceCapabilities, WorkspaceGroupHandle, WorkspaceHandle, WorkspaceState,
-                WorkspaceUpdateGuard,
+                GroupCapabilities, WorkspaceCapabilities, WorkspaceGroupHandle, WorkspaceHandle,
+                WorkspaceState, WorkspaceUpdateGuard,
             },
         },
     },
@@ -62,14 +66,18 @@ use crate::{
 };
 
 pub mod element;
+mod expose;
 pub mod focus;
 pub mod grabs;
 pub mod layout;
 mod workspace;
 pub use self::element::{CosmicMapped, CosmicMappedRenderElement, CosmicSurface};
+pub use self::expose::*;
 pub use self::workspace::*;
 use self::{
     element::{
+        mode_indicator::{mode_indicator, ModeIndicator},
+        notification::{notification, Notification},
         resize_indicator::{resize_indicator, ResizeIndicator},
         swap_indicator::{swap_indicator, SwapIndicator},
         CosmicWindow, MaximizedState,
@@ -83,11 +91,14 @@ use self::{
     },
     layout::{
         floating::{FloatingLayout, ResizeState},
+        saved::{LayoutRestore, SavedLayouts, SavedSlot, SavedWorkspace, WindowKey},
         tiling::{NodeDesc, ResizeForkGrab, TilingLayout},
     },
 };
 
 const ANIMATION_DURATION: Duration = Duration::from_millis(200);
+/// How long notifications of the compositor stay visible
+pub const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone)]
 pub enum Trigger {
@@ -125,12 +136,76 @@ impl OverviewMode {
     }
 }
 
+/// Progress of switching between two workspaces
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkspaceDelta {
+    /// Switch triggered at once, e.g. by a shortcut
+    Shortcut(Instant),
+    /// Switch following a running gesture, the progress in `0.0..=1.0`
+    Gesture(f64),
+    /// Finishing a switch after a gesture ended at the given progress
+    GestureEnd(Instant, f64),
+}
+
+impl WorkspaceDelta {
+    pub fn new_shortcut() -> WorkspaceDelta {
+        WorkspaceDelta::Shortcut(Instant::now())
+    }
+
+    pub fn percentage(&self) -> f32 {
+        match self {
+            WorkspaceDelta::Shortcut(start) => {
+                let percentage = Instant::now().duration_since(*start).as_millis() as f32
+                    / ANIMATION_DURATION.as_millis() as f32;
+                ease(EaseInOutCubic, 0.0, 1.0, percentage)
+            }
+            WorkspaceDelta::Gesture(progress) => *progress as f32,
+            WorkspaceDelta::GestureEnd(start, progress) => {
+                let percentage = Instant::now().duration_since(*start).as_millis() as f32
+                    / ANIMATION_DURATION.as_millis() as f32;
+                ease(EaseInOutCubic, *progress as f32, 1.0, percentage)
+            }
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        match self {
+            WorkspaceDelta::Shortcut(start) | WorkspaceDelta::GestureEnd(start, _) => {
+                Instant::now().duration_since(*start) >= ANIMATION_DURATION
+            }
+            WorkspaceDelta::Gesture(_) => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, serde::Deserialize, PartialEq, Eq, Hash)]
 pub enum ResizeDirection {
     Inwards,
     Outwards,
 }
 
+#[derive(Debug, Clone, Copy, serde::Deserialize, PartialEq, Eq, Hash)]
+pub enum ResizeDimension {
+    Width,
+    Height,
+}
+
+/// Target size of a tiled window or group
+#[derive(Debug, Clone, Copy, serde::Deserialize, PartialEq, Eq, Hash)]
+pub enum ResizeSize {
+    /// Share of the enclosing group
+    Percent(u8),
+    Pixels(i32),
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, PartialEq, Eq, Hash)]
+pub enum EqualizeScope {
+    /// The focused window or group and its siblings
+    Group,
+    /// Every group of the workspace
+    All,
+}
+
 #[derive(Debug, Clone)]
 pub enum ResizeMode {
     None,
@@ -209,6 +284,22 @@ pub struct Shell {
         Output,
     )>,
     resize_indicator: Option<ResizeIndicator>,
+    binding_mode: Option<(String, ModeIndicator)>,
+    notification: Option<(RegistrationToken, Notification)>,
+    scratchpad: Scratchpad,
+    /// Layouts of the previous session, waiting for their windows
+    layout_restore: LayoutRestore,
+    /// Overview of all windows, see [`Shell::open_expose`]
+    expose: Option<Expose>,
+}
+
+/// Windows parked in the scratchpad, see [`Shell::toggle_scratchpad`]
+#[derive(Debug, Default)]
+struct Scratchpad {
+    /// Hidden windows, in the order they are shown
+    hidden: VecDeque<CosmicMapped>,
+    /// Windows of the scratchpad currently shown on a workspace
+    shown: Vec<CosmicMapped>,
 }
 
 #[derive(Debug)]
@@ -219,7 +310,9 @@ pub struct SessionLock {
 
 #[derive(Debug)]
 pub struct WorkspaceSet {
-    previously_active: Option<(usize, Instant)>,
+    previously_active: Option<(usize, WorkspaceDelta)>,
+    /// Workspace shown by a running gesture and its progress, activated once the gesture completes
+    gesture: Option<(usize, f64)>,
     active: usize,
     group: WorkspaceGroupHandle,
     idx: usize,
@@ -289,6 +382,7 @@ fn merge_workspaces(
         }
     }
     into.tiling_layer.merge(workspace.tiling_layer);
+    into.scrolling_layer.merge(workspace.scrolling_layer);
     into.floating_layer.merge(workspace.floating_layer);
     workspace_state.remove_workspace(workspace.handle);
 }
@@ -313,7 +407,7 @@ impl WorkspaceSet {
                     tiling_enabled,
                     theme.clone(),
                 );
-                workspace_set_idx(state, 1, idx, &workspace.handle);
+                workspace_set_idx(state, 1, idx, &workspace);
                 state.set_workspace_capabilities(
                     &workspace.handle,
                     [WorkspaceCapabilities::Activate].into_iter(),
@@ -330,7 +424,7 @@ impl WorkspaceSet {
                         tiling_enabled,
                         theme.clone(),
                     );
-                    workspace_set_idx(state, i + 1, idx, &workspace.handle);
+                    workspace_set_idx(state, i + 1, idx, &workspace);
                     state.set_workspace_capabilities(
                         &workspace.handle,
                         [WorkspaceCapabilities::Activate].into_iter(),
@@ -343,6 +437,7 @@ impl WorkspaceSet {
 
         WorkspaceSet {
             previously_active: None,
+            gesture: None,
             active: 0,
             group: group_handle,
             idx,
@@ -357,11 +452,13 @@ impl WorkspaceSet {
     fn activate(
         &mut self,
         idx: usize,
+        delta: WorkspaceDelta,
         state: &mut WorkspaceUpdateGuard<'_, State>,
     ) -> Result<bool, InvalidWorkspaceIndex> {
         if idx >= self.workspaces.len() {
             return Err(InvalidWorkspaceIndex);
         }
+        self.gesture = None;
 
         if self.active != idx {
             let old_active = self.active;
@@ -370,7 +467,7 @@ impl WorkspaceSet {
             state.remove_workspace_state(&self.workspaces[idx].handle, WState::Urgent);
             state.add_workspace_state(&self.workspaces[idx].handle, WState::Active);
 
-            self.previously_active = Some((old_active, Instant::now()));
+            self.previously_active = Some((old_active, delta));
             self.active = idx;
             Ok(true)
         } else {
@@ -395,8 +492,8 @@ impl WorkspaceSet {
     }
 
     fn refresh<'a>(&mut self, xdg_activation_state: &XdgActivationState) {
-        if let Some((_, start)) = self.previously_active {
-            if Instant::now().duration_since(start).as_millis() >= ANIMATION_DURATION.as_millis() {
+        if let Some((_, delta)) = self.previously_active {
+            if delta.is_done() {
                 self.previously_active = None;
             }
         } else {
@@ -414,12 +511,7 @@ impl WorkspaceSet {
             self.tiling_enabled,
             self.theme.clone(),
         );
-        workspace_set_idx(
-            state,
-            self.workspaces.len() as u8 + 1,
-            self.idx,
-            &workspace.handle,
-        );
+        workspace_set_idx(state, self.workspaces.len() as u8 + 1, self.idx, &workspace);
         self.workspaces.push(workspace);
     }
 
@@ -428,7 +520,7 @@ impl WorkspaceSet {
         if self
             .workspaces
             .last()
-            .map(|last| !last.pending_tokens.is_empty() || last.windows().next().is_some())
+            .map(|last| last.is_used())
             .unwrap_or(true)
         {
             self.add_empty_workspace(state);
@@ -438,10 +530,7 @@ impl WorkspaceSet {
         let len = self.workspaces.len();
         let mut keep = vec![true; len];
         for (i, workspace) in self.workspaces.iter().enumerate() {
-            let has_windows =
-                !workspace.pending_tokens.is_empty() || workspace.windows().next().is_some();
-
-            if !has_windows && i != self.active && i != len - 1 {
+            if !workspace.is_used() && i != self.active && i != len - 1 {
                 state.remove_workspace(workspace.handle);
                 keep[i] = false;
             }
@@ -457,7 +546,7 @@ impl WorkspaceSet {
 
         if keep.iter().any(|val| *val == false) {
             for (i, workspace) in self.workspaces.iter().enumerate() {
-                workspace_set_idx(state, i as u8 + 1, self.idx, &workspace.handle);
+                workspace_set_idx(state, i as u8 + 1, self.idx, workspace);
             }
         }
     }
@@ -494,12 +583,7 @@ impl WorkspaceSet {
                     self.tiling_enabled,
                     self.theme.clone(),
                 );
-                workspace_set_idx(
-                    state,
-                    self.workspaces.len() as u8 + 1,
-                    self.idx,
-                    &workspace.handle,
-                );
+                workspace_set_idx(state, self.workspaces.len() as u8 + 1, self.idx, &workspace);
                 state.set_workspace_capabilities(
                     &workspace.handle,
                     [WorkspaceCapabilities::Activate].into_iter(),
@@ -512,8 +596,147 @@ impl WorkspaceSet {
     fn update_idx(&mut self, state: &mut WorkspaceUpdateGuard<'_, State>, idx: usize) {
         self.idx = idx;
         for (i, workspace) in self.workspaces.iter().enumerate() {
-            workspace_set_idx(state, i as u8 + 1, idx, &workspace.handle);
+            workspace_set_idx(state, i as u8 + 1, idx, workspace);
+        }
+    }
+
+    /// Inserts a new empty workspace at `idx`
+    fn insert_workspace(
+        &mut self,
+        idx: usize,
+        name: Option<String>,
+        state: &mut WorkspaceUpdateGuard<'_, State>,
+    ) {
+        let mut workspace = create_workspace(
+            state,
+            &self.output,
+            &self.group,
+            false,
+            self.tiling_enabled,
+            self.theme.clone(),
+        );
+        workspace.name = name;
+        self.workspaces.insert(idx, workspace);
+
+        if self.active >= idx {
+            self.active += 1;
+        }
+        if let Some((previous, _)) = self.previously_active.as_mut() {
+            if *previous >= idx {
+                *previous += 1;
+            }
+        }
+        if let Some((target, _)) = self.gesture.as_mut() {
+            if *target >= idx {
+                *target += 1;
+            }
+        }
+        self.update_idx(state, self.idx);
+    }
+
+    /// Removes the workspace at `idx`, activating its successor if it was active
+    fn remove_workspace(&mut self, idx: usize, state: &mut WorkspaceUpdateGuard<'_, State>) {
+        let workspace = self.workspaces.remove(idx);
+        state.remove_workspace(workspace.handle);
+
+        self.previously_active = match self.previously_active {
+            Some((previous, _)) if previous == idx => None,
+            Some((previous, delta)) if previous > idx => Some((previous - 1, delta)),
+            previously => previously,
+        };
+        self.gesture = match self.gesture {
+            Some((target, _)) if target == idx => None,
+            Some((target, progress)) if target > idx => Some((target - 1, progress)),
+            gesture => gesture,
+        };
+        let was_active = self.active == idx;
+        if self.active > idx || self.active == self.workspaces.len() {
+            self.active -= 1;
         }
+        if was_active {
+            state.add_workspace_state(&self.workspaces[self.active].handle, WState::Active);
+        }
+        self.update_idx(state, self.idx);
+    }
+
+    fn update_capabilities(
+        &self,
+        amount: WorkspaceAmount,
+        state: &mut WorkspaceUpdateGuard<'_, State>,
+    ) {
+        let dynamic = amount == WorkspaceAmount::Dynamic;
+        state.set_group_capabilities(
+            &self.group,
+            dynamic
+                .then_some(GroupCapabilities::CreateWorkspace)
+                .into_iter(),
+        );
+        for workspace in &self.workspaces {
+            let removable = dynamic && self.workspaces.len() > 1 && workspace.is_empty();
+            state.set_workspace_capabilities(
+                &workspace.handle,
+                std::iter::once(WorkspaceCapabilities::Activate)
+                    .chain(removable.then_some(WorkspaceCapabilities::Remove)),
+            );
+        }
+    }
+
+    /// Names the first workspaces with a static amount, creates missing named workspaces otherwise
+    fn apply_names(
+        &mut self,
+        names: &[String],
+        amount: WorkspaceAmount,
+        state: &mut WorkspaceUpdateGuard<'_, State>,
+    ) {
+        match amount {
+            WorkspaceAmount::Static(_) => {
+                let mut changed = false;
+                for (i, workspace) in self.workspaces.iter_mut().enumerate() {
+                    let name = names.get(i);
+                    if workspace.name.as_ref() != name {
+                        workspace.name = name.cloned();
+                        workspace.client_named = false;
+                        changed = true;
+                    }
+                }
+                if changed {
+                    self.update_idx(state, self.idx);
+                }
+            }
+            WorkspaceAmount::Dynamic => {
+                // forget names removed from the config, so their workspaces can go once empty
+                let mut changed = false;
+                for workspace in &mut self.workspaces {
+                    if !workspace.client_named
+                        && workspace
+                            .name
+                            .as_ref()
+                            .is_some_and(|name| !names.contains(name))
+                    {
+                        workspace.name = None;
+                        changed = true;
+                    }
+                }
+                if changed {
+                    self.update_idx(state, self.idx);
+                }
+
+                for name in names {
+                    if !self
+                        .workspaces
+                        .iter()
+                        .any(|w| w.name.as_ref() == Some(name))
+                    {
+                        let idx = self.workspaces.len().saturating_sub(1);
+                        self.insert_workspace(idx, Some(name.clone()), state);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn group(&self) -> WorkspaceGroupHandle {
+        self.group
     }
 
     fn update_tiling_status(&mut self, seat: &Seat<State>, tiling_enabled: bool) {
@@ -532,6 +755,7 @@ pub struct Workspaces {
     backup_set: Option<WorkspaceSet>,
     amount: WorkspaceAmount,
     mode: WorkspaceMode,
+    names: Vec<String>,
     tiling_enabled: bool,
     theme: cosmic::Theme,
 }
@@ -543,6 +767,7 @@ impl Workspaces {
             backup_set: None,
             amount: config.workspace.workspace_amount,
             mode: config.workspace.workspace_mode,
+            names: config.workspace.workspace_names.clone(),
             tiling_enabled: config.static_conf.tiling_enabled,
             theme,
         }
@@ -579,6 +804,7 @@ impl Workspaces {
         workspace_state.add_group_output(&set.group, &output);
 
         self.sets.insert(output.clone(), set);
+        self.sets[output].apply_names(&self.names, self.amount, workspace_state);
         let mut moved_workspaces = Vec::new();
         for set in self.sets.values_mut() {
             let (preferrs, doesnt) = set
@@ -602,7 +828,7 @@ impl Workspaces {
             for (i, workspace) in set.workspaces.iter_mut().enumerate() {
                 workspace.set_output(output, toplevel_info_state);
                 workspace.refresh(xdg_activation_state);
-                workspace_set_idx(workspace_state, i as u8 + 1, set.idx, &workspace.handle);
+                workspace_set_idx(workspace_state, i as u8 + 1, set.idx, workspace);
                 if i == set.active {
                     workspace_state.add_workspace_state(&workspace.handle, WState::Active);
                 }
@@ -737,6 +963,10 @@ impl Workspaces {
 
         self.mode = config.workspace.workspace_mode;
         self.amount = config.workspace.workspace_amount;
+        self.names = config.workspace.workspace_names.clone();
+        for set in self.sets.values_mut() {
+            set.apply_names(&self.names, self.amount, workspace_state);
+        }
 
         if self.sets.len() <= 1 {
             return;
@@ -839,7 +1069,7 @@ impl Workspaces {
                             .sets
                             .values()
                             .flat_map(|set| set.workspaces.last())
-                            .any(|w| w.mapped().next().is_some())
+                            .any(|w| w.windows().next().is_some() || w.name.is_some())
                         {
                             for set in self.sets.values_mut() {
                                 set.add_empty_workspace(workspace_state);
@@ -851,12 +1081,9 @@ impl Workspaces {
                         let mut active = self.sets[0].active;
                         let mut keep = vec![true; len];
                         for i in 0..len {
-                            let has_windows = self.sets.values().any(|s| {
-                                !s.workspaces[i].pending_tokens.is_empty()
-                                    || s.workspaces[i].windows().next().is_some()
-                            });
+                            let used = self.sets.values().any(|s| s.workspaces[i].is_used());
 
-                            if !has_windows && i != active && i != len - 1 {
+                            if !used && i != active && i != len - 1 {
                                 for workspace in self.sets.values().map(|s| &s.workspaces[i]) {
                                     workspace_state.remove_workspace(workspace.handle);
                                 }
@@ -880,7 +1107,7 @@ impl Workspaces {
                                         workspace_state,
                                         i as u8 + 1,
                                         set.idx,
-                                        &workspace.handle,
+                                        workspace,
                                     );
                                 }
                             }
@@ -893,7 +1120,8 @@ impl Workspaces {
                                 workspace_state,
                                 toplevel_info_state,
                                 xdg_activation_state,
-                            )
+                            );
+                            set.apply_names(&self.names, self.amount, workspace_state);
                         }
                     }
                 }
@@ -911,17 +1139,85 @@ impl Workspaces {
                             workspace_state,
                             toplevel_info_state,
                             xdg_activation_state,
-                        )
+                        );
+                        set.apply_names(&self.names, self.amount, workspace_state);
                     }
                 }
             },
         }
 
         for set in self.sets.values_mut() {
-            set.refresh(xdg_activation_state)
+            set.refresh(xdg_activation_state);
+            set.update_capabilities(self.amount, workspace_state);
         }
     }
 
+    /// Creates a new workspace in `group`, returns `None` unless workspaces are dynamic.
+    ///
+    /// The workspace is inserted before the trailing empty workspace and,
+    /// being named, kept even if it has no windows.
+    pub fn create_workspace(
+        &mut self,
+        group: &WorkspaceGroupHandle,
+        name: Option<String>,
+        workspace_state: &mut WorkspaceUpdateGuard<'_, State>,
+    ) -> Option<WorkspaceHandle> {
+        if self.amount != WorkspaceAmount::Dynamic {
+            return None;
+        }
+        let set = self.sets.values().find(|set| &set.group == group)?;
+        let idx = set.workspaces.len().saturating_sub(1);
+
+        for set in self.sets.values_mut() {
+            if self.mode == WorkspaceMode::Global || &set.group == group {
+                set.insert_workspace(idx, name.clone(), workspace_state);
+                set.workspaces[idx].client_named = name.is_some();
+            }
+        }
+
+        self.sets
+            .values()
+            .find(|set| &set.group == group)
+            .map(|set| set.workspaces[idx].handle)
+    }
+
+    /// Removes an empty workspace, returns `false` if it can't be removed.
+    pub fn remove_workspace(
+        &mut self,
+        handle: &WorkspaceHandle,
+        workspace_state: &mut WorkspaceUpdateGuard<'_, State>,
+    ) -> bool {
+        if self.amount != WorkspaceAmount::Dynamic {
+            return false;
+        }
+        let Some((group, idx)) = self.sets.values().find_map(|set| {
+            set.workspaces
+                .iter()
+                .position(|w| &w.handle == handle)
+                .map(|idx| (set.group, idx))
+        }) else {
+            return false;
+        };
+
+        let global = self.mode == WorkspaceMode::Global;
+        let mut affected = self
+            .sets
+            .values()
+            .filter(|set| global || set.group == group);
+        if affected.any(|set| set.workspaces.len() <= 1 || !set.workspaces[idx].is_empty()) {
+            return false;
+        }
+
+        for set in self
+            .sets
+            .values_mut()
+            .filter(|set| global || set.group == group)
+        {
+            set.remove_workspace(idx, workspace_state);
+        }
+        true
+    }
+
     pub fn get(&self, num: usize, output: &Output) -> Option<&Workspace> {
         self.sets
             .get(output)
@@ -934,11 +1230,11 @@ impl Workspaces {
             .and_then(|set| set.workspaces.get_mut(num))
     }
 
-    pub fn active(&self, output: &Output) -> (Option<(&Workspace, Instant)>, &Workspace) {
+    pub fn active(&self, output: &Output) -> (Option<(&Workspace, WorkspaceDelta)>, &Workspace) {
         let set = self.sets.get(output).or(self.backup_set.as_ref()).unwrap();
         (
             set.previously_active
-                .map(|(idx, start)| (&set.workspaces[idx], start)),
+                .map(|(idx, delta)| (&set.workspaces[idx], delta)),
             &set.workspaces[set.active],
         )
     }
@@ -957,6 +1253,39 @@ impl Workspaces {
         (set.previously_active.map(|(idx, _)| idx), set.active)
     }
 
+    /// The workspaces to render on `output`: the one transitioned from, if any, and the one transitioned to.
+    ///
+    /// While a gesture is running, this transitions from the still active workspace to the gesture's target.
+    pub fn visible(
+        &self,
+        output: &Output,
+    ) -> (
+        Option<(WorkspaceHandle, usize, WorkspaceDelta)>,
+        (WorkspaceHandle, usize),
+    ) {
+        let set = self.sets.get(output).or(self.backup_set.as_ref()).unwrap();
+        if let Some((target, progress)) = set.gesture {
+            return (
+                Some((
+                    set.workspaces[set.active].handle,
+                    set.active,
+                    WorkspaceDelta::Gesture(progress),
+                )),
+                (set.workspaces[target].handle, target),
+            );
+        }
+        (
+            set.previously_active
+                .map(|(idx, delta)| (set.workspaces[idx].handle, idx, delta)),
+            (set.workspaces[set.active].handle, set.active),
+        )
+    }
+
+    /// The target of a running workspace gesture on `output` and its progress.
+    pub fn gesture(&self, output: &Output) -> Option<(usize, f64)> {
+        self.sets.get(output).and_then(|set| set.gesture)
+    }
+
     pub fn idx_for_handle(&self, output: &Output, handle: &WorkspaceHandle) -> Option<usize> {
         let set = self.sets.get(output).unwrap();
         set.workspaces
@@ -1015,6 +1344,7 @@ impl Workspaces {
             for mut w in &mut s.workspaces {
                 w.tiling_layer.theme = theme.clone();
                 w.floating_layer.theme = theme.clone();
+                w.scrolling_layer.theme = theme.clone();
 
                 w.mapped().for_each(|m| {
                     m.update_theme(theme.clone());
@@ -1054,6 +1384,9 @@ impl Shell {
             vec![
                 ManagementCapabilities::Close,
                 ManagementCapabilities::Activate,
+                ManagementCapabilities::Maximize,
+                ManagementCapabilities::Minimize,
+                ManagementCapabilities::Fullscreen,
             ],
             client_should_see_privileged_protocols,
         );
@@ -1084,9 +1417,79 @@ impl Shell {
             resize_mode: ResizeMode::None,
             resize_state: None,
             resize_indicator: None,
+            binding_mode: None,
+            notification: None,
+            scratchpad: Scratchpad::default(),
+            layout_restore: LayoutRestore::new(config.dynamic_conf.layouts().clone()),
+            expose: None,
         }
     }
 
+    /// Snapshot of the tiling trees and floating windows of all workspaces
+    pub fn saved_layouts(&self) -> SavedLayouts {
+        let mut layouts = SavedLayouts::default();
+        for (output, set) in self.workspaces.sets.iter() {
+            for (idx, workspace) in set.workspaces.iter().enumerate() {
+                let tiling = workspace.tiling_layer.save();
+                let floating = workspace
+                    .floating_layer
+                    .mapped()
+                    // maximized tiled windows are kept in the tiling tree
+                    .filter(|mapped| {
+                        !workspace
+                            .tiling_layer
+                            .mapped()
+                            .any(|(_, m, _)| m == *mapped)
+                    })
+                    .filter_map(|mapped| {
+                        let geometry = mapped
+                            .maximized_state
+                            .lock()
+                            .unwrap()
+                            .as_ref()
+                            .map(|state| state.original_geometry)
+                            .or_else(|| workspace.floating_layer.element_geometry(mapped))?;
+                        Some((
+                            WindowKey::new(&mapped.active_window()),
+                            (
+                                geometry.loc.x,
+                                geometry.loc.y,
+                                geometry.size.w,
+                                geometry.size.h,
+                            ),
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+
+                if tiling.is_some() || !floating.is_empty() {
+                    layouts.workspaces.push(SavedWorkspace {
+                        output: output.name(),
+                        idx,
+                        tiling,
+                        floating,
+                    });
+                }
+            }
+        }
+        layouts
+    }
+
+    /// Replaces the layouts windows are restored into when they are mapped
+    pub fn restore_layouts(&mut self, layouts: SavedLayouts) {
+        self.layout_restore = LayoutRestore::new(layouts);
+    }
+
+    /// Returns `true` while windows are still placed into the layouts of the previous session.
+    ///
+    /// Once restoring ended, the remaining layouts are discarded.
+    pub fn restoring_layouts(&mut self) -> bool {
+        let active = self.layout_restore.is_active();
+        if !active {
+            self.layout_restore.expire();
+        }
+        active
+    }
+
     pub fn add_output(&mut self, output: &Output) {
         self.workspaces.add_output(
             output,
@@ -1135,10 +1538,39 @@ impl Shell {
         );
     }
 
+    /// Index of the workspace named `name` on `output`, created on demand if workspaces are dynamic
+    pub fn workspace_by_name(&mut self, output: &Output, name: &str) -> Option<usize> {
+        let set = self.workspaces.sets.get(output)?;
+        if let Some(idx) = set
+            .workspaces
+            .iter()
+            .position(|w| w.name.as_deref() == Some(name))
+        {
+            return Some(idx);
+        }
+
+        let group = set.group;
+        let handle = self.workspaces.create_workspace(
+            &group,
+            Some(name.to_string()),
+            &mut self.workspace_state.update(),
+        )?;
+        self.workspaces.idx_for_handle(output, &handle)
+    }
+
     pub fn activate(
         &mut self,
         output: &Output,
         idx: usize,
+    ) -> Result<Option<Point<i32, Global>>, InvalidWorkspaceIndex> {
+        self.activate_with_delta(output, idx, WorkspaceDelta::new_shortcut())
+    }
+
+    pub fn activate_with_delta(
+        &mut self,
+        output: &Output,
+        idx: usize,
+        delta: WorkspaceDelta,
     ) -> Result<Option<Point<i32, Global>>, InvalidWorkspaceIndex> {
         match &mut self.workspaces.mode {
             WorkspaceMode::OutputBound => {
@@ -1149,7 +1581,7 @@ impl Shell {
                     ) {
                         set.workspaces[set.active].tiling_layer.cleanup_drag();
                     }
-                    set.activate(idx, &mut self.workspace_state.update())?;
+                    set.activate(idx, delta, &mut self.workspace_state.update())?;
                     if let Some(xwm) = self
                         .xwayland_state
                         .as_mut()
@@ -1195,7 +1627,7 @@ impl Shell {
             }
             WorkspaceMode::Global => {
                 for set in self.workspaces.sets.values_mut() {
-                    set.activate(idx, &mut self.workspace_state.update())?;
+                    set.activate(idx, delta, &mut self.workspace_state.update())?;
                 }
                 Ok(None)
             }
@@ -1210,6 +1642,66 @@ impl Shell {
         self.workspaces.active_mut(output)
     }
 
+    /// Starts showing the workspace at `idx` following a gesture.
+    ///
+    /// The workspace is only activated by [`Shell::end_workspace_gesture`], once the gesture completes.
+    pub fn begin_workspace_gesture(
+        &mut self,
+        output: &Output,
+        idx: usize,
+    ) -> Result<(), InvalidWorkspaceIndex> {
+        let sets: Vec<&mut WorkspaceSet> = match self.workspaces.mode {
+            WorkspaceMode::OutputBound => {
+                self.workspaces.sets.get_mut(output).into_iter().collect()
+            }
+            WorkspaceMode::Global => self.workspaces.sets.values_mut().collect(),
+        };
+        if sets.is_empty() || sets.iter().any(|set| idx >= set.workspaces.len()) {
+            return Err(InvalidWorkspaceIndex);
+        }
+        for set in sets {
+            if set.active != idx {
+                set.previously_active = None;
+                set.gesture = Some((idx, 0.0));
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the progress of workspace switches following a gesture.
+    pub fn update_workspace_gesture(&mut self, progress: f64) {
+        for set in self.workspaces.sets.values_mut() {
+            if let Some((_, gesture_progress)) = set.gesture.as_mut() {
+                *gesture_progress = progress.clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    /// Finishes workspace switches following a gesture,
+    /// either activating the shown workspaces or returning to the active ones.
+    pub fn end_workspace_gesture(&mut self, output: &Output, complete: bool) {
+        let Some((target, progress)) = self.workspaces.gesture(output) else {
+            return;
+        };
+
+        if complete {
+            let _ = self.activate_with_delta(
+                output,
+                target,
+                WorkspaceDelta::GestureEnd(Instant::now(), progress),
+            );
+        } else {
+            for set in self.workspaces.sets.values_mut() {
+                if let Some((target, progress)) = set.gesture.take() {
+                    set.previously_active = Some((
+                        target,
+                        WorkspaceDelta::GestureEnd(Instant::now(), 1.0 - progress),
+                    ));
+                }
+            }
+        }
+    }
+
     pub fn refresh_active_space(&mut self, output: &Output) {
         self.workspaces
             .active_mut(output)
@@ -1406,12 +1898,13 @@ impl Shell {
     }
 
     pub fn animations_going(&self) -> bool {
-        self.workspaces
-            .sets
-            .values()
-            .any(|set| set.previously_active.is_some() || set.sticky_layer.animations_going())
-            || !matches!(self.overview_mode, OverviewMode::None)
+        self.workspaces.sets.values().any(|set| {
+            set.previously_active.is_some()
+                || set.gesture.is_some()
+                || set.sticky_layer.animations_going()
+        }) || !matches!(self.overview_mode, OverviewMode::None)
             || !matches!(self.resize_mode, ResizeMode::None)
+            || self.expose_animating()
             || self
                 .workspaces
                 .spaces()
@@ -1510,6 +2003,68 @@ impl Shell {
         (self.resize_mode.clone(), self.resize_indicator.clone())
     }
 
+    /// Switches the key bindings in use to the named mode of the config,
+    /// or back to the default bindings with `None`.
+    pub fn set_binding_mode(
+        &mut self,
+        mode: Option<String>,
+        evlh: LoopHandle<'static, crate::state::State>,
+    ) {
+        self.binding_mode = mode.map(|name| {
+            let indicator = mode_indicator(name.clone(), evlh, self.theme.clone());
+            (name, indicator)
+        });
+    }
+
+    pub fn binding_mode(&self) -> Option<&str> {
+        self.binding_mode.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    pub fn mode_indicator(&self) -> Option<ModeIndicator> {
+        self.binding_mode
+            .as_ref()
+            .map(|(_, indicator)| indicator.clone())
+    }
+
+    /// Shows a notification on the active output for [`NOTIFICATION_TIMEOUT`],
+    /// replacing any previous one.
+    pub fn show_notification(
+        &mut self,
+        summary: String,
+        body: String,
+        evlh: LoopHandle<'static, crate::state::State>,
+    ) {
+        self.dismiss_notification(&evlh);
+        let element = notification(summary, body, evlh.clone(), self.theme.clone());
+        let timer = Timer::from_duration(NOTIFICATION_TIMEOUT);
+        match evlh.insert_source(timer, |_, _, state| {
+            // the timer is removed whenever the notification is replaced or dismissed,
+            // so it always belongs to the current one
+            state.common.shell.notification = None;
+            schedule_active_render(state);
+            TimeoutAction::Drop
+        }) {
+            Ok(token) => {
+                self.notification = Some((token, element));
+                let _ = evlh.insert_idle(schedule_active_render);
+            }
+            Err(err) => error!(?err, "Failed to show notification"),
+        }
+    }
+
+    pub fn dismiss_notification(&mut self, evlh: &LoopHandle<'static, crate::state::State>) {
+        if let Some((token, _)) = self.notification.take() {
+            evlh.remove(token);
+            let _ = evlh.insert_idle(schedule_active_render);
+        }
+    }
+
+    pub fn notification(&self) -> Option<Notification> {
+        self.notification
+            .as_ref()
+            .map(|(_, element)| element.clone())
+    }
+
     pub fn refresh(&mut self) {
         #[cfg(feature = "debug")]
         puffin::profile_function!();
@@ -1530,6 +2085,9 @@ impl Shell {
             map.cleanup();
         }
 
+        self.scratchpad.hidden.retain(|m| m.alive());
+        self.scratchpad.shown.retain(|m| m.alive());
+
         self.override_redirect_windows.retain(|or| or.alive());
         self.override_redirect_windows
             .iter()
@@ -1539,6 +2097,124 @@ impl Shell {
             .refresh(Some(&self.workspace_state));
     }
 
+    /// Makes `surface` fullscreen on `output`, moving it to the active workspace of `output` if necessary.
+    ///
+    /// Windows that are not mapped yet are fullscreened on `output` once they are.
+    pub fn fullscreen_request(
+        &mut self,
+        surface: &CosmicSurface,
+        output: Output,
+        evlh: LoopHandle<'static, crate::state::State>,
+    ) {
+        let Some(mapped) = self.element_for_surface(surface).cloned() else {
+            if let Some(o) = self
+                .pending_windows
+                .iter_mut()
+                .find(|(s, _, _)| s == surface)
+                .map(|(_, _, o)| o)
+            {
+                *o = Some(output);
+            }
+            return;
+        };
+        let theme = self.theme.clone();
+
+        if let Some(set) = self
+            .workspaces
+            .sets
+            .values_mut()
+            .find(|set| set.sticky_layer.mapped().any(|m| m == &mapped))
+        {
+            let mapped = if mapped
+                .stack_ref()
+                .map(|stack| stack.len() > 1)
+                .unwrap_or(false)
+            {
+                let stack = mapped.stack_ref().unwrap();
+                stack.remove_window(surface);
+                CosmicMapped::from(CosmicWindow::new(surface.clone(), evlh, theme))
+            } else {
+                set.sticky_layer.unmap(&mapped);
+                mapped
+            };
+
+            let workspace_handle = self.active_space(&output).handle.clone();
+            for (window, _) in mapped.windows() {
+                self.toplevel_info_state
+                    .toplevel_enter_output(&window, &output);
+                self.toplevel_info_state
+                    .toplevel_enter_workspace(&window, &workspace_handle);
+            }
+
+            let workspace = self.active_space_mut(&output);
+            workspace.floating_layer.map(mapped.clone(), None);
+            workspace.fullscreen_request(
+                &mapped.active_window(),
+                Some((ManagedLayer::Sticky, workspace_handle)),
+            );
+        } else if let Some(workspace) = self.space_for_mut(&mapped) {
+            if workspace.output == output {
+                workspace.fullscreen_request(surface, None);
+                return;
+            }
+
+            let (mapped, layer) = if mapped
+                .stack_ref()
+                .map(|stack| stack.len() > 1)
+                .unwrap_or(false)
+            {
+                let stack = mapped.stack_ref().unwrap();
+                stack.remove_window(surface);
+                (
+                    CosmicMapped::from(CosmicWindow::new(surface.clone(), evlh, theme)),
+                    if workspace.is_tiled(&mapped) {
+                        ManagedLayer::Tiling
+                    } else {
+                        ManagedLayer::Floating
+                    },
+                )
+            } else {
+                let layer = workspace.unmap(&mapped).unwrap().layer;
+                (mapped, layer)
+            };
+            let handle = workspace.handle.clone();
+
+            let workspace_handle = self.active_space(&output).handle.clone();
+            for (window, _) in mapped.windows() {
+                self.toplevel_info_state
+                    .toplevel_enter_output(&window, &output);
+                self.toplevel_info_state
+                    .toplevel_enter_workspace(&window, &workspace_handle);
+            }
+
+            let workspace = self.active_space_mut(&output);
+            workspace.floating_layer.map(mapped.clone(), None);
+            workspace.fullscreen_request(&mapped.active_window(), Some((layer, handle)));
+        }
+    }
+
+    /// Ends fullscreen of `surface`, moving it back to where it was before, if it still exists.
+    pub fn unfullscreen_request(&mut self, surface: &CosmicSurface) {
+        let Some(mapped) = self.element_for_surface(surface).cloned() else {
+            return;
+        };
+        let Some(workspace) = self.space_for_mut(&mapped) else {
+            return;
+        };
+
+        if let Some((layer, previous_workspace)) = workspace.unfullscreen_request(surface) {
+            let old_handle = workspace.handle.clone();
+            let new_workspace_handle = self
+                .workspaces
+                .space_for_handle(&previous_workspace)
+                .is_some()
+                .then_some(previous_workspace)
+                .unwrap_or(old_handle); // if the workspace doesn't exist anymore, we can still remap on the right layer
+
+            self.remap_unfullscreened_window(mapped, &old_handle, &new_workspace_handle, layer);
+        }
+    }
+
     pub fn remap_unfullscreened_window(
         &mut self,
         mapped: CosmicMapped,
@@ -1587,12 +2263,7 @@ impl Shell {
                     .map(mapped, None)
             }
             ManagedLayer::Floating => new_workspace.floating_layer.map(mapped, None),
-            ManagedLayer::Tiling => new_workspace.tiling_layer.map(
-                mapped,
-                Option::<std::iter::Empty<_>>::None,
-                None,
-                false,
-            ),
+            ManagedLayer::Tiling => new_workspace.map_tiled(mapped, None, None, false),
         };
     }
 
@@ -1627,6 +2298,10 @@ impl Shell {
             _ => false,
         };
 
+        let window_rules = state.common.config.static_conf.window_rule_actions(&window);
+        // windows of the previous session go back to where they were
+        let saved_slot = state.common.shell.layout_restore.find(&window);
+
         let pending_activation = state
             .common
             .shell
@@ -1637,8 +2312,49 @@ impl Shell {
             _ => None,
         };
 
-        let should_be_fullscreen = output.is_some();
-        let mut output = output.unwrap_or_else(|| seat.active_output());
+        let should_be_fullscreen = output.is_some() || window_rules.fullscreen;
+        let mut output = output
+            .or_else(|| {
+                let name = window_rules.output.as_ref()?;
+                state
+                    .common
+                    .shell
+                    .outputs()
+                    .find(|o| &o.name() == name)
+                    .cloned()
+            })
+            .or_else(|| {
+                let (name, _) = saved_slot.as_ref()?.workspace();
+                state
+                    .common
+                    .shell
+                    .outputs()
+                    .find(|o| o.name() == name)
+                    .cloned()
+            })
+            .unwrap_or_else(|| seat.active_output());
+        let saved_handle = saved_slot.as_ref().and_then(|slot| {
+            let (_, idx) = slot.workspace();
+            state
+                .common
+                .shell
+                .workspaces
+                .get(idx, &output)
+                .map(|w| w.handle)
+        });
+        // window rules take precedence over saved layouts and activation tokens
+        let workspace_handle = window_rules
+            .workspace
+            .and_then(|idx| {
+                state
+                    .common
+                    .shell
+                    .workspaces
+                    .get(idx.checked_sub(1)?, &output)
+                    .map(|w| w.handle)
+            })
+            .or(saved_handle)
+            .or(workspace_handle);
 
         // this is beyond stupid, just to make the borrow checker happy
         let workspace = if let Some(handle) = workspace_handle.filter(|handle| {
@@ -1714,7 +2430,7 @@ impl Shell {
             .toplevel_info_state
             .toplevel_enter_workspace(&window, &workspace.handle);
 
-        let mapped = CosmicMapped::from(CosmicWindow::new(
+        let mut mapped = CosmicMapped::from(CosmicWindow::new(
             window.clone(),
             state.common.event_loop_handle.clone(),
             state.common.theme.clone(),
@@ -1726,8 +2442,31 @@ impl Shell {
 
         let workspace_empty = workspace.mapped().next().is_none();
 
-        if layout::should_be_floating(&window) || !workspace.tiling_enabled {
-            workspace.floating_layer.map(mapped.clone(), None);
+        let floating = window_rules
+            .floating
+            .or(saved_slot.as_ref().map(SavedSlot::is_floating))
+            .unwrap_or_else(|| layout::should_be_floating(&window));
+        // window rules or a missing workspace may override the saved slot
+        let restoring = saved_handle == Some(workspace.handle);
+        if floating || !workspace.tiling_enabled {
+            let saved_geometry = match saved_slot {
+                Some(SavedSlot::Floating { geometry, .. }) if restoring => {
+                    state.common.shell.layout_restore.claim(&window);
+                    Some(geometry)
+                }
+                _ => None,
+            };
+            workspace.floating_layer.map_internal(
+                mapped.clone(),
+                window_rules
+                    .position
+                    .map(Point::from)
+                    .or(saved_geometry.map(|geo| geo.loc)),
+                window_rules
+                    .size
+                    .map(Size::from)
+                    .or(saved_geometry.map(|geo| geo.size.as_logical())),
+            );
         } else {
             for mapped in workspace
                 .mapped()
@@ -1738,10 +2477,34 @@ impl Shell {
             {
                 workspace.unmaximize_request(&mapped);
             }
-            let focus_stack = workspace.focus_stack.get(&seat);
-            workspace
-                .tiling_layer
-                .map(mapped.clone(), Some(focus_stack.iter()), None, true);
+
+            if let Some(SavedSlot::Tiled {
+                output: saved_output,
+                idx,
+            }) = saved_slot
+                .as_ref()
+                .filter(|_| restoring && !workspace.scrolling_enabled)
+            {
+                state.common.shell.layout_restore.claim(&window);
+                let restore = &state.common.shell.layout_restore;
+                let stack_mates = restore.stack_mates(saved_output, *idx, &window);
+                match workspace
+                    .tiling_layer
+                    .map_to_stack(mapped.clone(), &stack_mates)
+                {
+                    Some(stack) => mapped = stack,
+                    None => workspace.map_tiled(mapped.clone(), Some(&seat), None, false),
+                }
+                if let Some((saved, windows)) = restore.tiling(saved_output, *idx) {
+                    workspace.tiling_layer.restore(saved, windows);
+                }
+            } else {
+                workspace.map_tiled(mapped.clone(), Some(&seat), None, true);
+                // the window might have been added to a stack
+                if let Some(element) = workspace.element_for_surface(&window) {
+                    mapped = element.clone();
+                }
+            }
         }
 
         if !parent_is_sticky && should_be_fullscreen {
@@ -1752,7 +2515,8 @@ impl Shell {
         let workspace_handle = workspace.handle;
         let workspace_output = workspace.output.clone();
 
-        if parent_is_sticky {
+        let is_sticky = parent_is_sticky || window_rules.sticky;
+        if is_sticky {
             let seats = state.common.seats().cloned().collect::<Vec<_>>();
             state
                 .common
@@ -1760,8 +2524,10 @@ impl Shell {
                 .toggle_sticky(seats.iter(), &seat, &mapped);
         }
 
-        if (workspace_output == seat.active_output() && active_handle == workspace_handle)
-            || parent_is_sticky
+        if window_rules.no_focus {
+            // leave focus where it is
+        } else if (workspace_output == seat.active_output() && active_handle == workspace_handle)
+            || is_sticky
         {
             // TODO: enforce focus stealing prevention by also checking the same rules as for the else case.
             Shell::set_focus(
@@ -1823,6 +2589,7 @@ impl Shell {
         }
         for workspace in state.common.shell.workspaces.spaces_mut() {
             workspace.tiling_layer.recalculate();
+            workspace.scrolling_layer.recalculate();
         }
 
         if wants_focus {
@@ -1915,16 +2682,10 @@ impl Shell {
             .workspaces
             .space_for_handle_mut(to)
             .unwrap(); // checked above
-        let focus_stack = seat.map(|seat| to_workspace.focus_stack.get(&seat));
         if window_state.layer == ManagedLayer::Floating {
             to_workspace.floating_layer.map(mapped.clone(), None);
         } else {
-            to_workspace.tiling_layer.map(
-                mapped.clone(),
-                focus_stack.as_ref().map(|x| x.iter()),
-                direction,
-                true,
-            );
+            to_workspace.map_tiled(mapped.clone(), seat, direction, true);
         }
 
         let focus_target = if let Some(f) = window_state.was_fullscreen {
@@ -2227,7 +2988,7 @@ impl Shell {
                 let pointer = seat.get_pointer().unwrap();
                 let pos = pointer.current_location().as_global();
 
-                let (initial_window_location, layer, workspace_handle) =
+                let (initial_window_location, layer, scrolled, workspace_handle) =
                     if let Some(workspace) = state.common.shell.space_for_mut(&old_mapped) {
                         if workspace
                             .fullscreen
@@ -2254,8 +3015,11 @@ impl Shell {
                                 .to_i32_round();
                         }
 
+                        let scrolled = workspace.scrolling_layer.mapped().any(|m| m == &old_mapped);
                         let layer = if mapped == old_mapped {
-                            let was_floating = workspace.floating_layer.unmap(&mapped);
+                            // scrolled windows are dragged like floating ones
+                            let was_floating = workspace.floating_layer.unmap(&mapped)
+                                || workspace.scrolling_layer.unmap(&mapped);
                             let was_tiled = workspace.tiling_layer.unmap_as_placeholder(&mapped);
                             assert!(was_floating != was_tiled.is_some());
                             was_tiled.is_some()
@@ -2268,7 +3032,7 @@ impl Shell {
                         .then_some(ManagedLayer::Tiling)
                         .unwrap_or(ManagedLayer::Floating);
 
-                        (initial_window_location, layer, workspace.handle)
+                        (initial_window_location, layer, scrolled, workspace.handle)
                     } else if let Some(sticky_layer) = state
                         .common
                         .shell
@@ -2307,6 +3071,7 @@ impl Shell {
                         (
                             initial_window_location,
                             ManagedLayer::Sticky,
+                            false,
                             state.common.shell.active_space(&output).handle,
                         )
                     } else {
@@ -2343,6 +3108,7 @@ impl Shell {
                     initial_window_location,
                     active_hint as u8,
                     layer,
+                    scrolled,
                     release,
                     state.common.event_loop_handle.clone(),
                 );
@@ -2401,7 +3167,9 @@ impl Shell {
             return FocusResult::Handled;
         }
 
-        if workspace.is_tiled(&focused) {
+        if workspace.scrolling_layer.mapped().any(|m| m == &focused) {
+            workspace.scrolling_layer.next_focus(direction, seat)
+        } else if workspace.is_tiled(&focused) {
             let focus_stack = workspace.focus_stack.get(seat);
             let swap_desc = match overview {
                 OverviewMode::Started(Trigger::KeyboardSwap(_, desc), _) => Some(desc),
@@ -2515,10 +3283,44 @@ impl Shell {
             workspace
                 .floating_layer
                 .move_current_element(direction, seat, theme)
+                .or_else(|| {
+                    workspace
+                        .scrolling_layer
+                        .move_current_element(direction, seat)
+                })
                 .or_else(|| workspace.tiling_layer.move_current_node(direction, seat))
         }
     }
 
+    /// Runs `func` with the focused window of `seat` and its layer, if the window is floating or sticky
+    pub fn with_focused_floating<R>(
+        &mut self,
+        seat: &Seat<State>,
+        func: impl FnOnce(&mut FloatingLayout, &CosmicMapped) -> R,
+    ) -> Option<R> {
+        let output = seat.active_output();
+        let workspace = self.active_space(&output);
+        if workspace.fullscreen.is_some() {
+            return None;
+        }
+        let focused = workspace.focus_stack.get(seat).last().cloned()?;
+
+        if let Some(set) = self
+            .workspaces
+            .sets
+            .values_mut()
+            .find(|set| set.sticky_layer.mapped().any(|m| m == &focused))
+        {
+            return Some(func(&mut set.sticky_layer, &focused));
+        }
+        let workspace = self.active_space_mut(&output);
+        workspace
+            .floating_layer
+            .mapped()
+            .any(|m| m == &focused)
+            .then(|| func(&mut workspace.floating_layer, &focused))
+    }
+
     pub fn menu_resize_request(
         state: &mut State,
         mapped: &CosmicMapped,
@@ -2602,6 +3404,229 @@ impl Shell {
         }
     }
 
+    pub fn minimize_request(&mut self, mapped: &CosmicMapped) {
+        if self
+            .workspaces
+            .sets
+            .values()
+            .any(|set| set.sticky_layer.mapped().any(|m| m == mapped))
+        {
+            let was_maximized = self.unmaximize_request(mapped).is_some();
+            let set = self
+                .workspaces
+                .sets
+                .values_mut()
+                .find(|set| set.sticky_layer.mapped().any(|m| m == mapped))
+                .unwrap();
+            let Some(previous_geometry) = set.sticky_layer.element_geometry(mapped) else {
+                return;
+            };
+            set.sticky_layer.unmap(mapped);
+            let active = set.active;
+            let target = minimize_target(
+                &self.toplevel_management_state,
+                mapped,
+                &set.workspaces[active].output,
+            );
+            set.workspaces[active].add_minimized(MinimizedWindow {
+                window: mapped.clone(),
+                previous_state: ManagedState {
+                    layer: ManagedLayer::Sticky,
+                    was_fullscreen: None,
+                },
+                previous_geometry,
+                was_maximized,
+                animation: target.map(|target| (Instant::now(), target)),
+            });
+        } else if let Some(workspace) = self
+            .workspaces
+            .spaces_mut()
+            .find(|w| w.mapped().any(|m| m == mapped))
+        {
+            let target =
+                minimize_target(&self.toplevel_management_state, mapped, &workspace.output);
+            workspace.minimize(mapped, target);
+        }
+    }
+
+    /// Restores the minimized window containing `surface`, returns it if there was any.
+    pub fn unminimize_request(
+        &mut self,
+        surface: &CosmicSurface,
+        seat: &Seat<State>,
+    ) -> Option<CosmicMapped> {
+        let mapped = self.minimized_element(surface)?.clone();
+        let set = self
+            .workspaces
+            .sets
+            .values_mut()
+            .find(|set| set.workspaces.iter().any(|w| w.is_minimized(&mapped)))?;
+        let idx = set
+            .workspaces
+            .iter()
+            .position(|w| w.is_minimized(&mapped))?;
+        let minimized = set.workspaces[idx].unminimize(&mapped, seat)?;
+        if minimized.previous_state.layer == ManagedLayer::Sticky {
+            set.sticky_layer.map_internal(
+                mapped.clone(),
+                Some(minimized.previous_geometry.loc),
+                Some(minimized.previous_geometry.size.as_logical()),
+            );
+        }
+        if minimized.was_maximized {
+            self.maximize_request(&mapped);
+        }
+
+        Some(mapped)
+    }
+
+    pub fn minimized_element(&self, surface: &CosmicSurface) -> Option<&CosmicMapped> {
+        self.workspaces.spaces().find_map(|workspace| {
+            workspace
+                .minimized_windows
+                .iter()
+                .map(|m| &m.window)
+                .find(|mapped| mapped.windows().any(|(w, _)| &w == surface))
+        })
+    }
+
+    /// Hides `mapped` in the scratchpad, it is shown again by [`Shell::toggle_scratchpad`].
+    pub fn move_to_scratchpad<'a>(
+        &mut self,
+        seats: impl Iterator<Item = &'a Seat<State>>,
+        mapped: &CosmicMapped,
+    ) {
+        let _ = self.unmaximize_request(mapped);
+        if let Some(set) = self
+            .workspaces
+            .sets
+            .values_mut()
+            .find(|set| set.sticky_layer.mapped().any(|m| m == mapped))
+        {
+            set.sticky_layer.unmap(mapped);
+            // sticky windows may be in the focus-stack of any workspace of the set
+            let seats = seats.collect::<Vec<_>>();
+            for workspace in set.workspaces.iter_mut() {
+                for seat in seats.iter() {
+                    workspace.focus_stack.get_mut(seat).remove(mapped);
+                }
+            }
+            let handle = set.workspaces[set.active].handle;
+            let output = set.output.clone();
+            for (surface, _) in mapped.windows() {
+                self.toplevel_info_state
+                    .toplevel_leave_workspace(&surface, &handle);
+                self.toplevel_info_state
+                    .toplevel_leave_output(&surface, &output);
+            }
+        } else if let Some(workspace) = self.space_for_mut(mapped) {
+            let handle = workspace.handle;
+            let output = workspace.output.clone();
+            let _ = workspace.unmap(mapped);
+            for (surface, _) in mapped.windows() {
+                self.toplevel_info_state
+                    .toplevel_leave_workspace(&surface, &handle);
+                self.toplevel_info_state
+                    .toplevel_leave_output(&surface, &output);
+            }
+        } else {
+            return;
+        }
+
+        for (surface, _) in mapped.windows() {
+            surface.set_minimized(true);
+        }
+        self.scratchpad.shown.retain(|m| m != mapped);
+        self.scratchpad.hidden.push_back(mapped.clone());
+    }
+
+    /// Hides the focused scratchpad window or shows the next hidden one on the active output.
+    ///
+    /// Returns the window to focus, if any.
+    pub fn toggle_scratchpad<'a>(
+        &mut self,
+        seats: impl Iterator<Item = &'a Seat<State>>,
+        seat: &Seat<State>,
+    ) -> Option<CosmicMapped> {
+        let output = seat.active_output();
+        let workspace = self.active_space(&output);
+        if let Some(focused) = workspace
+            .focus_stack
+            .get(seat)
+            .last()
+            .filter(|m| self.scratchpad.shown.contains(m))
+            .cloned()
+        {
+            self.move_to_scratchpad(seats, &focused);
+            return None;
+        }
+        if let Some(shown) = self
+            .scratchpad
+            .shown
+            .iter()
+            .find(|m| workspace.mapped().any(|w| w == *m))
+        {
+            return Some(shown.clone());
+        }
+
+        let mapped = self.scratchpad.hidden.pop_front()?;
+        self.show_scratchpad_window(mapped.clone(), &output);
+        Some(mapped)
+    }
+
+    /// Shows the hidden scratchpad window containing `surface` on the active output of `seat`.
+    pub fn summon_from_scratchpad(
+        &mut self,
+        surface: &CosmicSurface,
+        seat: &Seat<State>,
+    ) -> Option<CosmicMapped> {
+        let idx = self
+            .scratchpad
+            .hidden
+            .iter()
+            .position(|m| m.windows().any(|(w, _)| &w == surface))?;
+        let mapped = self.scratchpad.hidden.remove(idx)?;
+        self.show_scratchpad_window(mapped.clone(), &seat.active_output());
+        Some(mapped)
+    }
+
+    pub fn scratchpad_element(&self, surface: &CosmicSurface) -> Option<&CosmicMapped> {
+        self.scratchpad
+            .hidden
+            .iter()
+            .find(|m| m.windows().any(|(w, _)| &w == surface))
+    }
+
+    fn show_scratchpad_window(&mut self, mapped: CosmicMapped, output: &Output) {
+        for (surface, _) in mapped.windows() {
+            surface.set_minimized(false);
+        }
+
+        let zone = layer_map_for_output(output).non_exclusive_zone().as_local();
+        let size = mapped
+            .last_geometry
+            .lock()
+            .unwrap()
+            .map(|geo| geo.size)
+            .unwrap_or_else(|| mapped.geometry().size.as_local());
+        let position = zone.loc
+            + Point::from((
+                (zone.size.w - size.w).max(0) / 2,
+                (zone.size.h - size.h).max(0) / 2,
+            ));
+
+        let workspace = self.workspaces.active_mut(output);
+        workspace.floating_layer.map(mapped.clone(), position);
+        let handle = workspace.handle;
+        for (surface, _) in mapped.windows() {
+            self.toplevel_info_state
+                .toplevel_enter_output(&surface, output);
+            self.toplevel_info_state
+                .toplevel_enter_workspace(&surface, &handle);
+        }
+        self.scratchpad.shown.push(mapped);
+    }
+
     pub fn maximize_request(&mut self, mapped: &CosmicMapped) {
         let (original_layer, floating_layer, original_geometry) = if let Some(set) = self
             .workspaces
@@ -2784,6 +3809,8 @@ impl Shell {
                 workspace.tiling_layer.toggle_stacking(window)
             } else if workspace.floating_layer.mapped().any(|w| w == window) {
                 workspace.floating_layer.toggle_stacking(window)
+            } else if workspace.scrolling_layer.mapped().any(|m| m == window) {
+                workspace.scrolling_layer.toggle_stacking(window)
             } else {
                 None
             }
@@ -2812,6 +3839,10 @@ impl Shell {
                 workspace
                     .floating_layer
                     .toggle_stacking_focused(seat, workspace.focus_stack.get_mut(seat))
+            } else if workspace.scrolling_layer.mapped().any(|m| m == &window) {
+                workspace
+                    .scrolling_layer
+                    .toggle_stacking_focused(seat, workspace.focus_stack.get_mut(seat))
             } else {
                 None
             }
@@ -2890,13 +3921,7 @@ impl Shell {
                     workspace.floating_layer.map(mapped.clone(), geometry.loc)
                 }
                 ManagedLayer::Tiling => {
-                    let focus_stack = workspace.focus_stack.get(seat);
-                    workspace.tiling_layer.map(
-                        mapped.clone(),
-                        Some(focus_stack.iter()),
-                        None,
-                        false,
-                    );
+                    workspace.map_tiled(mapped.clone(), Some(seat), None, false);
                 }
                 ManagedLayer::Sticky => unreachable!(),
             }
@@ -2933,10 +3958,43 @@ fn workspace_set_idx<'a>(
     state: &mut WorkspaceUpdateGuard<'a, State>,
     idx: u8,
     output_pos: usize,
-    handle: &WorkspaceHandle,
+    workspace: &Workspace,
 ) {
-    state.set_workspace_name(&handle, format!("{}", idx));
-    state.set_workspace_coordinates(&handle, [Some(idx as u32), Some(output_pos as u32), None]);
+    let handle = &workspace.handle;
+    state.set_workspace_name(
+        handle,
+        workspace.name.clone().unwrap_or_else(|| format!("{}", idx)),
+    );
+    state.set_workspace_coordinates(handle, [Some(idx as u32), Some(output_pos as u32), None]);
+}
+
+/// Where `mapped` should shrink into when minimized on `output`,
+/// as set by a taskbar through the toplevel management protocol.
+fn minimize_target(
+    toplevel_management_state: &ToplevelManagementState,
+    mapped: &CosmicMapped,
+    output: &Output,
+) -> Option<Rectangle<i32, Local>> {
+    let layer_map = layer_map_for_output(output);
+    toplevel_management_state
+        .rectangles(&mapped.active_window())
+        .into_iter()
+        .find_map(|(surface, rectangle)| {
+            let layer = layer_map.layer_for_surface(&surface, WindowSurfaceType::TOPLEVEL)?;
+            let geometry = layer_map.layer_geometry(layer)?;
+            Some(
+                Rectangle::from_loc_and_size(geometry.loc + rectangle.loc, rectangle.size)
+                    .as_local(),
+            )
+        })
+}
+
+/// Redraws the active output, where notifications are shown.
+fn schedule_active_render(state: &mut State) {
+    let output = state.common.last_active_seat().active_output();
+    state
+        .backend
+        .schedule_render(&state.common.event_loop_handle, &output, None);
 }
 
 pub fn check_grab_preconditions(
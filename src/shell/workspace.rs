@@ -4,7 +4,11 @@ use crate::{
         BackdropShader, GlMultiError, GlMultiFrame, GlMultiRenderer,
     },
     shell::{
-        layout::{floating::FloatingLayout, tiling::TilingLayout},
+        layout::{
+            floating::FloatingLayout,
+            scrolling::ScrollingLayout,
+            tiling::{Preselection, TilingLayout},
+        },
         OverviewMode, ANIMATION_DURATION,
     },
     state::State,
@@ -65,20 +69,29 @@ use super::{
     },
     grabs::ResizeEdge,
     layout::tiling::{Data, NodeDesc},
-    CosmicMappedRenderElement, CosmicSurface, ResizeDirection, ResizeMode,
+    CosmicMappedRenderElement, CosmicSurface, Direction, ResizeDirection, ResizeMode,
 };
 
 const FULLSCREEN_ANIMATION_DURATION: Duration = Duration::from_millis(200);
+const MINIMIZE_ANIMATION_DURATION: Duration = Duration::from_millis(200);
 
 #[derive(Debug)]
 pub struct Workspace {
     pub output: Output,
     pub tiling_layer: TilingLayout,
     pub floating_layer: FloatingLayout,
+    pub scrolling_layer: ScrollingLayout,
     pub tiling_enabled: bool,
+    /// Tiled windows are placed in scrollable columns instead of the tiling tree
+    pub scrolling_enabled: bool,
     pub fullscreen: Option<FullscreenSurface>,
+    pub minimized_windows: Vec<MinimizedWindow>,
 
     pub handle: WorkspaceHandle,
+    /// User-defined name, workspaces are named by their position otherwise
+    pub name: Option<String>,
+    /// Whether `name` was given by a client through the workspace protocol instead of the config
+    pub client_named: bool,
     pub focus_stack: FocusStacks,
     pub pending_buffers: Vec<(ScreencopySession, BufferParams)>,
     pub screencopy_sessions: Vec<DropableSession>,
@@ -138,6 +151,17 @@ pub struct ManagedState {
     pub layer: ManagedLayer,
     pub was_fullscreen: Option<FullscreenSurface>,
 }
+/// A window removed from the layouts of a workspace, until it is restored
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinimizedWindow {
+    pub window: CosmicMapped,
+    pub previous_state: ManagedState,
+    pub previous_geometry: Rectangle<i32, Local>,
+    pub was_maximized: bool,
+    /// Start and target of the minimize animation, e.g. the rectangle of a taskbar entry
+    pub animation: Option<(Instant, Rectangle<i32, Local>)>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ManagedLayer {
     Tiling,
@@ -145,7 +169,7 @@ pub enum ManagedLayer {
     Sticky,
 }
 
-#[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction {
     Left,
     Right,
@@ -212,16 +236,22 @@ impl Workspace {
         theme: cosmic::Theme,
     ) -> Workspace {
         let tiling_layer = TilingLayout::new(theme.clone(), &output);
-        let floating_layer = FloatingLayout::new(theme, &output);
+        let floating_layer = FloatingLayout::new(theme.clone(), &output);
+        let scrolling_layer = ScrollingLayout::new(theme, &output);
         let output_name = output.name();
 
         Workspace {
             output,
             tiling_layer,
             floating_layer,
+            scrolling_layer,
             tiling_enabled,
+            scrolling_enabled: false,
             fullscreen: None,
+            minimized_windows: Vec::new(),
             handle,
+            name: None,
+            client_named: false,
             focus_stack: FocusStacks::default(),
             pending_buffers: Vec::new(),
             screencopy_sessions: Vec::new(),
@@ -247,6 +277,8 @@ impl Workspace {
 
         self.floating_layer.refresh();
         self.tiling_layer.refresh();
+        self.scrolling_layer.refresh();
+        self.minimized_windows.retain(|m| m.window.alive());
 
         self.pending_tokens
             .retain(|token| xdg_activation_state.data_for_token(token).is_some());
@@ -262,10 +294,12 @@ impl Workspace {
     pub fn animations_going(&self) -> bool {
         self.tiling_layer.animations_going()
             || self.floating_layer.animations_going()
+            || self.scrolling_layer.animations_going()
             || self
                 .fullscreen
                 .as_ref()
                 .is_some_and(|f| f.start_at.is_some() || f.ended_at.is_some())
+            || self.minimized_windows.iter().any(|m| m.animation.is_some())
             || self.dirty.swap(false, Ordering::SeqCst)
     }
 
@@ -311,8 +345,18 @@ impl Workspace {
             }
         }
 
+        for minimized in &mut self.minimized_windows {
+            if minimized.animation.is_some_and(|(start, _)| {
+                Instant::now().duration_since(start) >= MINIMIZE_ANIMATION_DURATION
+            }) {
+                minimized.animation = None;
+                self.dirty.store(true, Ordering::SeqCst);
+            }
+        }
+
         clients.extend(self.tiling_layer.update_animation_state());
         self.floating_layer.update_animation_state();
+        self.scrolling_layer.update_animation_state();
         clients
     }
 
@@ -338,7 +382,11 @@ impl Workspace {
     ) {
         self.tiling_layer.set_output(output);
         self.floating_layer.set_output(output);
-        for mapped in self.mapped() {
+        self.scrolling_layer.set_output(output);
+        for mapped in self
+            .mapped()
+            .chain(self.minimized_windows.iter().map(|m| &m.window))
+        {
             for (surface, _) in mapped.windows() {
                 toplevel_info.toplevel_leave_output(&surface, &self.output);
                 toplevel_info.toplevel_enter_output(&surface, output);
@@ -377,8 +425,9 @@ impl Workspace {
 
         let was_floating = self.floating_layer.unmap(&mapped);
         let was_tiling = self.tiling_layer.unmap(&mapped);
-        if was_floating || was_tiling {
-            assert!(was_floating != was_tiling);
+        let was_scrolling = self.scrolling_layer.unmap(&mapped);
+        if was_floating || was_tiling || was_scrolling {
+            assert!(was_floating as u8 + was_tiling as u8 + was_scrolling as u8 == 1);
         }
 
         self.focus_stack
@@ -390,7 +439,7 @@ impl Workspace {
                 layer: ManagedLayer::Floating,
                 was_fullscreen,
             })
-        } else if was_tiling {
+        } else if was_tiling || was_scrolling {
             Some(ManagedState {
                 layer: ManagedLayer::Tiling,
                 was_fullscreen,
@@ -400,10 +449,99 @@ impl Workspace {
         }
     }
 
+    /// Removes `mapped` from the layouts, remembering its state for [`Workspace::unminimize`].
+    ///
+    /// If a `target` is given, the window is animated shrinking into it.
+    pub fn minimize(
+        &mut self,
+        mapped: &CosmicMapped,
+        target: Option<Rectangle<i32, Local>>,
+    ) -> bool {
+        let was_maximized = mapped.maximized_state.lock().unwrap().is_some();
+        if was_maximized {
+            let _ = self.unmaximize_request(mapped);
+        }
+        let Some(previous_geometry) = self.element_geometry(mapped) else {
+            return false;
+        };
+        let Some(previous_state) = self.unmap(mapped) else {
+            return false;
+        };
+
+        self.add_minimized(MinimizedWindow {
+            window: mapped.clone(),
+            previous_state,
+            previous_geometry,
+            was_maximized,
+            animation: target.map(|target| (Instant::now(), target)),
+        });
+        true
+    }
+
+    pub fn add_minimized(&mut self, minimized: MinimizedWindow) {
+        for (surface, _) in minimized.window.windows() {
+            surface.set_minimized(true);
+        }
+        self.minimized_windows.push(minimized);
+    }
+
+    /// Maps a minimized window again in the layer it was minimized from.
+    ///
+    /// Windows minimized from the sticky layer are returned without being mapped,
+    /// as are windows that need to be maximized again.
+    pub fn unminimize(
+        &mut self,
+        mapped: &CosmicMapped,
+        seat: &Seat<State>,
+    ) -> Option<MinimizedWindow> {
+        let idx = self
+            .minimized_windows
+            .iter()
+            .position(|m| &m.window == mapped)?;
+        let minimized = self.minimized_windows.remove(idx);
+        for (surface, _) in mapped.windows() {
+            surface.set_minimized(false);
+        }
+
+        match minimized.previous_state.layer {
+            ManagedLayer::Tiling if self.tiling_enabled => {
+                self.map_tiled(mapped.clone(), Some(seat), None, false);
+            }
+            ManagedLayer::Tiling | ManagedLayer::Floating => {
+                self.floating_layer.map_internal(
+                    mapped.clone(),
+                    Some(minimized.previous_geometry.loc),
+                    Some(minimized.previous_geometry.size.as_logical()),
+                );
+            }
+            ManagedLayer::Sticky => {}
+        }
+        if let Some(fullscreen) = minimized.previous_state.was_fullscreen.as_ref() {
+            self.fullscreen_request(&fullscreen.surface, fullscreen.previously.clone());
+        }
+
+        Some(minimized)
+    }
+
+    /// Whether the workspace has neither windows nor pending activations
+    pub fn is_empty(&self) -> bool {
+        self.pending_tokens.is_empty() && self.windows().next().is_none()
+    }
+
+    /// Named workspaces are kept even if empty, dynamic workspaces are only removed if unused.
+    pub fn is_used(&self) -> bool {
+        !self.is_empty() || self.name.is_some()
+    }
+
+    pub fn is_minimized(&self, mapped: &CosmicMapped) -> bool {
+        self.minimized_windows.iter().any(|m| &m.window == mapped)
+    }
+
     pub fn element_for_surface(&self, surface: &CosmicSurface) -> Option<&CosmicMapped> {
         self.floating_layer
             .mapped()
             .chain(self.tiling_layer.mapped().map(|(_, w, _)| w))
+            .chain(self.scrolling_layer.mapped())
             .find(|e| e.windows().any(|(w, _)| &w == surface))
     }
 
@@ -411,6 +549,7 @@ impl Workspace {
         self.floating_layer
             .mapped()
             .chain(self.tiling_layer.mapped().map(|(_, w, _)| w))
+            .chain(self.scrolling_layer.mapped())
             .find(|e| {
                 e.windows()
                     .any(|(w, _)| w.wl_surface().as_ref() == Some(surface))
@@ -427,6 +566,7 @@ impl Workspace {
             .space
             .element_under(location.as_logical())
             .map(|(mapped, p)| (mapped.clone().into(), p.as_local()))
+            .or_else(|| self.scrolling_layer.element_under(location))
             .or_else(|| self.tiling_layer.element_under(location, overview))
             .map(|(m, p)| (m, p.to_global(&self.output)))
     }
@@ -435,10 +575,12 @@ impl Workspace {
         self.floating_layer
             .element_geometry(elem)
             .or_else(|| self.tiling_layer.element_geometry(elem))
+            .or_else(|| self.scrolling_layer.element_geometry(elem))
     }
 
     pub fn recalculate(&mut self) {
         self.tiling_layer.recalculate();
+        self.scrolling_layer.recalculate();
         self.floating_layer.refresh();
     }
 
@@ -454,8 +596,10 @@ impl Workspace {
                     elem.set_geometry(state.original_geometry.to_global(&self.output));
                     elem.configure();
                     self.tiling_layer.recalculate();
+                    self.scrolling_layer.recalculate();
                     self.tiling_layer
                         .element_geometry(&elem)
+                        .or_else(|| self.scrolling_layer.element_geometry(&elem))
                         .map(|geo| geo.size.as_logical())
                 }
                 ManagedLayer::Floating => {
@@ -532,6 +676,7 @@ impl Workspace {
             self.floating_layer.refresh();
             self.tiling_layer.recalculate();
             self.tiling_layer.refresh();
+            self.scrolling_layer.recalculate();
 
             let signal = if let Some(surface) = window.wl_surface() {
                 let signal = Arc::new(AtomicBool::new(false));
@@ -606,11 +751,69 @@ impl Workspace {
             }
         }
 
-        if !self.floating_layer.resize(focused, direction, edge, amount) {
-            self.tiling_layer.resize(focused, direction, edge, amount)
+        self.floating_layer.resize(focused, direction, edge, amount)
+            || self
+                .scrolling_layer
+                .resize(focused, direction, edge, amount)
+            || self.tiling_layer.resize(focused, direction, edge, amount)
+    }
+
+    /// Maps `mapped` into the tiling tree or, if enabled, the scrollable columns
+    pub fn map_tiled(
+        &mut self,
+        mapped: CosmicMapped,
+        seat: Option<&Seat<State>>,
+        direction: Option<Direction>,
+        add_to_stack: bool,
+    ) {
+        let focus_stack = seat.map(|seat| self.focus_stack.get(seat));
+        if self.scrolling_enabled {
+            self.scrolling_layer
+                .map(mapped, focus_stack.as_ref().map(|stack| stack.iter()));
         } else {
-            true
+            self.tiling_layer.map(
+                mapped,
+                focus_stack.as_ref().map(|stack| stack.iter()),
+                direction,
+                add_to_stack,
+            );
+        }
+    }
+
+    pub fn toggle_scrolling(&mut self, seat: &Seat<State>) {
+        let windows = self
+            .tiling_layer
+            .mapped()
+            .map(|(_, m, _)| m.clone())
+            .chain(self.scrolling_layer.mapped().cloned())
+            .collect::<Vec<_>>();
+        for window in &windows {
+            self.tiling_layer.unmap(window);
+            self.scrolling_layer.unmap(window);
+        }
+        self.scrolling_enabled = !self.scrolling_enabled;
+        // map the least recently focused windows first, so focus order matches the new layout
+        let focus_stack = self.focus_stack.get(seat);
+        let mut ordered = focus_stack
+            .iter()
+            .filter(|m| windows.contains(m))
+            .cloned()
+            .collect::<Vec<_>>();
+        ordered.reverse();
+        ordered.extend(windows.into_iter().filter(|m| !ordered.contains(m)));
+        for window in ordered {
+            self.map_tiled(window, Some(seat), None, false);
+        }
+    }
+
+    /// Preselects where the next tiled window goes, see [`TilingLayout::preselect`]
+    pub fn preselect(&mut self, seat: &Seat<State>, preselection: Preselection) {
+        if !self.tiling_enabled || self.scrolling_enabled {
+            return;
         }
+        self.tiling_layer.preselect(seat, preselection);
+        // the hint is only part of the next frame
+        self.dirty.store(true, Ordering::SeqCst);
     }
 
     pub fn toggle_tiling(&mut self, seat: &Seat<State>) {
@@ -625,9 +828,12 @@ impl Workspace {
                 self.tiling_layer.unmap(&window);
                 self.floating_layer.map(window, None);
             }
+            for window in self.scrolling_layer.mapped().cloned().collect::<Vec<_>>() {
+                self.scrolling_layer.unmap(&window);
+                self.floating_layer.map(window, None);
+            }
             self.tiling_enabled = false;
         } else {
-            let focus_stack = self.focus_stack.get(seat);
             for window in self
                 .floating_layer
                 .mapped()
@@ -636,8 +842,7 @@ impl Workspace {
                 .into_iter()
             {
                 self.floating_layer.unmap(&window);
-                self.tiling_layer
-                    .map(window, Some(focus_stack.iter()), None, false)
+                self.map_tiled(window, Some(seat), None, false)
             }
             self.tiling_enabled = true;
         }
@@ -651,11 +856,11 @@ impl Workspace {
             if self.tiling_layer.mapped().any(|(_, m, _)| m == window) {
                 self.tiling_layer.unmap(window);
                 self.floating_layer.map(window.clone(), None);
+            } else if self.scrolling_layer.unmap(window) {
+                self.floating_layer.map(window.clone(), None);
             } else if self.floating_layer.mapped().any(|w| w == window) {
-                let focus_stack = self.focus_stack.get(seat);
                 self.floating_layer.unmap(&window);
-                self.tiling_layer
-                    .map(window.clone(), Some(focus_stack.iter()), None, false)
+                self.map_tiled(window.clone(), Some(seat), None, false)
             }
         }
     }
@@ -671,16 +876,24 @@ impl Workspace {
         self.floating_layer
             .mapped()
             .chain(self.tiling_layer.mapped().map(|(_, w, _)| w))
+            .chain(self.scrolling_layer.mapped())
     }
 
     pub fn outputs(&self) -> impl Iterator<Item = &Output> {
         self.floating_layer.space.outputs()
     }
 
+    /// All windows of the workspace, including minimized ones
     pub fn windows(&self) -> impl Iterator<Item = CosmicSurface> + '_ {
         self.floating_layer
             .windows()
             .chain(self.tiling_layer.windows().map(|(_, w, _)| w))
+            .chain(self.scrolling_layer.windows())
+            .chain(
+                self.minimized_windows
+                    .iter()
+                    .flat_map(|m| m.window.windows().map(|(w, _)| w)),
+            )
     }
 
     pub fn is_fullscreen(&self, mapped: &CosmicMapped) -> bool {
@@ -694,7 +907,9 @@ impl Workspace {
     }
 
     pub fn is_tiled(&self, mapped: &CosmicMapped) -> bool {
-        !self.is_fullscreen(mapped) && self.tiling_layer.mapped().any(|(_, m, _)| m == mapped)
+        !self.is_fullscreen(mapped)
+            && (self.tiling_layer.mapped().any(|(_, m, _)| m == mapped)
+                || self.scrolling_layer.mapped().any(|m| m == mapped))
     }
 
     pub fn node_desc(&self, focus: KeyboardFocusTarget) -> Option<NodeDesc> {
@@ -769,6 +984,53 @@ impl Workspace {
             layer_map.non_exclusive_zone().as_local()
         };
 
+        // windows being minimized
+        for minimized in &self.minimized_windows {
+            let Some((started, target)) = minimized.animation else {
+                continue;
+            };
+            let duration = Instant::now().duration_since(started).as_secs_f64()
+                / MINIMIZE_ANIMATION_DURATION.as_secs_f64();
+            if duration >= 1.0 {
+                continue;
+            }
+
+            let surface = minimized.window.active_window();
+            let bbox = surface.bbox();
+            if bbox.size.w <= 0 || bbox.size.h <= 0 {
+                continue;
+            }
+            let geometry = ease(
+                EaseInOutCubic,
+                EaseRectangle(minimized.previous_geometry),
+                EaseRectangle(target),
+                duration,
+            )
+            .0;
+            let alpha = ease(EaseInOutCubic, 1.0, 0.0, duration);
+
+            let render_loc = geometry
+                .loc
+                .as_logical()
+                .to_physical_precise_round(output_scale);
+            let scale = Scale {
+                x: geometry.size.w as f64 / bbox.size.w as f64,
+                y: geometry.size.h as f64 / bbox.size.h as f64,
+            };
+            let (w_elements, _) = surface.split_render_elements::<R, CosmicWindowRenderElement<R>>(
+                renderer,
+                render_loc,
+                output_scale.into(),
+                alpha,
+            );
+            window_elements.extend(
+                w_elements
+                    .into_iter()
+                    .map(|elem| RescaleRenderElement::from_element(elem, render_loc, scale))
+                    .map(Into::into),
+            );
+        }
+
         if let Some(fullscreen) = self.fullscreen.as_ref() {
             // fullscreen window
             let bbox = fullscreen.surface.bbox().as_local();
@@ -778,6 +1040,7 @@ impl Workspace {
                         self.floating_layer
                             .element_geometry(elem)
                             .or_else(|| self.tiling_layer.element_geometry(elem))
+                            .or_else(|| self.scrolling_layer.element_geometry(elem))
                             .map(|mut geo| {
                                 geo.loc -= elem.geometry().loc.as_local();
                                 geo
@@ -900,6 +1163,18 @@ impl Workspace {
             popup_elements.extend(p_elements.into_iter().map(WorkspaceRenderElement::from));
             window_elements.extend(w_elements.into_iter().map(WorkspaceRenderElement::from));
 
+            // scrolled surfaces
+            let (w_elements, p_elements) = self.scrolling_layer.render::<R>(
+                renderer,
+                focused.as_ref(),
+                resize_indicator.clone(),
+                indicator_thickness,
+                alpha,
+                theme,
+            );
+            popup_elements.extend(p_elements.into_iter().map(WorkspaceRenderElement::from));
+            window_elements.extend(w_elements.into_iter().map(WorkspaceRenderElement::from));
+
             let alpha = match &overview.0 {
                 OverviewMode::Started(_, start) => Some(
                     (Instant::now().duration_since(*start).as_millis() as f64 / 100.0).min(1.0)
@@ -2,22 +2,30 @@
 
 use crate::{
     backend::{
+        headless::HeadlessState,
         kms::{source_node_for_surface, KmsState},
         winit::WinitState,
         x11::X11State,
     },
     config::{Config, OutputConfig},
     input::Devices,
-    shell::{grabs::SeatMoveGrabState, Shell},
+    shell::{
+        grabs::{SeatMoveGrabState, SeatWindowSwitcherState},
+        Shell,
+    },
     utils::prelude::*,
-    wayland::protocols::{
-        drm::WlDrmState,
-        output_configuration::OutputConfigurationState,
-        screencopy::{BufferParams, ScreencopyState, Session as ScreencopySession},
-        workspace::WorkspaceClientState,
+    wayland::{
+        handlers::compositor::client_compositor_state,
+        protocols::{
+            drm::WlDrmState,
+            output_configuration::OutputConfigurationState,
+            screencopy::{BufferParams, ScreencopyState, Session as ScreencopySession},
+            workspace::WorkspaceClientState,
+        },
     },
 };
 use anyhow::Context;
+use cosmic_comp_config::NumlockState;
 use cosmic_protocols::screencopy::v1::server::zcosmic_screencopy_manager_v1::CursorMode;
 use i18n_embed::{
     fluent::{fluent_language_loader, FluentLanguageLoader},
@@ -179,6 +187,7 @@ pub enum BackendData {
     X11(X11State),
     Winit(WinitState),
     Kms(KmsState),
+    Headless(HeadlessState),
     // TODO
     // Wayland(WaylandState),
     Unset,
@@ -212,6 +221,13 @@ impl BackendData {
         }
     }
 
+    pub fn headless(&mut self) -> &mut HeadlessState {
+        match self {
+            BackendData::Headless(ref mut headless_state) => headless_state,
+            _ => unreachable!("Called headless in non headless backend"),
+        }
+    }
+
     pub fn apply_config_for_output(
         &mut self,
         output: &Output,
@@ -226,6 +242,9 @@ impl BackendData {
             }
             BackendData::Winit(ref mut state) => state.apply_config_for_output(output, test_only),
             BackendData::X11(ref mut state) => state.apply_config_for_output(output, test_only),
+            BackendData::Headless(ref mut state) => {
+                state.apply_config_for_output(output, test_only)
+            }
             _ => unreachable!("No backend set when applying output config"),
         };
 
@@ -267,6 +286,7 @@ impl BackendData {
             // Winit has a very strict render-loop and skipping frames breaks atleast the wayland winit-backend.
             // Swapping with damage (which should be empty on these frames) is likely good enough anyway.
             BackendData::X11(ref mut state) => state.schedule_render(output, screencopy),
+            BackendData::Headless(ref mut state) => state.schedule_render(output, screencopy),
             BackendData::Kms(ref mut state) => {
                 if let Err(err) = state.schedule_render(loop_handle, output, None, screencopy) {
                     error!(?err, "Failed to schedule event, are we shutting down?");
@@ -289,6 +309,9 @@ impl BackendData {
             BackendData::X11(ref mut state) => {
                 state.renderer.import_dmabuf(&dmabuf, None)?;
             }
+            BackendData::Headless(ref mut state) => {
+                state.renderer.import_dmabuf(&dmabuf, None)?;
+            }
             _ => unreachable!("No backend set when importing dmabuf"),
         }
         Ok(())
@@ -335,7 +358,8 @@ impl State {
             .with_context(|| "Failed to load languages")
             .unwrap();
 
-        let local_offset = UtcOffset::current_local_offset().expect("No yet multithreaded");
+        // fails once we are multithreaded (e.g. when running tests), fall back to UTC then
+        let local_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
         let clock = Clock::new();
         let config = Config::load(&handle);
         let compositor_state = CompositorState::new::<Self>(dh);
@@ -428,6 +452,23 @@ impl State {
         }
     }
 
+    /// Per-iteration housekeeping of the event loop: advance animations,
+    /// refresh the shell and focus and flush pending events to clients.
+    pub fn refresh(&mut self) {
+        let clients = self.common.shell.update_animations();
+        {
+            let dh = self.common.display_handle.clone();
+            for client in clients.values() {
+                client_compositor_state(&client).blocker_cleared(self, &dh);
+            }
+        }
+        self.common.shell.refresh();
+        Common::refresh_focus(self);
+
+        // send out events
+        let _ = self.common.display_handle.flush_clients();
+    }
+
     pub fn new_client_state(&self) -> ClientState {
         ClientState {
             compositor_client_state: CompositorClientState::default(),
@@ -484,6 +525,11 @@ impl Common {
         if self.seats.is_empty() {
             self.last_active_seat = Some(seat.clone());
         }
+        let seat_clone = seat.clone();
+        self.event_loop_handle.insert_idle(move |state| {
+            let enabled = state.common.config.keyboard.numlock_state == NumlockState::BootOn;
+            state.set_numlock(&seat_clone, enabled);
+        });
         self.seats.push(seat);
     }
 
@@ -613,6 +659,17 @@ impl Common {
                     }
                 }
 
+                if let Some(switcher) = seat.user_data().get::<SeatWindowSwitcherState>() {
+                    if let Some(switcher) = switcher.borrow().as_ref() {
+                        switcher.send_frames(
+                            output,
+                            time,
+                            throttle,
+                            surface_primary_scanout_output,
+                        );
+                    }
+                }
+
                 self.shell
                     .workspaces
                     .sets
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+
+use smithay::input::keyboard::Keysym;
+
+use super::{TestCompositor, KEY_ESC, KEY_G, KEY_LEFT, KEY_LEFTMETA, KEY_RIGHT};
+use crate::{
+    config::{Action, KeyModifiers, KeyPattern},
+    shell::focus::FocusDirection,
+};
+
+fn add_focus_mode(compositor: &mut TestCompositor) {
+    compositor.bind(Keysym::g, Action::EnterMode("focus".into()));
+    compositor.state.common.config.static_conf.modes.insert(
+        "focus".into(),
+        HashMap::from([
+            (
+                KeyPattern::new(KeyModifiers::default(), Some(Keysym::Left)),
+                Action::Focus(FocusDirection::Left),
+            ),
+            (
+                KeyPattern::new(KeyModifiers::default(), Some(Keysym::Right)),
+                Action::Focus(FocusDirection::Right),
+            ),
+        ]),
+    );
+}
+
+#[test]
+fn mode_replaces_bindings() {
+    let mut compositor = TestCompositor::new();
+    add_focus_mode(&mut compositor);
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+
+    // unbound keys are not intercepted outside of the mode
+    compositor.press_keys(&[KEY_LEFT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    assert_eq!(compositor.state.common.shell.binding_mode(), Some("focus"));
+    assert!(compositor.state.common.shell.mode_indicator().is_some());
+
+    compositor.press_keys(&[KEY_LEFT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+
+    // default bindings are inactive in the mode
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_RIGHT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+
+    compositor.press_keys(&[KEY_RIGHT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+}
+
+#[test]
+fn escape_leaves_mode() {
+    let mut compositor = TestCompositor::new();
+    add_focus_mode(&mut compositor);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    assert_eq!(compositor.state.common.shell.binding_mode(), Some("focus"));
+
+    compositor.press_keys(&[KEY_ESC]);
+    assert_eq!(compositor.state.common.shell.binding_mode(), None);
+    assert!(compositor.state.common.shell.mode_indicator().is_none());
+}
+
+#[test]
+fn unknown_mode_is_ignored() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(Keysym::g, Action::EnterMode("missing".into()));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    assert_eq!(compositor.state.common.shell.binding_mode(), None);
+}
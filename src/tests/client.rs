@@ -0,0 +1,453 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A minimal scripted wayland client.
+//!
+//! Surfaces are drawn as soon as they are configured, using a transparent shm buffer
+//! of the configured size (or the size requested on creation, if the compositor leaves
+//! the choice to the client).
+
+use std::{
+    os::{
+        fd::{AsFd, FromRawFd, OwnedFd},
+        unix::net::UnixStream,
+    },
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use wayland_client::{
+    delegate_noop,
+    protocol::{
+        wl_buffer::WlBuffer, wl_callback::WlCallback, wl_compositor::WlCompositor,
+        wl_display::WlDisplay, wl_registry::WlRegistry, wl_shm, wl_shm::WlShm,
+        wl_shm_pool::WlShmPool, wl_surface::WlSurface,
+    },
+    Connection, Dispatch, EventQueue, QueueHandle, WaylandError,
+};
+use wayland_protocols::xdg::shell::client::{
+    xdg_popup::{self, XdgPopup},
+    xdg_positioner::{self, XdgPositioner},
+    xdg_surface::{self, XdgSurface},
+    xdg_toplevel::{self, XdgToplevel},
+    xdg_wm_base::{self, XdgWmBase},
+};
+use wayland_protocols_wlr::layer_shell::v1::client::{
+    zwlr_layer_shell_v1::{self, ZwlrLayerShellV1},
+    zwlr_layer_surface_v1::{self, ZwlrLayerSurfaceV1},
+};
+
+pub use zwlr_layer_shell_v1::Layer;
+pub use zwlr_layer_surface_v1::Anchor;
+
+/// Handle to a surface created by a [`TestClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceId(usize);
+
+#[derive(Debug)]
+enum Role {
+    Toplevel(XdgSurface, XdgToplevel),
+    Popup(XdgSurface, XdgPopup),
+    Layer(ZwlrLayerSurfaceV1),
+}
+
+#[derive(Debug)]
+struct Surface {
+    wl_surface: WlSurface,
+    role: Role,
+    default_size: (i32, i32),
+    pending_size: (i32, i32),
+    size: Option<(i32, i32)>,
+    closed: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ClientState {
+    compositor: Option<WlCompositor>,
+    shm: Option<WlShm>,
+    wm_base: Option<XdgWmBase>,
+    layer_shell: Option<ZwlrLayerShellV1>,
+    surfaces: Vec<Surface>,
+}
+
+pub struct TestClient {
+    connection: Connection,
+    display: WlDisplay,
+    queue: EventQueue<ClientState>,
+    qh: QueueHandle<ClientState>,
+    state: ClientState,
+}
+
+impl TestClient {
+    pub fn new(stream: UnixStream) -> TestClient {
+        stream
+            .set_nonblocking(true)
+            .expect("Failed to set client socket non-blocking");
+        let connection = Connection::from_socket(stream).expect("Failed to connect client");
+        let display = connection.display();
+        let queue = connection.new_event_queue();
+        let qh = queue.handle();
+        display.get_registry(&qh, ());
+
+        TestClient {
+            connection,
+            display,
+            queue,
+            qh,
+            state: ClientState::default(),
+        }
+    }
+
+    pub(super) fn sync(&self) -> Arc<AtomicBool> {
+        let done = Arc::new(AtomicBool::new(false));
+        self.display.sync(&self.qh, done.clone());
+        done
+    }
+
+    pub(super) fn is_done(&self, sync: &Arc<AtomicBool>) -> bool {
+        sync.load(Ordering::SeqCst)
+    }
+
+    pub(super) fn flush(&mut self) {
+        self.connection.flush().expect("Failed to flush client");
+    }
+
+    /// Reads everything the compositor sent so far and dispatches it, without blocking.
+    pub(super) fn dispatch(&mut self) {
+        if let Some(guard) = self.queue.prepare_read() {
+            match guard.read() {
+                Ok(_) => {}
+                Err(WaylandError::Io(err)) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(err) => panic!("Client connection failed: {}", err),
+            }
+        }
+        self.queue
+            .dispatch_pending(&mut self.state)
+            .expect("Failed to dispatch client events");
+    }
+
+    fn create_surface(
+        &mut self,
+        role: impl FnOnce(&mut Self, &WlSurface) -> Role,
+        size: (i32, i32),
+    ) -> SurfaceId {
+        let id = SurfaceId(self.state.surfaces.len());
+        let wl_surface = self
+            .state
+            .compositor
+            .as_ref()
+            .expect("Compositor does not advertise wl_compositor")
+            .create_surface(&self.qh, ());
+        let role = role(self, &wl_surface);
+        // initial commit without a buffer, we draw once configured
+        wl_surface.commit();
+        self.state.surfaces.push(Surface {
+            wl_surface,
+            role,
+            default_size: size,
+            pending_size: (0, 0),
+            size: None,
+            closed: false,
+        });
+        id
+    }
+
+    pub fn create_toplevel(&mut self, title: &str) -> SurfaceId {
+        let id = self.state.surfaces.len();
+        let title = title.to_string();
+        self.create_surface(
+            move |client, wl_surface| {
+                let xdg_surface = client
+                    .state
+                    .wm_base
+                    .as_ref()
+                    .expect("Compositor does not advertise xdg_wm_base")
+                    .get_xdg_surface(wl_surface, &client.qh, id);
+                let toplevel = xdg_surface.get_toplevel(&client.qh, id);
+                toplevel.set_title(title.clone());
+                toplevel.set_app_id(title);
+                Role::Toplevel(xdg_surface, toplevel)
+            },
+            (200, 200),
+        )
+    }
+
+    /// Creates a popup of the given size, anchored to `anchor` in the parents surface coordinates.
+    pub fn create_popup(
+        &mut self,
+        parent: SurfaceId,
+        anchor: (i32, i32, i32, i32),
+        size: (i32, i32),
+    ) -> SurfaceId {
+        let id = self.state.surfaces.len();
+        let parent = match &self.state.surfaces[parent.0].role {
+            Role::Toplevel(xdg_surface, _) | Role::Popup(xdg_surface, _) => xdg_surface.clone(),
+            Role::Layer(_) => panic!("Layer surface popups are not supported"),
+        };
+        self.create_surface(
+            move |client, wl_surface| {
+                let wm_base = client.state.wm_base.as_ref().unwrap();
+                let positioner = wm_base.create_positioner(&client.qh, ());
+                positioner.set_size(size.0, size.1);
+                positioner.set_anchor_rect(anchor.0, anchor.1, anchor.2, anchor.3);
+                positioner.set_anchor(xdg_positioner::Anchor::BottomLeft);
+                positioner.set_gravity(xdg_positioner::Gravity::BottomRight);
+                let xdg_surface = wm_base.get_xdg_surface(wl_surface, &client.qh, id);
+                let popup = xdg_surface.get_popup(Some(&parent), &positioner, &client.qh, id);
+                positioner.destroy();
+                Role::Popup(xdg_surface, popup)
+            },
+            size,
+        )
+    }
+
+    pub fn create_layer_surface(
+        &mut self,
+        layer: Layer,
+        anchor: Anchor,
+        exclusive_zone: i32,
+        size: (i32, i32),
+    ) -> SurfaceId {
+        let id = self.state.surfaces.len();
+        self.create_surface(
+            move |client, wl_surface| {
+                let layer_surface = client
+                    .state
+                    .layer_shell
+                    .as_ref()
+                    .expect("Compositor does not advertise zwlr_layer_shell_v1")
+                    .get_layer_surface(
+                        wl_surface,
+                        None,
+                        layer,
+                        String::from("test"),
+                        &client.qh,
+                        id,
+                    );
+                layer_surface.set_anchor(anchor);
+                layer_surface.set_size(size.0 as u32, size.1 as u32);
+                layer_surface.set_exclusive_zone(exclusive_zone);
+                Role::Layer(layer_surface)
+            },
+            size,
+        )
+    }
+
+    /// Destroys the surface and its role objects, unmapping it.
+    pub fn destroy(&mut self, id: SurfaceId) {
+        let surface = &mut self.state.surfaces[id.0];
+        match &surface.role {
+            Role::Toplevel(xdg_surface, toplevel) => {
+                toplevel.destroy();
+                xdg_surface.destroy();
+            }
+            Role::Popup(xdg_surface, popup) => {
+                popup.destroy();
+                xdg_surface.destroy();
+            }
+            Role::Layer(layer_surface) => layer_surface.destroy(),
+        }
+        surface.wl_surface.destroy();
+        surface.closed = true;
+    }
+
+    /// Size of the last buffer attached to the surface, `None` if it was never configured.
+    pub fn size(&self, id: SurfaceId) -> Option<(i32, i32)> {
+        self.state.surfaces[id.0].size
+    }
+
+    /// Whether the compositor asked the surface to close or it was destroyed.
+    pub fn is_closed(&self, id: SurfaceId) -> bool {
+        self.state.surfaces[id.0].closed
+    }
+}
+
+impl ClientState {
+    fn draw(&mut self, id: usize, qh: &QueueHandle<ClientState>) {
+        let surface = &mut self.surfaces[id];
+        let (w, h) = match surface.pending_size {
+            (w, h) if w > 0 && h > 0 => (w, h),
+            (0, 0) => surface.default_size,
+            (w, 0) => (w, surface.default_size.1),
+            (_, h) => (surface.default_size.0, h),
+        };
+
+        let shm = self
+            .shm
+            .as_ref()
+            .expect("Compositor does not advertise wl_shm");
+        let len = w * h * 4;
+        // SAFETY: memfd_create returns a new file descriptor we take ownership of
+        let fd = unsafe {
+            let fd = libc::memfd_create(
+                b"test-buffer\0".as_ptr() as *const libc::c_char,
+                libc::MFD_CLOEXEC,
+            );
+            assert!(fd >= 0, "Failed to create memfd");
+            assert_eq!(libc::ftruncate(fd, len as libc::off_t), 0);
+            OwnedFd::from_raw_fd(fd)
+        };
+        let pool = shm.create_pool(fd.as_fd(), len, qh, ());
+        let buffer = pool.create_buffer(0, w, h, w * 4, wl_shm::Format::Argb8888, qh, ());
+        pool.destroy();
+
+        surface.wl_surface.attach(Some(&buffer), 0, 0);
+        surface.wl_surface.damage_buffer(0, 0, w, h);
+        surface.wl_surface.commit();
+        surface.size = Some((w, h));
+    }
+}
+
+impl Dispatch<WlRegistry, ()> for ClientState {
+    fn event(
+        state: &mut Self,
+        registry: &WlRegistry,
+        event: <WlRegistry as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        use wayland_client::protocol::wl_registry::Event;
+
+        if let Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            match interface.as_str() {
+                "wl_compositor" => {
+                    state.compositor = Some(registry.bind(name, version.min(5), qh, ()));
+                }
+                "wl_shm" => state.shm = Some(registry.bind(name, 1, qh, ())),
+                "xdg_wm_base" => {
+                    state.wm_base = Some(registry.bind(name, version.min(5), qh, ()));
+                }
+                "zwlr_layer_shell_v1" => {
+                    state.layer_shell = Some(registry.bind(name, version.min(4), qh, ()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Dispatch<WlCallback, Arc<AtomicBool>> for ClientState {
+    fn event(
+        _: &mut Self,
+        _: &WlCallback,
+        _: <WlCallback as wayland_client::Proxy>::Event,
+        done: &Arc<AtomicBool>,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        done.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Dispatch<XdgWmBase, ()> for ClientState {
+    fn event(
+        _: &mut Self,
+        wm_base: &XdgWmBase,
+        event: xdg_wm_base::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let xdg_wm_base::Event::Ping { serial } = event {
+            wm_base.pong(serial);
+        }
+    }
+}
+
+impl Dispatch<XdgSurface, usize> for ClientState {
+    fn event(
+        state: &mut Self,
+        xdg_surface: &XdgSurface,
+        event: xdg_surface::Event,
+        id: &usize,
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let xdg_surface::Event::Configure { serial } = event {
+            xdg_surface.ack_configure(serial);
+            if !state.surfaces[*id].closed {
+                state.draw(*id, qh);
+            }
+        }
+    }
+}
+
+impl Dispatch<XdgToplevel, usize> for ClientState {
+    fn event(
+        state: &mut Self,
+        _: &XdgToplevel,
+        event: xdg_toplevel::Event,
+        id: &usize,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            xdg_toplevel::Event::Configure { width, height, .. } => {
+                state.surfaces[*id].pending_size = (width, height);
+            }
+            xdg_toplevel::Event::Close => state.surfaces[*id].closed = true,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<XdgPopup, usize> for ClientState {
+    fn event(
+        state: &mut Self,
+        _: &XdgPopup,
+        event: xdg_popup::Event,
+        id: &usize,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            xdg_popup::Event::Configure { width, height, .. } => {
+                state.surfaces[*id].pending_size = (width, height);
+            }
+            xdg_popup::Event::PopupDone => state.surfaces[*id].closed = true,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZwlrLayerSurfaceV1, usize> for ClientState {
+    fn event(
+        state: &mut Self,
+        layer_surface: &ZwlrLayerSurfaceV1,
+        event: zwlr_layer_surface_v1::Event,
+        id: &usize,
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_layer_surface_v1::Event::Configure {
+                serial,
+                width,
+                height,
+            } => {
+                layer_surface.ack_configure(serial);
+                state.surfaces[*id].pending_size = (width as i32, height as i32);
+                if !state.surfaces[*id].closed {
+                    state.draw(*id, qh);
+                }
+            }
+            zwlr_layer_surface_v1::Event::Closed => state.surfaces[*id].closed = true,
+            _ => {}
+        }
+    }
+}
+
+delegate_noop!(ClientState: WlCompositor);
+delegate_noop!(ClientState: WlShmPool);
+delegate_noop!(ClientState: XdgPositioner);
+delegate_noop!(ClientState: ZwlrLayerShellV1);
+delegate_noop!(ClientState: ignore WlShm);
+delegate_noop!(ClientState: ignore WlBuffer);
+delegate_noop!(ClientState: ignore WlSurface);
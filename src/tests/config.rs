@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::{Path, PathBuf};
+
+use smithay::input::keyboard::Keysym;
+
+use super::TestCompositor;
+use crate::config::{self, Action, ConfigError, KeyModifier, KeyPattern, StaticConfig};
+
+fn config_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "cosmic-comp-config-{}-{}.ron",
+        std::process::id(),
+        name
+    ));
+    std::fs::write(&path, contents).expect("Failed to write config file");
+    path
+}
+
+const VALID: &str = r#"(
+    key_bindings: {
+        (modifiers: [Super], key: "g"): Spawn("valid"),
+    },
+    tiling_enabled: false,
+    data_control_enabled: false,
+)"#;
+
+const MALFORMED: &str = r#"(
+    key_bindings: {
+        (modifiers: [Super], key: "g"): Spawn("malformed"),
+    }
+    tiling_enabled: false,
+    data_control_enabled: false,
+)"#;
+
+#[test]
+fn default_config_is_valid() {
+    StaticConfig::parse(Path::new("config.ron")).expect("Malformed default config");
+}
+
+#[test]
+fn parse_errors_have_a_position() {
+    let path = config_file("position", MALFORMED);
+    match StaticConfig::parse(&path) {
+        Err(ConfigError::Parse(_, err)) => {
+            assert_eq!(err.position.line, 5);
+            assert!(ConfigError::Parse(path.clone(), err)
+                .to_string()
+                .starts_with(&format!("{}:5:", path.display())));
+        }
+        result => panic!("Unexpected result: {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn malformed_reload_keeps_config() {
+    let mut compositor = TestCompositor::new();
+    let pattern = KeyPattern::new(KeyModifier::Super, Some(Keysym::g));
+
+    let path = config_file("reload", VALID);
+    config::reload_static(&mut compositor.state, Some(path.clone()));
+    assert_eq!(
+        compositor.state.common.config.static_conf.key_bindings[&pattern],
+        Action::Spawn("valid".into())
+    );
+    assert!(compositor.state.common.shell.notification().is_none());
+
+    std::fs::write(&path, MALFORMED).unwrap();
+    config::reload_static(&mut compositor.state, Some(path.clone()));
+    assert_eq!(
+        compositor.state.common.config.static_conf.key_bindings[&pattern],
+        Action::Spawn("valid".into())
+    );
+    assert!(compositor.state.common.shell.notification().is_some());
+
+    // fixing the file dismisses the error
+    std::fs::write(&path, VALID).unwrap();
+    config::reload_static(&mut compositor.state, Some(path));
+    assert!(compositor.state.common.shell.notification().is_none());
+}
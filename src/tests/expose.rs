@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use smithay::input::keyboard::Keysym;
+
+use super::{client::TestClient, TestCompositor, KEY_E, KEY_ENTER, KEY_ESC, KEY_F, KEY_LEFTMETA};
+use crate::{config::Action, utils::prelude::*};
+
+fn expose(compositor: &mut TestCompositor) -> TestClient {
+    compositor.bind(Keysym::e, Action::Expose);
+    compositor.set_tiling(true);
+    compositor.map_windows(&["first", "second", "third"])
+}
+
+#[test]
+fn click_focuses_window() {
+    let mut compositor = TestCompositor::new();
+    let mut client = expose(&mut compositor);
+    assert_eq!(compositor.focused_title().as_deref(), Some("third"));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_E]);
+    assert!(compositor.state.common.shell.expose_active());
+
+    let output = compositor.output(0);
+    let slots = compositor.state.common.shell.expose_layout(&output);
+    assert_eq!(slots.len(), 3);
+    for (i, a) in slots.iter().enumerate() {
+        assert!(a.slot.size.w <= a.geometry.size.w);
+        for b in &slots[i + 1..] {
+            assert!(!a.slot.overlaps(b.slot));
+        }
+    }
+
+    let slot = slots
+        .iter()
+        .find(|slot| slot.window.active_window().title() == "first")
+        .unwrap()
+        .slot
+        .to_global(&output);
+    compositor.click((slot.loc + slot.size.downscale(2).to_point()).to_f64());
+    compositor.settle(&mut client);
+    assert!(!compositor.state.common.shell.expose_active());
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn typing_filters_windows() {
+    let mut compositor = TestCompositor::new();
+    let mut client = expose(&mut compositor);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_E]);
+    compositor.press_keys(&[KEY_F]);
+    let selected = compositor.state.common.shell.expose_selected().unwrap();
+    assert_eq!(selected.active_window().title(), "first");
+
+    compositor.press_keys(&[KEY_ENTER]);
+    compositor.settle(&mut client);
+    assert!(!compositor.state.common.shell.expose_active());
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn escape_closes() {
+    let mut compositor = TestCompositor::new();
+    let mut client = expose(&mut compositor);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_E]);
+    assert!(compositor.state.common.shell.expose_active());
+    compositor.press_keys(&[KEY_ESC]);
+    compositor.settle(&mut client);
+    assert!(!compositor.state.common.shell.expose_active());
+    assert_eq!(compositor.focused_title().as_deref(), Some("third"));
+}
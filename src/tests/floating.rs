@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use smithay::{
+    input::keyboard::Keysym,
+    utils::{Logical, Point, Rectangle},
+};
+
+use super::{
+    TestCompositor, KEY_DOWN, KEY_G, KEY_H, KEY_LEFTALT, KEY_LEFTCTRL, KEY_LEFTMETA, KEY_LEFTSHIFT,
+    KEY_RIGHT,
+};
+use crate::{
+    config::{Action, KeyModifier, KeyModifiers, KeyPattern},
+    shell::{grabs::snap_offset, layout::floating::TiledCorners, Direction},
+};
+
+fn rect(x: i32, y: i32, w: i32, h: i32) -> Rectangle<i32, Logical> {
+    Rectangle::from_loc_and_size((x, y), (w, h))
+}
+
+#[test]
+fn snap_to_output_edges() {
+    let output = [rect(0, 0, 1920, 1080)];
+    let offset = snap_offset(rect(10, 500, 400, 300), &output, &[], 16, None);
+    assert_eq!(offset, Point::from((-10, 0)));
+
+    let offset = snap_offset(rect(1510, 770, 400, 300), &output, &[], 16, None);
+    assert_eq!(offset, Point::from((10, 10)));
+
+    // too far away
+    let offset = snap_offset(rect(40, 40, 400, 300), &output, &[], 16, None);
+    assert_eq!(offset, Point::from((0, 0)));
+}
+
+#[test]
+fn snap_to_exclusive_zones() {
+    // a panel reserving the top 32 pixels
+    let areas = [rect(0, 0, 1920, 1080), rect(0, 32, 1920, 1048)];
+    let offset = snap_offset(rect(500, 40, 400, 300), &areas, &[], 16, None);
+    assert_eq!(offset, Point::from((0, -8)));
+}
+
+#[test]
+fn snap_to_adjacent_windows() {
+    let output = [rect(0, 0, 1920, 1080)];
+    let windows = [rect(100, 100, 400, 300)];
+    let offset = snap_offset(rect(510, 150, 400, 300), &output, &windows, 16, None);
+    assert_eq!(offset, Point::from((-10, 0)));
+
+    // windows far below don't attract
+    let offset = snap_offset(rect(510, 600, 400, 300), &output, &windows, 16, None);
+    assert_eq!(offset, Point::from((0, 0)));
+}
+
+#[test]
+fn snap_to_grid() {
+    let output = [rect(0, 0, 1920, 1080)];
+    let grid = Some((Point::from((0, 0)), 50));
+    let offset = snap_offset(rect(130, 170, 400, 300), &output, &[], 16, grid);
+    assert_eq!(offset, Point::from((20, -20)));
+
+    // edges win over the grid
+    let offset = snap_offset(rect(8, 170, 400, 300), &output, &[], 16, grid);
+    assert_eq!(offset, Point::from((-8, -20)));
+}
+
+fn bind_floating(compositor: &mut TestCompositor) {
+    compositor.bind_pattern(
+        KeyPattern::new(KeyModifier::Super | KeyModifier::Alt, Some(Keysym::Right)),
+        Action::MoveFloating(Direction::Right),
+    );
+    compositor.bind_pattern(
+        KeyPattern::new(KeyModifier::Super | KeyModifier::Ctrl, Some(Keysym::Down)),
+        Action::ResizeFloating(Direction::Down),
+    );
+    compositor.bind(Keysym::h, Action::TileFloating(TiledCorners::Left));
+    compositor.bind(Keysym::g, Action::RestoreFloating);
+    compositor.set_tiling(false);
+}
+
+#[test]
+fn keyboard_move_by_step() {
+    let mut compositor = TestCompositor::new();
+    bind_floating(&mut compositor);
+    let mut client = compositor.map_windows(&["window"]);
+    let (_, start) = compositor.window("window").unwrap();
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_LEFTALT, KEY_RIGHT]);
+    compositor.settle(&mut client);
+    let (_, moved) = compositor.window("window").unwrap();
+    assert_eq!(moved.loc - start.loc, Point::from((20, 0)));
+    assert_eq!(moved.size, start.size);
+
+    // shift uses the larger step
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_LEFTALT, KEY_LEFTSHIFT, KEY_RIGHT]);
+    compositor.settle(&mut client);
+    let (_, moved) = compositor.window("window").unwrap();
+    assert_eq!(moved.loc - start.loc, Point::from((120, 0)));
+}
+
+#[test]
+fn keyboard_resize_by_step() {
+    let mut compositor = TestCompositor::new();
+    bind_floating(&mut compositor);
+    let mut client = compositor.map_windows(&["window"]);
+    let (_, start) = compositor.window("window").unwrap();
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_LEFTCTRL, KEY_DOWN]);
+    compositor.settle(&mut client);
+    let (_, resized) = compositor.window("window").unwrap();
+    assert_eq!(resized.loc, start.loc);
+    assert_eq!(resized.size.w, start.size.w);
+    assert_eq!(resized.size.h, start.size.h + 20);
+}
+
+#[test]
+fn exact_binding_wins_over_larger_step() {
+    let mut compositor = TestCompositor::new();
+    bind_floating(&mut compositor);
+    compositor.bind_pattern(
+        KeyPattern::new(
+            KeyModifiers {
+                logo: true,
+                ctrl: true,
+                shift: true,
+                ..Default::default()
+            },
+            Some(Keysym::Down),
+        ),
+        Action::TileFloating(TiledCorners::Left),
+    );
+    let mut client = compositor.map_windows(&["window"]);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_LEFTCTRL, KEY_LEFTSHIFT, KEY_DOWN]);
+    compositor.settle(&mut client);
+    let (_, tiled) = compositor.window("window").unwrap();
+    assert_eq!(tiled.size.w, compositor.output_geometry(0).size.w / 2);
+}
+
+#[test]
+fn restore_after_tiling() {
+    let mut compositor = TestCompositor::new();
+    bind_floating(&mut compositor);
+    let mut client = compositor.map_windows(&["window"]);
+    let (_, start) = compositor.window("window").unwrap();
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    let (_, tiled) = compositor.window("window").unwrap();
+    assert_ne!(tiled, start);
+    assert_eq!(tiled.size.w, compositor.output_geometry(0).size.w / 2);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+    let (_, restored) = compositor.window("window").unwrap();
+    assert_eq!(restored, start);
+}
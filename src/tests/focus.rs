@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use super::{TestCompositor, KEY_LEFT, KEY_LEFTMETA, KEY_RIGHT};
+use crate::{config::Action, shell::focus::FocusDirection, utils::prelude::*};
+use smithay::{
+    input::keyboard::Keysym,
+    utils::{Point, Rectangle},
+};
+
+fn center(rect: Rectangle<i32, Global>) -> Point<f64, Global> {
+    rect.to_f64().loc + rect.to_f64().size.downscale(2.0).to_point()
+}
+
+#[test]
+fn new_windows_get_focus() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    let mut client = compositor.connect();
+
+    client.create_toplevel("first");
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+
+    client.create_toplevel("second");
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+}
+
+#[test]
+fn click_to_focus() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    let mut client = compositor.connect();
+
+    client.create_toplevel("first");
+    client.create_toplevel("second");
+    compositor.settle(&mut client);
+
+    let (_, first_geo) = compositor.window("first").unwrap();
+    compositor.click(center(first_geo));
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+
+    // neither moving nor scrolling changes focus
+    compositor.pointer_motion((10.0, 10.0));
+    compositor.scroll(1.0);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn keyboard_focus_navigation() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(Keysym::Left, Action::Focus(FocusDirection::Left));
+    compositor.bind(Keysym::Right, Action::Focus(FocusDirection::Right));
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_LEFT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_RIGHT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+}
+
+#[test]
+fn popups_are_configured() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    let mut client = compositor.connect();
+
+    let window = client.create_toplevel("first");
+    compositor.settle(&mut client);
+    let popup = client.create_popup(window, (10, 10, 1, 1), (100, 50));
+    compositor.settle(&mut client);
+
+    assert_eq!(client.size(popup), Some((100, 50)));
+    assert!(!client.is_closed(popup));
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn focus_follows_cursor() {
+    let mut compositor = TestCompositor::new();
+    let focus = &mut compositor.state.common.config.focus;
+    focus.focus_follows_cursor = true;
+    focus.focus_follows_cursor_delay = 0;
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    let (_, first_geo) = compositor.window("first").unwrap();
+    compositor.pointer_motion_to(center(first_geo));
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn cursor_follows_focus() {
+    let mut compositor = TestCompositor::new();
+    compositor.state.common.config.focus.cursor_follows_focus = true;
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    let (_, second_geo) = compositor.window("second").unwrap();
+    compositor.pointer_motion_to(center(second_geo));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_LEFT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+    let (_, first_geo) = compositor.window("first").unwrap();
+    let pointer = compositor
+        .state
+        .common
+        .last_active_seat()
+        .get_pointer()
+        .unwrap()
+        .current_location()
+        .as_global();
+    assert_eq!(pointer.to_i32_round(), center(first_geo).to_i32_round());
+}
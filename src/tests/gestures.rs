@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+
+use cosmic_comp_config::workspace::WorkspaceLayout;
+
+use super::TestCompositor;
+use crate::config::{Action, Gesture, PinchDirection};
+
+/// Finger movement towards the next workspace
+fn next_workspace_delta(compositor: &TestCompositor, distance: f64) -> (f64, f64) {
+    match compositor.state.common.config.workspace.workspace_layout {
+        WorkspaceLayout::Horizontal => (-distance, 0.0),
+        WorkspaceLayout::Vertical => (0.0, -distance),
+    }
+}
+
+fn active_workspace(compositor: &TestCompositor) -> usize {
+    let output = compositor.output(0);
+    compositor
+        .state
+        .common
+        .shell
+        .workspaces
+        .active_num(&output)
+        .1
+}
+
+/// Maps a window, so a second workspace exists to switch to.
+fn with_second_workspace() -> (TestCompositor, super::client::TestClient) {
+    let mut compositor = TestCompositor::new();
+    let client = compositor.map_windows(&["first"]);
+    (compositor, client)
+}
+
+#[test]
+fn swipe_switches_workspace() {
+    let (mut compositor, mut client) = with_second_workspace();
+
+    let delta = next_workspace_delta(&compositor, 400.0);
+    compositor.swipe(4, delta);
+    compositor.settle(&mut client);
+    assert_eq!(active_workspace(&compositor), 1);
+
+    let delta = next_workspace_delta(&compositor, -400.0);
+    compositor.swipe(4, delta);
+    compositor.settle(&mut client);
+    assert_eq!(active_workspace(&compositor), 0);
+}
+
+#[test]
+fn swipe_is_tracked() {
+    let (mut compositor, _client) = with_second_workspace();
+    let output = compositor.output(0);
+
+    compositor.swipe_begin(4);
+    let delta = next_workspace_delta(&compositor, 20.0);
+    compositor.swipe_update(delta);
+    let delta = next_workspace_delta(&compositor, 60.0);
+    compositor.swipe_update(delta);
+
+    // shown, but not activated yet
+    match compositor.state.common.shell.workspaces.gesture(&output) {
+        Some((1, progress)) => assert!(progress > 0.0 && progress < 0.5),
+        gesture => panic!("Unexpected workspace gesture: {:?}", gesture),
+    }
+    assert_eq!(active_workspace(&compositor), 0);
+
+    // not far enough, returns to the first workspace
+    compositor.swipe_end(false);
+    assert_eq!(active_workspace(&compositor), 0);
+}
+
+#[test]
+fn cancelled_swipe_reverts() {
+    let (mut compositor, _client) = with_second_workspace();
+
+    compositor.swipe_begin(4);
+    for _ in 0..10 {
+        let delta = next_workspace_delta(&compositor, 40.0);
+        compositor.swipe_update(delta);
+    }
+    assert_eq!(active_workspace(&compositor), 0);
+    compositor.swipe_end(true);
+    assert_eq!(active_workspace(&compositor), 0);
+    let output = compositor.output(0);
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .workspaces
+        .gesture(&output)
+        .is_none());
+}
+
+#[test]
+fn unbound_fingers_are_ignored() {
+    let (mut compositor, mut client) = with_second_workspace();
+
+    let delta = next_workspace_delta(&compositor, 400.0);
+    compositor.swipe(3, delta);
+    compositor.settle(&mut client);
+    assert_eq!(active_workspace(&compositor), 0);
+}
+
+#[test]
+fn pinch_triggers_action() {
+    let mut compositor = TestCompositor::new();
+    let static_conf = &mut compositor.state.common.config.static_conf;
+    static_conf.modes.insert("pinched".into(), HashMap::new());
+    static_conf.gestures.insert(
+        Gesture::Pinch(4, PinchDirection::In),
+        Action::EnterMode("pinched".into()),
+    );
+
+    compositor.pinch(4, 1.5);
+    assert_eq!(compositor.state.common.shell.binding_mode(), None);
+
+    compositor.pinch(4, 0.5);
+    assert_eq!(
+        compositor.state.common.shell.binding_mode(),
+        Some("pinched")
+    );
+}
@@ -0,0 +1,304 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A virtual [`InputBackend`] to inject synthetic events into the compositor.
+
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use smithay::backend::input::{
+    AbsolutePositionEvent, Axis, AxisSource, ButtonState, Device, DeviceCapability, Event,
+    GestureBeginEvent, GestureEndEvent, GesturePinchUpdateEvent, GestureSwipeUpdateEvent,
+    InputBackend, KeyState, KeyboardKeyEvent, PointerAxisEvent, PointerButtonEvent,
+    PointerMotionEvent, UnusedEvent,
+};
+use xkbcommon::xkb::Keycode;
+
+#[derive(Debug)]
+pub struct TestInput;
+
+static DEVICE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A device offering keyboard, pointer and gesture capabilities.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TestDevice {
+    id: usize,
+}
+
+impl TestDevice {
+    pub fn new() -> TestDevice {
+        TestDevice {
+            id: DEVICE_ID.fetch_add(1, Ordering::SeqCst),
+        }
+    }
+}
+
+impl Device for TestDevice {
+    fn id(&self) -> String {
+        format!("test-device-{}", self.id)
+    }
+
+    fn name(&self) -> String {
+        String::from("Test Device")
+    }
+
+    fn has_capability(&self, capability: DeviceCapability) -> bool {
+        matches!(
+            capability,
+            DeviceCapability::Keyboard | DeviceCapability::Pointer | DeviceCapability::Gesture
+        )
+    }
+
+    fn usb_id(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    fn syspath(&self) -> Option<PathBuf> {
+        None
+    }
+}
+
+impl InputBackend for TestInput {
+    type Device = TestDevice;
+    type KeyboardKeyEvent = KeyboardKey;
+    type PointerAxisEvent = PointerAxis;
+    type PointerButtonEvent = PointerButton;
+    type PointerMotionEvent = PointerMotion;
+    type PointerMotionAbsoluteEvent = PointerMotionAbsolute;
+    type GestureSwipeBeginEvent = GestureBegin;
+    type GestureSwipeUpdateEvent = GestureSwipeUpdate;
+    type GestureSwipeEndEvent = GestureEnd;
+    type GesturePinchBeginEvent = GestureBegin;
+    type GesturePinchUpdateEvent = GesturePinchUpdate;
+    type GesturePinchEndEvent = GestureEnd;
+    type GestureHoldBeginEvent = GestureBegin;
+    type GestureHoldEndEvent = GestureEnd;
+    type TouchDownEvent = UnusedEvent;
+    type TouchUpEvent = UnusedEvent;
+    type TouchMotionEvent = UnusedEvent;
+    type TouchCancelEvent = UnusedEvent;
+    type TouchFrameEvent = UnusedEvent;
+    type TabletToolAxisEvent = UnusedEvent;
+    type TabletToolProximityEvent = UnusedEvent;
+    type TabletToolTipEvent = UnusedEvent;
+    type TabletToolButtonEvent = UnusedEvent;
+    type SpecialEvent = UnusedEvent;
+}
+
+macro_rules! impl_event {
+    ($ty:ty) => {
+        impl Event<TestInput> for $ty {
+            fn time(&self) -> u64 {
+                self.time
+            }
+
+            fn device(&self) -> TestDevice {
+                self.device.clone()
+            }
+        }
+    };
+}
+
+#[derive(Debug)]
+pub struct KeyboardKey {
+    pub device: TestDevice,
+    pub time: u64,
+    pub key: Keycode,
+    pub state: KeyState,
+}
+impl_event!(KeyboardKey);
+
+impl KeyboardKeyEvent<TestInput> for KeyboardKey {
+    fn key_code(&self) -> Keycode {
+        self.key
+    }
+
+    fn state(&self) -> KeyState {
+        self.state
+    }
+
+    fn count(&self) -> u32 {
+        match self.state {
+            KeyState::Pressed => 1,
+            KeyState::Released => 0,
+        }
+    }
+}
+
+/// Absolute pointer position, normalized to `0.0..=1.0` of the active output.
+#[derive(Debug)]
+pub struct PointerMotionAbsolute {
+    pub device: TestDevice,
+    pub time: u64,
+    pub x: f64,
+    pub y: f64,
+}
+impl_event!(PointerMotionAbsolute);
+
+impl AbsolutePositionEvent<TestInput> for PointerMotionAbsolute {
+    fn x(&self) -> f64 {
+        self.x
+    }
+
+    fn y(&self) -> f64 {
+        self.y
+    }
+
+    fn x_transformed(&self, width: i32) -> f64 {
+        self.x * width as f64
+    }
+
+    fn y_transformed(&self, height: i32) -> f64 {
+        self.y * height as f64
+    }
+}
+
+#[derive(Debug)]
+pub struct PointerMotion {
+    pub device: TestDevice,
+    pub time: u64,
+    pub delta: (f64, f64),
+}
+impl_event!(PointerMotion);
+
+impl PointerMotionEvent<TestInput> for PointerMotion {
+    fn delta_x(&self) -> f64 {
+        self.delta.0
+    }
+
+    fn delta_y(&self) -> f64 {
+        self.delta.1
+    }
+
+    fn delta_x_unaccel(&self) -> f64 {
+        self.delta.0
+    }
+
+    fn delta_y_unaccel(&self) -> f64 {
+        self.delta.1
+    }
+}
+
+#[derive(Debug)]
+pub struct PointerButton {
+    pub device: TestDevice,
+    pub time: u64,
+    pub button: u32,
+    pub state: ButtonState,
+}
+impl_event!(PointerButton);
+
+impl PointerButtonEvent<TestInput> for PointerButton {
+    fn button_code(&self) -> u32 {
+        self.button
+    }
+
+    fn state(&self) -> ButtonState {
+        self.state
+    }
+}
+
+#[derive(Debug)]
+pub struct PointerAxis {
+    pub device: TestDevice,
+    pub time: u64,
+    pub source: AxisSource,
+    pub horizontal: Option<f64>,
+    pub vertical: Option<f64>,
+}
+impl_event!(PointerAxis);
+
+impl PointerAxisEvent<TestInput> for PointerAxis {
+    fn amount(&self, axis: Axis) -> Option<f64> {
+        match axis {
+            Axis::Horizontal => self.horizontal,
+            Axis::Vertical => self.vertical,
+        }
+    }
+
+    fn amount_v120(&self, axis: Axis) -> Option<f64> {
+        match self.source {
+            AxisSource::Wheel | AxisSource::WheelTilt => {
+                self.amount(axis).map(|amount| amount / 15.0 * 120.0)
+            }
+            _ => None,
+        }
+    }
+
+    fn source(&self) -> AxisSource {
+        self.source
+    }
+}
+
+#[derive(Debug)]
+pub struct GestureBegin {
+    pub device: TestDevice,
+    pub time: u64,
+    pub fingers: u32,
+}
+impl_event!(GestureBegin);
+
+impl GestureBeginEvent<TestInput> for GestureBegin {
+    fn fingers(&self) -> u32 {
+        self.fingers
+    }
+}
+
+#[derive(Debug)]
+pub struct GestureSwipeUpdate {
+    pub device: TestDevice,
+    pub time: u64,
+    pub delta: (f64, f64),
+}
+impl_event!(GestureSwipeUpdate);
+
+impl GestureSwipeUpdateEvent<TestInput> for GestureSwipeUpdate {
+    fn delta_x(&self) -> f64 {
+        self.delta.0
+    }
+
+    fn delta_y(&self) -> f64 {
+        self.delta.1
+    }
+}
+
+#[derive(Debug)]
+pub struct GesturePinchUpdate {
+    pub device: TestDevice,
+    pub time: u64,
+    pub scale: f64,
+}
+impl_event!(GesturePinchUpdate);
+
+impl GesturePinchUpdateEvent<TestInput> for GesturePinchUpdate {
+    fn delta_x(&self) -> f64 {
+        0.0
+    }
+
+    fn delta_y(&self) -> f64 {
+        0.0
+    }
+
+    fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    fn rotation(&self) -> f64 {
+        0.0
+    }
+}
+
+#[derive(Debug)]
+pub struct GestureEnd {
+    pub device: TestDevice,
+    pub time: u64,
+    pub cancelled: bool,
+}
+impl_event!(GestureEnd);
+
+impl GestureEndEvent<TestInput> for GestureEnd {
+    fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+
+use smithay::{backend::input::KeyState, input::keyboard::Keysym};
+
+use super::{TestCompositor, KEY_G, KEY_LEFTMETA};
+use crate::{
+    config::{Action, KeyModifier, KeyPattern},
+    shell::focus::FocusDirection,
+};
+
+#[test]
+fn patterns_deserialize() {
+    let bindings: HashMap<KeyPattern, Action> = ron::Options::default()
+        .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+        .from_str(
+            r#"{
+                (modifiers: [Super], key: "h"): Focus(Left),
+                (modifiers: [Super], keycode: 44): Focus(Down),
+                (modifiers: [Super, Shift], key: "k", release: true): Focus(Up),
+                (modifiers: [Super], release: true): Spawn("launcher"),
+            }"#,
+        )
+        .expect("Failed to parse key bindings");
+
+    let mut keycode = KeyPattern::new(KeyModifier::Super, None);
+    keycode.keycode = Some(44);
+    assert_eq!(bindings[&keycode], Action::Focus(FocusDirection::Down));
+    assert!(!keycode.is_modifiers_only());
+
+    let mut release = KeyPattern::new(KeyModifier::Super | KeyModifier::Shift, Some(Keysym::k));
+    release.release = true;
+    assert_eq!(bindings[&release], Action::Focus(FocusDirection::Up));
+
+    let mut modifiers_only = KeyPattern::new(KeyModifier::Super, None);
+    modifiers_only.release = true;
+    assert!(modifiers_only.is_modifiers_only());
+    assert!(bindings.contains_key(&modifiers_only));
+}
+
+#[test]
+fn keycode_binding() {
+    let mut compositor = TestCompositor::new();
+    let mut pattern = KeyPattern::new(KeyModifier::Super, None);
+    pattern.keycode = Some(KEY_G);
+    compositor.bind_pattern(pattern, Action::Focus(FocusDirection::Left));
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn release_binding() {
+    let mut compositor = TestCompositor::new();
+    let mut pattern = KeyPattern::new(KeyModifier::Super, Some(Keysym::g));
+    pattern.release = true;
+    compositor.bind_pattern(pattern, Action::Focus(FocusDirection::Left));
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    compositor.key(KEY_LEFTMETA, KeyState::Pressed);
+    compositor.key(KEY_G, KeyState::Pressed);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    compositor.key(KEY_G, KeyState::Released);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+    compositor.key(KEY_LEFTMETA, KeyState::Released);
+}
+
+#[test]
+fn release_binding_ignores_modifier_changes() {
+    let mut compositor = TestCompositor::new();
+    let mut pattern = KeyPattern::new(KeyModifier::Super, Some(Keysym::g));
+    pattern.release = true;
+    compositor.bind_pattern(pattern, Action::Focus(FocusDirection::Left));
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    compositor.key(KEY_LEFTMETA, KeyState::Pressed);
+    compositor.key(KEY_G, KeyState::Pressed);
+    compositor.key(KEY_LEFTMETA, KeyState::Released);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    compositor.key(KEY_G, KeyState::Released);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
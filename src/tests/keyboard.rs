@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic_comp_config::{LayoutMemory, XkbConfig};
+use smithay::input::keyboard::Keysym;
+
+use super::{TestCompositor, KEY_G, KEY_H, KEY_LEFT, KEY_LEFTMETA, KEY_RIGHT};
+use crate::config::{xkb_config_to_wl, Action};
+
+#[test]
+fn repeat_info_defaults() {
+    let config: XkbConfig =
+        ron::from_str(r#"(rules: "", model: "", layout: "us", variant: "", options: None)"#)
+            .expect("Failed to parse xkb config");
+    assert_eq!(config.repeat_delay, 200);
+    assert_eq!(config.repeat_rate, 25);
+}
+
+#[test]
+fn numlock_is_set() {
+    let mut compositor = TestCompositor::new();
+    let seat = compositor.state.common.last_active_seat().clone();
+    let keyboard = seat.get_keyboard().unwrap();
+    assert!(!keyboard.modifier_state().num_lock);
+
+    compositor.state.set_numlock(&seat, true);
+    assert!(keyboard.modifier_state().num_lock);
+    // already locked, stays locked
+    compositor.state.set_numlock(&seat, true);
+    assert!(keyboard.modifier_state().num_lock);
+
+    compositor.state.set_numlock(&seat, false);
+    assert!(!keyboard.modifier_state().num_lock);
+}
+
+fn with_layouts(compositor: &mut TestCompositor) {
+    let keyboard = compositor
+        .state
+        .common
+        .last_active_seat()
+        .get_keyboard()
+        .unwrap();
+    let config = XkbConfig {
+        layout: "us,de".into(),
+        variant: ",".into(),
+        ..XkbConfig::default()
+    };
+    keyboard
+        .set_xkb_config(&mut compositor.state, xkb_config_to_wl(&config))
+        .expect("Failed to load keymap");
+
+    compositor.bind(Keysym::g, Action::NextLayout);
+    compositor.bind(Keysym::h, Action::SetLayout(0));
+}
+
+fn active_layout(compositor: &mut TestCompositor) -> u32 {
+    let keyboard = compositor
+        .state
+        .common
+        .last_active_seat()
+        .get_keyboard()
+        .unwrap();
+    keyboard.with_xkb_state(&mut compositor.state, |context| {
+        context.xkb().lock().unwrap().active_layout().0
+    })
+}
+
+#[test]
+fn layout_actions() {
+    let mut compositor = TestCompositor::new();
+    with_layouts(&mut compositor);
+    assert_eq!(active_layout(&mut compositor), 0);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    assert_eq!(active_layout(&mut compositor), 1);
+    // wraps around
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    assert_eq!(active_layout(&mut compositor), 0);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    assert_eq!(active_layout(&mut compositor), 0);
+}
+
+#[test]
+fn layout_per_window() {
+    let mut compositor = TestCompositor::new();
+    with_layouts(&mut compositor);
+    compositor.state.common.config.keyboard.layout_memory = LayoutMemory::PerWindow;
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    assert_eq!(active_layout(&mut compositor), 1);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_LEFT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+    assert_eq!(active_layout(&mut compositor), 0);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_RIGHT]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+    assert_eq!(active_layout(&mut compositor), 1);
+}
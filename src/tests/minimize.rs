@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use smithay::input::keyboard::Keysym;
+
+use super::{TestCompositor, KEY_G, KEY_LEFTMETA};
+use crate::config::Action;
+
+#[test]
+fn minimize_and_restore_tiled() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(Keysym::g, Action::Minimize);
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    let (mapped, _) = compositor.window("second").unwrap();
+    let surface = mapped.active_window();
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+    assert!(compositor.window("second").is_none());
+    assert!(surface.is_minimized());
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+
+    // still owned by the workspace
+    let output = compositor.output(0);
+    let workspace = compositor.state.common.shell.active_space(&output);
+    assert!(workspace.is_minimized(&mapped));
+    assert!(workspace.windows().any(|w| w == surface));
+
+    let seat = compositor.state.common.last_active_seat().clone();
+    let restored = compositor
+        .state
+        .common
+        .shell
+        .unminimize_request(&surface, &seat);
+    assert_eq!(restored.as_ref(), Some(&mapped));
+    compositor.settle(&mut client);
+    assert!(!surface.is_minimized());
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(&output)
+        .is_tiled(&mapped));
+}
+
+#[test]
+fn floating_geometry_is_restored() {
+    let mut compositor = TestCompositor::new();
+    let mut client = compositor.map_windows(&["first"]);
+    let (mapped, geometry) = compositor.window("first").unwrap();
+
+    compositor.state.common.shell.minimize_request(&mapped);
+    compositor.settle(&mut client);
+    assert!(compositor.window("first").is_none());
+
+    let seat = compositor.state.common.last_active_seat().clone();
+    compositor
+        .state
+        .common
+        .shell
+        .unminimize_request(&mapped.active_window(), &seat);
+    compositor.settle(&mut client);
+    let (_, restored) = compositor.window("first").unwrap();
+    assert_eq!(restored, geometry);
+}
+
+#[test]
+fn unminimize_unknown_window() {
+    let mut compositor = TestCompositor::new();
+    let _client = compositor.map_windows(&["first"]);
+    let (mapped, _) = compositor.window("first").unwrap();
+
+    let seat = compositor.state.common.last_active_seat().clone();
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .unminimize_request(&mapped.active_window(), &seat)
+        .is_none());
+}
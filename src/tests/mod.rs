@@ -0,0 +1,431 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! In-process integration tests.
+//!
+//! [`TestCompositor`] boots a full [`State`] on top of the headless backend
+//! and lets tests connect scripted wayland clients (see [`client::TestClient`])
+//! and inject synthetic input events (see [`input::TestInput`]).
+//! Everything runs on the test thread, [`TestCompositor::roundtrip`] alternates
+//! between dispatching the compositor and a client until the client has
+//! processed all replies to its requests.
+
+use std::{
+    ffi::OsString,
+    os::unix::net::UnixStream,
+    sync::{Arc, Once},
+    time::Duration,
+};
+
+use smithay::{
+    backend::input::{AxisSource, ButtonState, InputEvent, KeyState},
+    input::keyboard::Keysym,
+    output::Output,
+    reexports::{
+        calloop::{generic::Generic, EventLoop, Interest, Mode, PostAction},
+        wayland_server::Display,
+    },
+    utils::{Point, Rectangle},
+};
+use xkbcommon::xkb::Keycode;
+
+use crate::{
+    backend::headless::{self, HeadlessConfig},
+    config::{Action, KeyModifier, KeyPattern},
+    shell::{focus::target::KeyboardFocusTarget, CosmicMapped},
+    utils::prelude::*,
+};
+
+mod binding_modes;
+mod client;
+mod config;
+mod expose;
+mod floating;
+mod focus;
+mod gestures;
+mod input;
+mod key_bindings;
+mod keyboard;
+mod minimize;
+mod mouse_bindings;
+mod saved_layouts;
+mod scratchpad;
+mod scrolling;
+mod tiling;
+mod toplevel_management;
+mod window_rules;
+mod window_switcher;
+mod workspaces;
+
+use self::{
+    client::TestClient,
+    input::{TestDevice, TestInput},
+};
+
+/// evdev codes of some keys, offset by 8 to get xkb keycodes
+pub const KEY_ESC: u32 = 1 + 8;
+pub const KEY_E: u32 = 18 + 8;
+pub const KEY_F: u32 = 33 + 8;
+pub const KEY_G: u32 = 34 + 8;
+pub const KEY_H: u32 = 35 + 8;
+pub const KEY_TAB: u32 = 15 + 8;
+pub const KEY_ENTER: u32 = 28 + 8;
+pub const KEY_LEFTCTRL: u32 = 29 + 8;
+pub const KEY_LEFTSHIFT: u32 = 42 + 8;
+pub const KEY_LEFTALT: u32 = 56 + 8;
+pub const KEY_LEFTMETA: u32 = 125 + 8;
+pub const KEY_LEFT: u32 = 105 + 8;
+pub const KEY_RIGHT: u32 = 106 + 8;
+pub const KEY_DOWN: u32 = 108 + 8;
+pub const BTN_LEFT: u32 = 0x110;
+pub const BTN_MIDDLE: u32 = 0x112;
+
+const MAX_ROUNDTRIP_ITERATIONS: usize = 1000;
+
+static ISOLATE_ENV: Once = Once::new();
+
+/// Don't let the configuration of the user running the tests leak into them.
+fn isolate_env() {
+    ISOLATE_ENV.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("cosmic-comp-tests-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("Failed to create test directory");
+        std::env::set_var("XDG_CONFIG_HOME", dir.join("config"));
+        std::env::set_var("XDG_STATE_HOME", dir.join("state"));
+    });
+}
+
+pub struct TestCompositor {
+    event_loop: EventLoop<'static, State>,
+    pub state: State,
+    device: TestDevice,
+    time: u64,
+}
+
+impl TestCompositor {
+    pub fn new() -> TestCompositor {
+        TestCompositor::with_config(HeadlessConfig {
+            outputs: 1,
+            size: (1920, 1080),
+            scale: 1.0,
+        })
+    }
+
+    pub fn with_config(config: HeadlessConfig) -> TestCompositor {
+        isolate_env();
+
+        let mut event_loop = EventLoop::try_new().expect("Failed to initialize event loop");
+        let display = Display::new().unwrap();
+        let dh = display.handle();
+        event_loop
+            .handle()
+            .insert_source(
+                Generic::new(display, Interest::READ, Mode::Level),
+                move |_, display, state| {
+                    // SAFETY: We don't drop the display
+                    unsafe { display.get_mut().dispatch_clients(state) }
+                        .map(|_| PostAction::Continue)
+                },
+            )
+            .expect("Failed to init the wayland event source");
+
+        let mut state = State::new(
+            &dh,
+            OsString::new(),
+            event_loop.handle(),
+            event_loop.get_signal(),
+        );
+        let outputs = headless::init_headless(&dh, &mut event_loop, &mut state, config)
+            .expect("Failed to initialize headless backend");
+        let seat = crate::input::add_seat(
+            &dh,
+            &mut state.common.seat_state,
+            &outputs[0],
+            &state.common.config,
+            "seat-0".into(),
+        );
+        state.common.add_seat(seat);
+
+        let mut compositor = TestCompositor {
+            event_loop,
+            state,
+            device: TestDevice::new(),
+            time: 0,
+        };
+        let device = compositor.device.clone();
+        compositor.input(InputEvent::DeviceAdded { device });
+        compositor.dispatch();
+        compositor
+    }
+
+    /// Runs a single, non-blocking iteration of the compositor's event loop.
+    pub fn dispatch(&mut self) {
+        self.event_loop
+            .dispatch(Some(Duration::ZERO), &mut self.state)
+            .expect("Failed to dispatch event loop");
+        self.state.refresh();
+    }
+
+    /// Connects a new (privileged) client.
+    pub fn connect(&mut self) -> TestClient {
+        let (server, client) = UnixStream::pair().expect("Failed to create socket pair");
+        self.state
+            .common
+            .display_handle
+            .insert_client(server, Arc::new(self.state.new_privileged_client_state()))
+            .expect("Failed to insert client");
+
+        let mut client = TestClient::new(client);
+        self.roundtrip(&mut client);
+        client
+    }
+
+    /// Dispatches compositor and client until the client received all replies to the
+    /// requests it sent so far.
+    pub fn roundtrip(&mut self, client: &mut TestClient) {
+        let done = client.sync();
+        for _ in 0..MAX_ROUNDTRIP_ITERATIONS {
+            client.flush();
+            self.dispatch();
+            client.dispatch();
+            if client.is_done(&done) {
+                return;
+            }
+        }
+        panic!("Roundtrip did not finish in time");
+    }
+
+    /// Connects a new client and maps a toplevel for every title, one after another,
+    /// so the last one ends up focused.
+    pub fn map_windows(&mut self, titles: &[&str]) -> TestClient {
+        let mut client = self.connect();
+        for title in titles {
+            client.create_toplevel(title);
+            self.settle(&mut client);
+        }
+        client
+    }
+
+    /// Roundtrips until the compositor stops sending configures in reply to the
+    /// clients commits, so every surface is mapped at its final size.
+    pub fn settle(&mut self, client: &mut TestClient) {
+        for _ in 0..3 {
+            self.roundtrip(client);
+        }
+    }
+
+    pub fn output(&self, idx: usize) -> Output {
+        self.state
+            .common
+            .shell
+            .outputs()
+            .nth(idx)
+            .expect("No such output")
+            .clone()
+    }
+
+    pub fn output_geometry(&self, idx: usize) -> Rectangle<i32, Global> {
+        self.output(idx).geometry()
+    }
+
+    /// Injects an input event as if it was emitted by a backend.
+    pub fn input(&mut self, event: InputEvent<TestInput>) {
+        self.state.process_input_event(event, false);
+    }
+
+    fn next_time(&mut self) -> u64 {
+        // microseconds, advance a bit more than a frame for every event
+        self.time += 20_000;
+        self.time
+    }
+
+    pub fn key(&mut self, keycode: u32, state: KeyState) {
+        let event = input::KeyboardKey {
+            device: self.device.clone(),
+            time: self.next_time(),
+            key: Keycode::new(keycode),
+            state,
+        };
+        self.input(InputEvent::Keyboard { event });
+    }
+
+    /// Presses and releases a combination of keys.
+    pub fn press_keys(&mut self, keycodes: &[u32]) {
+        for keycode in keycodes {
+            self.key(*keycode, KeyState::Pressed);
+        }
+        for keycode in keycodes.iter().rev() {
+            self.key(*keycode, KeyState::Released);
+        }
+    }
+
+    /// Moves the pointer to the given position in global coordinates.
+    pub fn pointer_motion_to(&mut self, position: Point<f64, Global>) {
+        let seat = self.state.common.last_active_seat().clone();
+        let output = self
+            .state
+            .common
+            .shell
+            .outputs()
+            .find(|o| o.geometry().to_f64().contains(position))
+            .cloned()
+            .expect("Position is not on any output");
+        seat.set_active_output(&output);
+
+        let geometry = output.geometry().to_f64();
+        let event = input::PointerMotionAbsolute {
+            device: self.device.clone(),
+            time: self.next_time(),
+            x: (position.x - geometry.loc.x) / geometry.size.w,
+            y: (position.y - geometry.loc.y) / geometry.size.h,
+        };
+        self.input(InputEvent::PointerMotionAbsolute { event });
+    }
+
+    pub fn pointer_button(&mut self, button: u32, state: ButtonState) {
+        let event = input::PointerButton {
+            device: self.device.clone(),
+            time: self.next_time(),
+            button,
+            state,
+        };
+        self.input(InputEvent::PointerButton { event });
+    }
+
+    pub fn pointer_motion(&mut self, delta: (f64, f64)) {
+        let event = input::PointerMotion {
+            device: self.device.clone(),
+            time: self.next_time(),
+            delta,
+        };
+        self.input(InputEvent::PointerMotion { event });
+    }
+
+    /// Scrolls the given amount of wheel steps vertically.
+    pub fn scroll(&mut self, steps: f64) {
+        let event = input::PointerAxis {
+            device: self.device.clone(),
+            time: self.next_time(),
+            source: AxisSource::Wheel,
+            horizontal: None,
+            vertical: Some(steps * 15.0),
+        };
+        self.input(InputEvent::PointerAxis { event });
+    }
+
+    pub fn swipe_begin(&mut self, fingers: u32) {
+        let event = input::GestureBegin {
+            device: self.device.clone(),
+            time: self.next_time(),
+            fingers,
+        };
+        self.input(InputEvent::GestureSwipeBegin { event });
+    }
+
+    pub fn swipe_update(&mut self, delta: (f64, f64)) {
+        let event = input::GestureSwipeUpdate {
+            device: self.device.clone(),
+            time: self.next_time(),
+            delta,
+        };
+        self.input(InputEvent::GestureSwipeUpdate { event });
+    }
+
+    pub fn swipe_end(&mut self, cancelled: bool) {
+        let event = input::GestureEnd {
+            device: self.device.clone(),
+            time: self.next_time(),
+            cancelled,
+        };
+        self.input(InputEvent::GestureSwipeEnd { event });
+    }
+
+    /// Swipes the given distance in ten steps.
+    pub fn swipe(&mut self, fingers: u32, distance: (f64, f64)) {
+        self.swipe_begin(fingers);
+        for _ in 0..10 {
+            self.swipe_update((distance.0 / 10.0, distance.1 / 10.0));
+        }
+        self.swipe_end(false);
+    }
+
+    /// Pinches to the given scale in ten steps.
+    pub fn pinch(&mut self, fingers: u32, scale: f64) {
+        let event = input::GestureBegin {
+            device: self.device.clone(),
+            time: self.next_time(),
+            fingers,
+        };
+        self.input(InputEvent::GesturePinchBegin { event });
+        for step in 1..=10 {
+            let event = input::GesturePinchUpdate {
+                device: self.device.clone(),
+                time: self.next_time(),
+                scale: 1.0 + (scale - 1.0) * step as f64 / 10.0,
+            };
+            self.input(InputEvent::GesturePinchUpdate { event });
+        }
+        let event = input::GestureEnd {
+            device: self.device.clone(),
+            time: self.next_time(),
+            cancelled: false,
+        };
+        self.input(InputEvent::GesturePinchEnd { event });
+    }
+
+    pub fn click(&mut self, position: Point<f64, Global>) {
+        self.pointer_motion_to(position);
+        self.pointer_button(BTN_LEFT, ButtonState::Pressed);
+        self.pointer_button(BTN_LEFT, ButtonState::Released);
+    }
+
+    pub fn set_tiling(&mut self, enabled: bool) {
+        let seat = self.state.common.last_active_seat().clone();
+        self.state
+            .common
+            .shell
+            .workspaces
+            .update_tiling_status(&seat, enabled);
+    }
+
+    /// Binds `Super` and `keysym` to `action`.
+    pub fn bind(&mut self, keysym: Keysym, action: Action) {
+        self.bind_pattern(KeyPattern::new(KeyModifier::Super, Some(keysym)), action);
+    }
+
+    pub fn bind_pattern(&mut self, pattern: KeyPattern, action: Action) {
+        self.state
+            .common
+            .config
+            .static_conf
+            .key_bindings
+            .insert(pattern, action);
+    }
+
+    /// Finds the element containing the window with the given title on any active workspace
+    /// and returns it alongside its geometry in global coordinates.
+    pub fn window(&self, title: &str) -> Option<(CosmicMapped, Rectangle<i32, Global>)> {
+        let shell = &self.state.common.shell;
+        shell.outputs().find_map(|output| {
+            let workspace = shell.active_space(output);
+            workspace
+                .mapped()
+                .find(|mapped| mapped.windows().any(|(w, _)| w.title() == title))
+                .map(|mapped| {
+                    let geometry = workspace
+                        .element_geometry(mapped)
+                        .unwrap()
+                        .to_global(output);
+                    (mapped.clone(), geometry)
+                })
+        })
+    }
+
+    /// Title of the window currently holding keyboard focus of the active seat.
+    pub fn focused_title(&self) -> Option<String> {
+        let seat = self.state.common.last_active_seat();
+        match seat.get_keyboard().unwrap().current_focus()? {
+            KeyboardFocusTarget::Element(mapped) => Some(mapped.active_window().title()),
+            KeyboardFocusTarget::Fullscreen(surface) => Some(surface.title()),
+            _ => None,
+        }
+    }
+}
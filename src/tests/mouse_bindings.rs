@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+
+use smithay::{
+    backend::input::{ButtonState, KeyState},
+    input::keyboard::Keysym,
+};
+
+use super::{TestCompositor, BTN_MIDDLE, KEY_G, KEY_LEFTMETA};
+use crate::config::{Action, KeyModifier, MouseButton, MousePattern};
+
+fn active_workspace(compositor: &TestCompositor) -> usize {
+    let output = compositor.output(0);
+    compositor
+        .state
+        .common
+        .shell
+        .workspaces
+        .active_num(&output)
+        .1
+}
+
+#[test]
+fn scroll_bindings_switch_workspaces() {
+    let mut compositor = TestCompositor::new();
+    let mouse_bindings = &mut compositor.state.common.config.static_conf.mouse_bindings;
+    mouse_bindings.insert(
+        MousePattern::new(KeyModifier::Super, MouseButton::ScrollUp),
+        Action::PreviousWorkspace,
+    );
+    mouse_bindings.insert(
+        MousePattern::new(KeyModifier::Super, MouseButton::ScrollDown),
+        Action::NextWorkspace,
+    );
+    let mut client = compositor.map_windows(&["first"]);
+    compositor.pointer_motion_to((100.0, 100.0).into());
+
+    // scrolling without modifiers is not bound
+    compositor.scroll(1.0);
+    compositor.settle(&mut client);
+    assert_eq!(active_workspace(&compositor), 0);
+
+    compositor.key(KEY_LEFTMETA, KeyState::Pressed);
+    compositor.scroll(1.0);
+    compositor.settle(&mut client);
+    assert_eq!(active_workspace(&compositor), 1);
+
+    // partial steps accumulate
+    compositor.scroll(-0.5);
+    compositor.settle(&mut client);
+    assert_eq!(active_workspace(&compositor), 1);
+    compositor.scroll(-0.5);
+    compositor.settle(&mut client);
+    assert_eq!(active_workspace(&compositor), 0);
+    compositor.key(KEY_LEFTMETA, KeyState::Released);
+}
+
+#[test]
+fn button_binding_toggles_floating() {
+    let mut compositor = TestCompositor::new();
+    compositor
+        .state
+        .common
+        .config
+        .static_conf
+        .mouse_bindings
+        .insert(
+            MousePattern::new(KeyModifier::Super, MouseButton::Middle),
+            Action::ToggleWindowFloating,
+        );
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first"]);
+
+    let (mapped, geometry) = compositor.window("first").unwrap();
+    let output = compositor.output(0);
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(&output)
+        .is_tiled(&mapped));
+
+    compositor.pointer_motion_to(geometry.loc.to_f64() + (10.0, 10.0).into());
+    compositor.key(KEY_LEFTMETA, KeyState::Pressed);
+    compositor.pointer_button(BTN_MIDDLE, ButtonState::Pressed);
+    compositor.pointer_button(BTN_MIDDLE, ButtonState::Released);
+    compositor.key(KEY_LEFTMETA, KeyState::Released);
+    compositor.settle(&mut client);
+
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(&output)
+        .is_floating(&mapped));
+}
+
+#[test]
+fn button_binding_acts_on_clicked_window() {
+    let mut compositor = TestCompositor::new();
+    compositor
+        .state
+        .common
+        .config
+        .static_conf
+        .mouse_bindings
+        .insert(
+            MousePattern::new(KeyModifier::Super, MouseButton::Middle),
+            Action::ToggleWindowFloating,
+        );
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    let (first, geometry) = compositor.window("first").unwrap();
+    let (second, _) = compositor.window("second").unwrap();
+    compositor.pointer_motion_to(geometry.loc.to_f64() + (10.0, 10.0).into());
+    compositor.key(KEY_LEFTMETA, KeyState::Pressed);
+    compositor.pointer_button(BTN_MIDDLE, ButtonState::Pressed);
+    compositor.pointer_button(BTN_MIDDLE, ButtonState::Released);
+    compositor.key(KEY_LEFTMETA, KeyState::Released);
+    compositor.settle(&mut client);
+
+    let output = compositor.output(0);
+    let workspace = compositor.state.common.shell.active_space(&output);
+    assert!(workspace.is_floating(&first));
+    assert!(workspace.is_tiled(&second));
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn mouse_bindings_are_inactive_in_modes() {
+    let mut compositor = TestCompositor::new();
+    compositor
+        .state
+        .common
+        .config
+        .static_conf
+        .mouse_bindings
+        .insert(
+            MousePattern::new(KeyModifier::Super, MouseButton::ScrollDown),
+            Action::NextWorkspace,
+        );
+    compositor
+        .state
+        .common
+        .config
+        .static_conf
+        .modes
+        .insert("empty".into(), HashMap::new());
+    compositor.bind(Keysym::g, Action::EnterMode("empty".into()));
+    let mut client = compositor.map_windows(&["first"]);
+    compositor.pointer_motion_to((100.0, 100.0).into());
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    assert_eq!(compositor.state.common.shell.binding_mode(), Some("empty"));
+    compositor.key(KEY_LEFTMETA, KeyState::Pressed);
+    compositor.scroll(1.0);
+    compositor.settle(&mut client);
+    assert_eq!(active_workspace(&compositor), 0);
+    compositor.key(KEY_LEFTMETA, KeyState::Released);
+}
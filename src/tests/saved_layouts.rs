@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use smithay::input::keyboard::Keysym;
+
+use super::{TestCompositor, KEY_G, KEY_LEFTMETA};
+use crate::{
+    config::{Action, Pattern, WindowRule},
+    shell::layout::saved::{SavedLayouts, SavedWorkspace, WindowKey},
+};
+
+fn key(title: &str) -> WindowKey {
+    WindowKey {
+        app_id: title.into(),
+        title: title.into(),
+    }
+}
+
+#[test]
+fn restore_tiling_tree() {
+    let titles = ["first", "second", "third"];
+    let (saved, geometries) = {
+        let mut compositor = TestCompositor::new();
+        compositor.set_tiling(true);
+        let _client = compositor.map_windows(&titles);
+        let geometries = titles.map(|title| compositor.window(title).unwrap().1);
+        (compositor.state.common.shell.saved_layouts(), geometries)
+    };
+    assert_eq!(saved.workspaces.len(), 1);
+
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    compositor.state.common.shell.restore_layouts(saved);
+    // mapped in a different order, but placed into the same slots
+    let _client = compositor.map_windows(&["third", "first", "second"]);
+    for (title, geometry) in titles.into_iter().zip(geometries) {
+        let (_, restored) = compositor.window(title).unwrap();
+        assert!((restored.loc.x - geometry.loc.x).abs() <= 1, "{}", title);
+        assert!((restored.loc.y - geometry.loc.y).abs() <= 1, "{}", title);
+        assert!((restored.size.w - geometry.size.w).abs() <= 1, "{}", title);
+        assert!((restored.size.h - geometry.size.h).abs() <= 1, "{}", title);
+    }
+}
+
+#[test]
+fn restore_stack() {
+    let saved = {
+        let mut compositor = TestCompositor::new();
+        compositor.bind(Keysym::g, Action::ToggleStacking);
+        compositor.set_tiling(true);
+        let mut client = compositor.map_windows(&["first"]);
+        compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+        compositor.settle(&mut client);
+        client.create_toplevel("second");
+        compositor.settle(&mut client);
+        assert!(compositor.window("second").unwrap().0.is_stack());
+        compositor.state.common.shell.saved_layouts()
+    };
+
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    compositor.state.common.shell.restore_layouts(saved);
+    let _client = compositor.map_windows(&["second", "first"]);
+
+    let (first, _) = compositor.window("first").unwrap();
+    let (second, _) = compositor.window("second").unwrap();
+    assert!(first.is_stack());
+    assert_eq!(first, second);
+}
+
+#[test]
+fn restore_floating_geometry() {
+    let mut compositor = TestCompositor::new();
+    compositor.state.common.shell.restore_layouts(SavedLayouts {
+        workspaces: vec![SavedWorkspace {
+            output: compositor.output(0).name(),
+            idx: 0,
+            tiling: None,
+            floating: vec![(key("first"), (10, 20, 200, 200))],
+        }],
+    });
+    compositor.set_tiling(true);
+    let _client = compositor.map_windows(&["first"]);
+
+    let (mapped, geometry) = compositor.window("first").unwrap();
+    let output = compositor.output_geometry(0);
+    assert_eq!(
+        (geometry.loc.x, geometry.loc.y),
+        (output.loc.x + 10, output.loc.y + 20)
+    );
+    let workspace = compositor
+        .state
+        .common
+        .shell
+        .active_space(&compositor.output(0));
+    assert!(workspace.is_floating(&mapped));
+}
+
+#[test]
+fn overridden_slot_is_kept() {
+    let mut compositor = TestCompositor::new();
+    compositor.state.common.shell.restore_layouts(SavedLayouts {
+        workspaces: vec![SavedWorkspace {
+            output: compositor.output(0).name(),
+            idx: 0,
+            tiling: None,
+            floating: vec![(key("first"), (10, 20, 200, 200))],
+        }],
+    });
+    compositor.set_tiling(true);
+    let rules = &mut compositor.state.common.config.static_conf.window_rules;
+    rules.push(WindowRule {
+        app_id: Some(Pattern::new("^first$").unwrap()),
+        floating: Some(false),
+        ..Default::default()
+    });
+    let mut client = compositor.map_windows(&["first"]);
+    assert!(compositor.state.common.shell.restoring_layouts());
+
+    // the next matching window still gets the slot, which ends restoring
+    compositor
+        .state
+        .common
+        .config
+        .static_conf
+        .window_rules
+        .clear();
+    client.create_toplevel("first");
+    compositor.settle(&mut client);
+    assert!(!compositor.state.common.shell.restoring_layouts());
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use smithay::input::keyboard::Keysym;
+
+use super::{TestCompositor, KEY_G, KEY_H, KEY_LEFTMETA};
+use crate::config::Action;
+
+fn bind_scratchpad(compositor: &mut TestCompositor) {
+    compositor.bind(Keysym::g, Action::MoveToScratchpad);
+    compositor.bind(Keysym::h, Action::ShowScratchpad);
+}
+
+#[test]
+fn hide_and_show() {
+    let mut compositor = TestCompositor::new();
+    bind_scratchpad(&mut compositor);
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    let (mapped, _) = compositor.window("second").unwrap();
+    let surface = mapped.active_window();
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+    assert!(compositor.window("second").is_none());
+    assert!(surface.is_minimized());
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .scratchpad_element(&surface)
+        .is_some());
+
+    // shown centered and floating
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    assert!(!surface.is_minimized());
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+    let (_, geometry) = compositor.window("second").unwrap();
+    let output = compositor.output_geometry(0);
+    let center = |rect: smithay::utils::Rectangle<i32, _>| rect.loc.x + rect.size.w / 2;
+    assert!((center(geometry) - center(output)).abs() <= 1);
+    let workspace = compositor
+        .state
+        .common
+        .shell
+        .active_space(&compositor.output(0));
+    assert!(workspace.is_floating(&mapped));
+
+    // hidden again while focused
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    assert!(compositor.window("second").is_none());
+}
+
+#[test]
+fn show_cycles() {
+    let mut compositor = TestCompositor::new();
+    bind_scratchpad(&mut compositor);
+    let mut client = compositor.map_windows(&["first"]);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+    client.create_toplevel("second");
+    compositor.settle(&mut client);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    assert!(compositor.window("first").is_some());
+    assert!(compositor.window("second").is_none());
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    assert!(compositor.window("first").is_none());
+    assert!(compositor.window("second").is_some());
+}
+
+#[test]
+fn sticky_window() {
+    let mut compositor = TestCompositor::new();
+    let mut client = compositor.map_windows(&["first"]);
+    let (mapped, _) = compositor.window("first").unwrap();
+    let seat = compositor.state.common.last_active_seat().clone();
+    let seats = compositor.state.common.seats().cloned().collect::<Vec<_>>();
+    let output = compositor.output(0);
+
+    let shell = &mut compositor.state.common.shell;
+    shell.toggle_sticky(seats.iter(), &seat, &mapped);
+    shell
+        .active_space_mut(&output)
+        .focus_stack
+        .get_mut(&seat)
+        .append(&mapped);
+    shell.move_to_scratchpad(seats.iter(), &mapped);
+    compositor.settle(&mut client);
+
+    let shell = &compositor.state.common.shell;
+    assert!(shell.scratchpad_element(&mapped.active_window()).is_some());
+    assert!(!shell
+        .active_space(&output)
+        .focus_stack
+        .get(&seat)
+        .iter()
+        .any(|m| m == &mapped));
+}
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use smithay::{input::keyboard::Keysym, wayland::seat::WaylandFocus};
+
+use super::{TestCompositor, KEY_E, KEY_G, KEY_H, KEY_LEFTMETA};
+use crate::{
+    config::Action,
+    shell::{focus::FocusDirection, grabs::ReleaseMode, Shell},
+};
+
+fn enable_scrolling(compositor: &mut TestCompositor) {
+    compositor.bind(Keysym::g, Action::ToggleScrolling);
+    compositor.bind(Keysym::h, Action::Focus(FocusDirection::Left));
+    compositor.set_tiling(true);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    let output = compositor.output(0);
+    assert!(
+        compositor
+            .state
+            .common
+            .shell
+            .active_space(&output)
+            .scrolling_enabled
+    );
+}
+
+#[test]
+fn new_windows_open_right() {
+    let mut compositor = TestCompositor::new();
+    enable_scrolling(&mut compositor);
+    let mut client = compositor.map_windows(&["first"]);
+    let (_, first) = compositor.window("first").unwrap();
+
+    client.create_toplevel("second");
+    compositor.settle(&mut client);
+    let (_, first_after) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+    assert_eq!(first, first_after);
+    assert_eq!(first.size, second.size);
+    assert!(second.loc.x >= first.loc.x + first.size.w);
+
+    // the third column doesn't fit, the strip scrolls instead of shrinking windows
+    client.create_toplevel("third");
+    compositor.settle(&mut client);
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, third) = compositor.window("third").unwrap();
+    let output = compositor.output_geometry(0);
+    assert_eq!(first.size, third.size);
+    assert!(first.loc.x < output.loc.x);
+    assert!(third.loc.x + third.size.w <= output.loc.x + output.size.w);
+}
+
+#[test]
+fn focus_scrolls_viewport() {
+    let mut compositor = TestCompositor::new();
+    enable_scrolling(&mut compositor);
+    let mut client = compositor.map_windows(&["first", "second", "third"]);
+    assert_eq!(compositor.focused_title().as_deref(), Some("third"));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, third) = compositor.window("third").unwrap();
+    let output = compositor.output_geometry(0);
+    assert!(first.loc.x >= output.loc.x);
+    assert!(third.loc.x + third.size.w > output.loc.x + output.size.w);
+}
+
+#[test]
+fn toggle_stacking() {
+    let mut compositor = TestCompositor::new();
+    enable_scrolling(&mut compositor);
+    compositor.bind(Keysym::e, Action::ToggleStacking);
+    let mut client = compositor.map_windows(&["first", "second"]);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_E]);
+    compositor.settle(&mut client);
+    let (mapped, _) = compositor.window("second").unwrap();
+    assert!(mapped.is_stack());
+    let output = compositor.output(0);
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(&output)
+        .is_tiled(&mapped));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_E]);
+    compositor.settle(&mut client);
+    let (mapped, _) = compositor.window("second").unwrap();
+    assert!(mapped.is_window());
+}
+
+#[test]
+fn dragged_window_stays_in_strip() {
+    let mut compositor = TestCompositor::new();
+    enable_scrolling(&mut compositor);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    let (mapped, first) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+
+    compositor.pointer_motion_to(first.loc.to_f64() + (10.0, 10.0).into());
+    let surface = mapped.active_window().wl_surface().unwrap();
+    let seat = compositor.state.common.last_active_seat().clone();
+    Shell::move_request(
+        &mut compositor.state,
+        &surface,
+        &seat,
+        None,
+        ReleaseMode::Click,
+        false,
+    );
+    compositor.settle(&mut client);
+    // dropped onto the right half of the other column
+    compositor.click((second.loc + (second.size.w * 3 / 4, second.size.h / 2).into()).to_f64());
+    compositor.settle(&mut client);
+
+    let output = compositor.output(0);
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(&output)
+        .is_tiled(&mapped));
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+    assert!(first.loc.x > second.loc.x);
+}
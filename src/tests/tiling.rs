@@ -0,0 +1,237 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use smithay::input::keyboard::Keysym;
+
+use super::{
+    client::{Anchor, Layer, TestClient},
+    TestCompositor, KEY_G, KEY_H, KEY_LEFTMETA,
+};
+use crate::{
+    config::Action,
+    shell::{layout::TilingMode, Direction, EqualizeScope, ResizeDimension, ResizeSize},
+};
+
+#[test]
+fn single_window_fills_output() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    let _client = compositor.map_windows(&["first"]);
+
+    let output = compositor.output_geometry(0);
+    let (_, geometry) = compositor.window("first").expect("Window wasn't mapped");
+    assert!(output.contains_rect(geometry));
+    // only gaps are left uncovered
+    assert!(geometry.size.w > output.size.w * 9 / 10);
+    assert!(geometry.size.h > output.size.h * 9 / 10);
+}
+
+#[test]
+fn two_windows_split_output() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    let mut client = compositor.connect();
+
+    client.create_toplevel("first");
+    compositor.settle(&mut client);
+    let second = client.create_toplevel("second");
+    compositor.settle(&mut client);
+
+    let output = compositor.output_geometry(0);
+    let (_, first_geo) = compositor.window("first").unwrap();
+    let (_, second_geo) = compositor.window("second").unwrap();
+    assert!(output.contains_rect(first_geo));
+    assert!(output.contains_rect(second_geo));
+    assert!(!first_geo.overlaps(second_geo));
+    // the output is landscape, so we split horizontally
+    assert!(first_geo.loc.x + first_geo.size.w <= second_geo.loc.x);
+    assert_eq!(first_geo.loc.y, second_geo.loc.y);
+    assert_eq!(first_geo.size.h, second_geo.size.h);
+    // the client got configured to its new size
+    assert_eq!(
+        client.size(second),
+        Some((second_geo.size.w, second_geo.size.h))
+    );
+
+    client.destroy(second);
+    compositor.settle(&mut client);
+
+    assert!(compositor.window("second").is_none());
+    let (_, first_geo) = compositor.window("first").unwrap();
+    assert!(first_geo.size.w > output.size.w * 9 / 10);
+}
+
+#[test]
+fn exclusive_zone_is_respected() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    let mut client = compositor.connect();
+
+    let panel = client.create_layer_surface(
+        Layer::Top,
+        Anchor::Top | Anchor::Left | Anchor::Right,
+        40,
+        (0, 40),
+    );
+    compositor.settle(&mut client);
+    let output = compositor.output_geometry(0);
+    assert_eq!(client.size(panel), Some((output.size.w, 40)));
+
+    client.create_toplevel("first");
+    compositor.settle(&mut client);
+
+    let (_, geometry) = compositor.window("first").unwrap();
+    assert!(geometry.loc.y >= output.loc.y + 40);
+    assert!(output.contains_rect(geometry));
+}
+
+fn tile_three(compositor: &mut TestCompositor, mode: TilingMode) -> TestClient {
+    compositor.bind(Keysym::g, Action::SetTilingMode(mode));
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second", "third"]);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+    client
+}
+
+#[test]
+fn master_stack_mode() {
+    let mut compositor = TestCompositor::new();
+    tile_three(
+        &mut compositor,
+        TilingMode::MasterStack {
+            masters: 1,
+            ratio: 60,
+        },
+    );
+
+    let output = compositor.output_geometry(0);
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+    let (_, third) = compositor.window("third").unwrap();
+    // master on the left with 60% of the width
+    assert!((first.size.w - output.size.w * 6 / 10).abs() < 50);
+    assert!(first.size.h > output.size.h * 9 / 10);
+    // the rest stacked on the right
+    assert!(first.loc.x + first.size.w <= second.loc.x);
+    assert_eq!(second.loc.x, third.loc.x);
+    assert!(second.loc.y + second.size.h <= third.loc.y);
+}
+
+#[test]
+fn dwindle_mode() {
+    let mut compositor = TestCompositor::new();
+    tile_three(&mut compositor, TilingMode::Dwindle);
+
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+    let (_, third) = compositor.window("third").unwrap();
+    assert!(first.loc.x + first.size.w <= second.loc.x);
+    assert!(second.loc.y + second.size.h <= third.loc.y);
+    assert!((second.size.w - third.size.w).abs() <= 1);
+}
+
+#[test]
+fn columns_mode() {
+    let mut compositor = TestCompositor::new();
+    let mut client = tile_three(&mut compositor, TilingMode::Columns);
+
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+    let (_, third) = compositor.window("third").unwrap();
+    assert!(first.loc.x + first.size.w <= second.loc.x);
+    assert!(second.loc.x + second.size.w <= third.loc.x);
+    assert_eq!(first.loc.y, third.loc.y);
+    assert!((first.size.w - third.size.w).abs() <= 1);
+
+    // new windows keep the arrangement
+    client.create_toplevel("fourth");
+    compositor.settle(&mut client);
+    let (_, fourth) = compositor.window("fourth").unwrap();
+    let (_, third) = compositor.window("third").unwrap();
+    assert_eq!(fourth.loc.y, third.loc.y);
+    assert!((fourth.size.w - third.size.w).abs() <= 1);
+}
+
+#[test]
+fn move_in_arranged_mode() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(Keysym::h, Action::Move(Direction::Left));
+    let mut client = tile_three(&mut compositor, TilingMode::Columns);
+    assert_eq!(compositor.focused_title().as_deref(), Some("third"));
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    let (_, second) = compositor.window("second").unwrap();
+    let (_, third) = compositor.window("third").unwrap();
+    assert!(third.loc.x + third.size.w <= second.loc.x);
+}
+
+#[test]
+fn preselect_vertical_split() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(Keysym::g, Action::SplitVertical);
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+
+    client.create_toplevel("third");
+    compositor.settle(&mut client);
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+    let (_, third) = compositor.window("third").unwrap();
+    assert!(first.loc.x + first.size.w <= second.loc.x);
+    assert_eq!(second.loc.x, third.loc.x);
+    assert!(second.loc.y + second.size.h <= third.loc.y);
+
+    // the preselection is used up
+    client.create_toplevel("fourth");
+    compositor.settle(&mut client);
+    let (_, third) = compositor.window("third").unwrap();
+    let (_, fourth) = compositor.window("fourth").unwrap();
+    assert_eq!(third.loc.y, fourth.loc.y);
+}
+
+#[test]
+fn preselect_stacked() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(Keysym::g, Action::SplitStacked);
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first"]);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+
+    client.create_toplevel("second");
+    compositor.settle(&mut client);
+    let (first, _) = compositor.window("first").unwrap();
+    let (second, _) = compositor.window("second").unwrap();
+    assert!(first.is_stack());
+    assert_eq!(first, second);
+}
+
+#[test]
+fn resize_to_and_equalize() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(
+        Keysym::g,
+        Action::ResizeTo(ResizeDimension::Width, ResizeSize::Percent(30)),
+    );
+    compositor.bind(Keysym::h, Action::Equalize(EqualizeScope::All));
+    compositor.set_tiling(true);
+    let mut client = compositor.map_windows(&["first", "second"]);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+    let output = compositor.output_geometry(0);
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+    // only gaps are subtracted
+    assert!((second.size.w - output.size.w * 3 / 10).abs() < 50);
+    assert!(first.size.w > second.size.w * 2);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_H]);
+    compositor.settle(&mut client);
+    let (_, first) = compositor.window("first").unwrap();
+    let (_, second) = compositor.window("second").unwrap();
+    assert!((first.size.w - second.size.w).abs() <= 1);
+}
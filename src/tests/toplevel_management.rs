@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::{
+    backend::headless::HeadlessConfig,
+    wayland::protocols::toplevel_management::ToplevelManagementHandler,
+};
+
+use super::TestCompositor;
+
+#[test]
+fn maximize_and_unmaximize() {
+    let mut compositor = TestCompositor::new();
+    let mut client = compositor.map_windows(&["first"]);
+    let (mapped, geometry) = compositor.window("first").unwrap();
+    let surface = mapped.active_window();
+    let dh = compositor.state.common.display_handle.clone();
+
+    compositor.state.maximize(&dh, &surface);
+    compositor.settle(&mut client);
+    assert!(mapped.is_maximized(false));
+
+    compositor.state.unmaximize(&dh, &surface);
+    compositor.settle(&mut client);
+    assert!(!mapped.is_maximized(false));
+    let (_, restored) = compositor.window("first").unwrap();
+    assert_eq!(restored, geometry);
+}
+
+#[test]
+fn maximize_restores_minimized() {
+    let mut compositor = TestCompositor::new();
+    let mut client = compositor.map_windows(&["first"]);
+    let (mapped, _) = compositor.window("first").unwrap();
+    let surface = mapped.active_window();
+    let dh = compositor.state.common.display_handle.clone();
+
+    compositor.state.minimize(&dh, &surface);
+    compositor.settle(&mut client);
+    assert!(surface.is_minimized());
+
+    compositor.state.maximize(&dh, &surface);
+    compositor.settle(&mut client);
+    assert!(!surface.is_minimized());
+    assert!(mapped.is_maximized(false));
+}
+
+#[test]
+fn fullscreen_on_requested_output() {
+    let mut compositor = TestCompositor::with_config(HeadlessConfig {
+        outputs: 2,
+        ..Default::default()
+    });
+    let mut client = compositor.map_windows(&["first"]);
+    let (mapped, _) = compositor.window("first").unwrap();
+    let surface = mapped.active_window();
+    let dh = compositor.state.common.display_handle.clone();
+    let first = compositor.output(0);
+    let second = compositor.output(1);
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(&first)
+        .mapped()
+        .any(|m| m == &mapped));
+
+    compositor
+        .state
+        .fullscreen(&dh, &surface, Some(second.clone()));
+    compositor.settle(&mut client);
+    let workspace = compositor.state.common.shell.active_space(&second);
+    assert!(workspace
+        .fullscreen
+        .as_ref()
+        .is_some_and(|f| f.surface == surface));
+
+    // returns to the workspace it was on before
+    compositor.state.unfullscreen(&dh, &surface);
+    compositor.settle(&mut client);
+    let mapped = compositor.state.common.shell.element_for_surface(&surface);
+    assert!(mapped.is_some_and(|mapped| compositor
+        .state
+        .common
+        .shell
+        .active_space(&first)
+        .mapped()
+        .any(|m| m == mapped)));
+}
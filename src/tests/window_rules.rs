@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use super::TestCompositor;
+use crate::config::{Pattern, WindowRule};
+
+fn add_rule(compositor: &mut TestCompositor, rule: WindowRule) {
+    compositor
+        .state
+        .common
+        .config
+        .static_conf
+        .window_rules
+        .push(rule);
+}
+
+#[test]
+fn floating_rule_with_geometry() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    add_rule(
+        &mut compositor,
+        WindowRule {
+            app_id: Some(Pattern::new("^floater$").unwrap()),
+            floating: Some(true),
+            size: Some((400, 300)),
+            position: Some((50, 60)),
+            ..Default::default()
+        },
+    );
+    let mut client = compositor.connect();
+
+    client.create_toplevel("tiled");
+    client.create_toplevel("floater");
+    compositor.settle(&mut client);
+
+    let output = compositor.output_geometry(0);
+    let (tiled, tiled_geo) = compositor.window("tiled").unwrap();
+    let (floater, floater_geo) = compositor.window("floater").unwrap();
+    let workspace = compositor
+        .state
+        .common
+        .shell
+        .active_space(compositor.state.common.shell.outputs().next().unwrap());
+    assert!(workspace.is_tiled(&tiled));
+    assert!(workspace.is_floating(&floater));
+    assert!(tiled_geo.size.w > output.size.w * 9 / 10);
+    assert_eq!(
+        (floater_geo.loc.x, floater_geo.loc.y),
+        (output.loc.x + 50, output.loc.y + 60)
+    );
+    assert_eq!((floater_geo.size.w, floater_geo.size.h), (400, 300));
+}
+
+#[test]
+fn tiling_rule_overrides_defaults() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    let mut client = compositor.connect();
+
+    // floated by the default rules
+    client.create_toplevel("Steam");
+    compositor.settle(&mut client);
+    let (steam, _) = compositor.window("Steam").unwrap();
+    let output = compositor.state.common.shell.outputs().next().unwrap();
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(output)
+        .is_floating(&steam));
+
+    add_rule(
+        &mut compositor,
+        WindowRule {
+            title: Some(Pattern::new("Steam").unwrap()),
+            floating: Some(false),
+            ..Default::default()
+        },
+    );
+    client.create_toplevel("Steam Friends");
+    compositor.settle(&mut client);
+    let (friends, _) = compositor.window("Steam Friends").unwrap();
+    let output = compositor.state.common.shell.outputs().next().unwrap();
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(output)
+        .is_tiled(&friends));
+}
+
+#[test]
+fn no_focus_rule() {
+    let mut compositor = TestCompositor::new();
+    compositor.set_tiling(true);
+    add_rule(
+        &mut compositor,
+        WindowRule {
+            title: Some(Pattern::new("^quiet$").unwrap()),
+            no_focus: Some(true),
+            ..Default::default()
+        },
+    );
+    let _client = compositor.map_windows(&["first", "quiet"]);
+
+    assert!(compositor.window("quiet").is_some());
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn workspace_rule() {
+    let mut compositor = TestCompositor::new();
+    add_rule(
+        &mut compositor,
+        WindowRule {
+            title: Some(Pattern::new("^elsewhere$").unwrap()),
+            workspace: Some(2),
+            ..Default::default()
+        },
+    );
+    let _client = compositor.map_windows(&["first", "elsewhere"]);
+
+    // only windows on active workspaces are found
+    assert!(compositor.window("elsewhere").is_none());
+    let output = compositor
+        .state
+        .common
+        .shell
+        .outputs()
+        .next()
+        .unwrap()
+        .clone();
+    let workspace = compositor
+        .state
+        .common
+        .shell
+        .workspaces
+        .get(1, &output)
+        .unwrap();
+    assert!(workspace
+        .mapped()
+        .any(|m| m.windows().any(|(w, _)| w.title() == "elsewhere")));
+}
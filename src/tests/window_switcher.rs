@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use smithay::{backend::input::KeyState, input::keyboard::Keysym};
+
+use super::{client::TestClient, TestCompositor, KEY_ESC, KEY_LEFTALT, KEY_TAB};
+use crate::{
+    config::{Action, KeyModifier, KeyPattern},
+    shell::grabs::SeatWindowSwitcherState,
+};
+
+fn switcher(compositor: &mut TestCompositor) -> TestClient {
+    compositor.bind_pattern(
+        KeyPattern::new(KeyModifier::Alt, Some(Keysym::Tab)),
+        Action::WindowSwitcher,
+    );
+    compositor.set_tiling(true);
+    compositor.map_windows(&["first", "second", "third"])
+}
+
+fn is_open(compositor: &TestCompositor) -> bool {
+    let seat = compositor.state.common.last_active_seat();
+    seat.user_data()
+        .get::<SeatWindowSwitcherState>()
+        .unwrap()
+        .borrow()
+        .is_some()
+}
+
+#[test]
+fn cycle_by_recency() {
+    let mut compositor = TestCompositor::new();
+    let mut client = switcher(&mut compositor);
+    assert_eq!(compositor.focused_title().as_deref(), Some("third"));
+
+    compositor.press_keys(&[KEY_LEFTALT, KEY_TAB]);
+    compositor.settle(&mut client);
+    assert!(!is_open(&compositor));
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+
+    // second, third, first
+    compositor.key(KEY_LEFTALT, KeyState::Pressed);
+    compositor.press_keys(&[KEY_TAB]);
+    compositor.press_keys(&[KEY_TAB]);
+    assert!(is_open(&compositor));
+    assert_eq!(compositor.focused_title().as_deref(), Some("second"));
+    compositor.key(KEY_LEFTALT, KeyState::Released);
+    compositor.settle(&mut client);
+    assert!(!is_open(&compositor));
+    assert_eq!(compositor.focused_title().as_deref(), Some("first"));
+}
+
+#[test]
+fn escape_cancels() {
+    let mut compositor = TestCompositor::new();
+    let mut client = switcher(&mut compositor);
+
+    compositor.key(KEY_LEFTALT, KeyState::Pressed);
+    compositor.press_keys(&[KEY_TAB]);
+    assert!(is_open(&compositor));
+    compositor.press_keys(&[KEY_ESC]);
+    assert!(!is_open(&compositor));
+    compositor.key(KEY_LEFTALT, KeyState::Released);
+    compositor.settle(&mut client);
+    assert_eq!(compositor.focused_title().as_deref(), Some("third"));
+}
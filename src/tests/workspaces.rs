@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic_comp_config::workspace::WorkspaceAmount;
+use smithay::input::keyboard::Keysym;
+
+use super::{TestCompositor, KEY_G, KEY_LEFTMETA};
+use crate::{
+    config::Action,
+    wayland::protocols::workspace::{Request, WorkspaceHandle, WorkspaceHandler},
+};
+
+fn request(compositor: &mut TestCompositor, request: Request) {
+    let dh = compositor.state.common.display_handle.clone();
+    compositor.state.commit_requests(&dh, vec![request]);
+    compositor.state.common.shell.refresh();
+}
+
+fn names(compositor: &TestCompositor) -> Vec<String> {
+    let output = compositor.output(0);
+    let shell = &compositor.state.common.shell;
+    shell
+        .workspaces
+        .spaces_for_output(&output)
+        .map(|w| {
+            shell
+                .workspace_state
+                .workspace_name(&w.handle)
+                .unwrap()
+                .to_string()
+        })
+        .collect()
+}
+
+fn create(compositor: &mut TestCompositor, name: &str) -> Option<WorkspaceHandle> {
+    let output = compositor.output(0);
+    let group = compositor.state.common.shell.workspaces.sets[&output].group();
+    request(
+        compositor,
+        Request::Create {
+            in_group: group,
+            name: name.into(),
+        },
+    );
+    compositor
+        .state
+        .common
+        .shell
+        .workspaces
+        .spaces_for_output(&output)
+        .find(|w| w.name.as_deref() == Some(name))
+        .map(|w| w.handle)
+}
+
+#[test]
+fn create_named_workspace() {
+    let mut compositor = TestCompositor::new();
+    assert_eq!(names(&compositor), ["1"]);
+
+    create(&mut compositor, "mail").expect("Workspace wasn't created");
+    // kept despite being empty, before the trailing empty workspace
+    assert_eq!(names(&compositor), ["mail", "2"]);
+}
+
+#[test]
+fn remove_empty_workspace() {
+    let mut compositor = TestCompositor::new();
+    let handle = create(&mut compositor, "mail").unwrap();
+
+    request(&mut compositor, Request::Remove(handle));
+    assert_eq!(names(&compositor), ["1"]);
+}
+
+#[test]
+fn workspaces_with_windows_are_kept() {
+    let mut compositor = TestCompositor::new();
+    let _client = compositor.map_windows(&["first"]);
+    let output = compositor.output(0);
+    let handle = compositor.state.common.shell.active_space(&output).handle;
+
+    request(&mut compositor, Request::Remove(handle));
+    assert!(compositor.window("first").is_some());
+    assert_eq!(names(&compositor), ["1", "2"]);
+}
+
+#[test]
+fn static_workspaces_are_fixed() {
+    let mut compositor = TestCompositor::new();
+    compositor.state.common.config.workspace.workspace_amount = WorkspaceAmount::Static(2);
+    let common = &mut compositor.state.common;
+    common.shell.update_config(&common.config);
+    compositor.state.common.shell.refresh();
+    assert_eq!(names(&compositor), ["1", "2"]);
+
+    assert!(create(&mut compositor, "mail").is_none());
+    let output = compositor.output(0);
+    let handle = compositor.state.common.shell.active_space(&output).handle;
+    request(&mut compositor, Request::Remove(handle));
+    assert_eq!(names(&compositor), ["1", "2"]);
+}
+
+fn active_name(compositor: &TestCompositor) -> Option<String> {
+    let output = compositor.output(0);
+    compositor
+        .state
+        .common
+        .shell
+        .active_space(&output)
+        .name
+        .clone()
+}
+
+#[test]
+fn configured_names() {
+    let mut compositor = TestCompositor::new();
+    compositor.state.common.config.workspace.workspace_names = vec!["mail".into(), "code".into()];
+    let common = &mut compositor.state.common;
+    common.shell.update_config(&common.config);
+    compositor.state.common.shell.refresh();
+    assert_eq!(names(&compositor), ["mail", "code", "3"]);
+
+    compositor.state.common.config.workspace.workspace_amount = WorkspaceAmount::Static(3);
+    let common = &mut compositor.state.common;
+    common.shell.update_config(&common.config);
+    compositor.state.common.shell.refresh();
+    assert_eq!(names(&compositor), ["mail", "code", "3"]);
+}
+
+#[test]
+fn removed_names_are_forgotten() {
+    let mut compositor = TestCompositor::new();
+    compositor.state.common.config.workspace.workspace_names = vec!["mail".into(), "code".into()];
+    let common = &mut compositor.state.common;
+    common.shell.update_config(&common.config);
+    compositor.state.common.shell.refresh();
+    create(&mut compositor, "notes").expect("Workspace wasn't created");
+    assert_eq!(names(&compositor), ["mail", "code", "notes", "4"]);
+
+    // names given by clients are not part of the config, but kept
+    compositor.state.common.config.workspace.workspace_names = vec!["mail".into()];
+    let common = &mut compositor.state.common;
+    common.shell.update_config(&common.config);
+    compositor.state.common.shell.refresh();
+    assert_eq!(names(&compositor), ["mail", "notes", "3"]);
+}
+
+#[test]
+fn workspace_by_name() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(Keysym::g, Action::WorkspaceByName("mail".into()));
+
+    // created on demand
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.state.common.shell.refresh();
+    assert_eq!(active_name(&compositor).as_deref(), Some("mail"));
+    assert_eq!(names(&compositor), ["mail", "2"]);
+
+    // and found afterwards
+    let output = compositor.output(0);
+    let _ = compositor.state.common.shell.activate(&output, 1);
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.state.common.shell.refresh();
+    assert_eq!(active_name(&compositor).as_deref(), Some("mail"));
+    assert_eq!(names(&compositor), ["mail", "2"]);
+}
+
+#[test]
+fn move_to_workspace_by_name() {
+    let mut compositor = TestCompositor::new();
+    compositor.bind(Keysym::g, Action::MoveToWorkspaceByName("code".into()));
+    let mut client = compositor.map_windows(&["first"]);
+
+    compositor.press_keys(&[KEY_LEFTMETA, KEY_G]);
+    compositor.settle(&mut client);
+    assert_eq!(active_name(&compositor).as_deref(), Some("code"));
+    let (mapped, _) = compositor.window("first").unwrap();
+    let output = compositor.output(0);
+    assert!(compositor
+        .state
+        .common
+        .shell
+        .active_space(&output)
+        .mapped()
+        .any(|m| m == &mapped));
+}
@@ -116,6 +116,9 @@ pub fn screenshot_window(state: &mut State, surface: &CosmicSurface) {
             BackendData::X11(x11) => {
                 render_window(&mut x11.renderer, surface, &state.common.local_offset)
             }
+            BackendData::Headless(headless) => {
+                render_window(&mut headless.renderer, surface, &state.common.local_offset)
+            }
             BackendData::Unset => unreachable!(),
         };
         if let Err(err) = res {
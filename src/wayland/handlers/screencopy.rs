@@ -194,6 +194,7 @@ impl ScreencopyHandler for State {
             }
             BackendData::Winit(ref mut winit) => winit.backend.renderer(),
             BackendData::X11(ref mut x11) => &mut x11.renderer,
+            BackendData::Headless(ref mut headless) => &mut headless.renderer,
             _ => unreachable!(),
         };
 
@@ -462,6 +463,7 @@ fn formats_for_output(
         }
         BackendData::Winit(ref mut winit) => winit.backend.renderer(),
         BackendData::X11(ref mut x11) => &mut x11.renderer,
+        BackendData::Headless(ref mut headless) => &mut headless.renderer,
         _ => unreachable!(),
     };
 
@@ -761,6 +763,17 @@ pub fn render_output_to_buffer(
             },
         )
         .map_err(|err| (FailureReason::Unspec, err.into())),
+        BackendData::Headless(headless) => render_session::<_, _>(
+            node,
+            &mut headless.renderer,
+            session,
+            &params,
+            output.current_transform(),
+            |node, buffer, renderer, dt, age| {
+                render_fn(node, buffer, renderer, dt, age, common, session, output)
+            },
+        )
+        .map_err(|err| (FailureReason::Unspec, err.into())),
         _ => unreachable!(),
     }
 }
@@ -904,6 +917,19 @@ pub fn render_workspace_to_buffer(
             },
         )
         .map_err(|err| (FailureReason::Unspec, err.into())),
+        BackendData::Headless(headless) => render_session::<_, _>(
+            node,
+            &mut headless.renderer,
+            session,
+            &params,
+            output.current_transform(),
+            |node, buffer, renderer, dt, age| {
+                render_fn(
+                    node, buffer, renderer, dt, age, common, session, output, handle,
+                )
+            },
+        )
+        .map_err(|err| (FailureReason::Unspec, err.into())),
         _ => unreachable!(),
     }
 }
@@ -1070,6 +1096,17 @@ pub fn render_window_to_buffer(
             },
         )
         .map_err(|err| (FailureReason::Unspec, err.into())),
+        BackendData::Headless(headless) => render_session::<_, _>(
+            node,
+            &mut headless.renderer,
+            session,
+            &params,
+            Transform::Normal,
+            |_node, buffer, renderer, dt, age| {
+                render_fn(buffer, renderer, dt, age, session, common, window, geometry)
+            },
+        )
+        .map_err(|err| (FailureReason::Unspec, err.into())),
         _ => unreachable!(),
     }
 }
@@ -26,6 +26,10 @@ impl ToplevelManagementHandler for State {
         window: &<Self as ToplevelInfoHandler>::Window,
         seat: Option<Seat<Self>>,
     ) {
+        let seat = seat.unwrap_or(self.common.last_active_seat().clone());
+        self.common.shell.unminimize_request(window, &seat);
+        self.common.shell.summon_from_scratchpad(window, &seat);
+
         for output in self
             .common
             .shell
@@ -42,16 +46,16 @@ impl ToplevelManagementHandler for State {
                 .enumerate()
                 .find(|(_, w)| w.windows().any(|w| &w == window));
             if let Some((idx, workspace)) = maybe {
-                let seat = seat.unwrap_or(self.common.last_active_seat().clone());
                 let mapped = workspace
                     .mapped()
                     .find(|m| m.windows().any(|(w, _)| &w == window))
                     .unwrap()
                     .clone();
 
-                let _ = self.common.shell.activate(&output, idx as usize); // TODO: Move pointer?
+                let _ = self.common.shell.activate(&output, idx as usize);
                 mapped.focus_window(window);
                 Common::set_focus(self, Some(&mapped.clone().into()), &seat, None);
+                Common::warp_cursor_to(self, &seat, &mapped);
                 return;
             }
         }
@@ -71,6 +75,8 @@ impl ToplevelManagementHandler for State {
         let Some(to_handle) = self.common.shell.workspace_state.get_workspace_handle(&workspace) else {
             return;
         };
+        let seat = self.common.last_active_seat().clone();
+        self.common.shell.unminimize_request(window, &seat);
 
         let from_workspace = self
             .common
@@ -89,6 +95,55 @@ impl ToplevelManagementHandler for State {
             return;
         }
     }
+
+    fn fullscreen(
+        &mut self,
+        _dh: &DisplayHandle,
+        window: &<Self as ToplevelInfoHandler>::Window,
+        output: Option<Output>,
+    ) {
+        let seat = self.common.last_active_seat().clone();
+        self.common.shell.unminimize_request(window, &seat);
+
+        let output = output.unwrap_or_else(|| seat.active_output());
+        let evlh = self.common.event_loop_handle.clone();
+        self.common.shell.fullscreen_request(window, output, evlh);
+    }
+
+    fn unfullscreen(
+        &mut self,
+        _dh: &DisplayHandle,
+        window: &<Self as ToplevelInfoHandler>::Window,
+    ) {
+        self.common.shell.unfullscreen_request(window);
+    }
+
+    fn maximize(&mut self, _dh: &DisplayHandle, window: &<Self as ToplevelInfoHandler>::Window) {
+        let seat = self.common.last_active_seat().clone();
+        self.common.shell.unminimize_request(window, &seat);
+
+        if let Some(mapped) = self.common.shell.element_for_surface(window).cloned() {
+            self.common.shell.maximize_request(&mapped);
+        }
+    }
+
+    fn unmaximize(&mut self, _dh: &DisplayHandle, window: &<Self as ToplevelInfoHandler>::Window) {
+        if let Some(mapped) = self.common.shell.element_for_surface(window).cloned() {
+            self.common.shell.unmaximize_request(&mapped);
+        }
+    }
+
+    fn minimize(&mut self, _dh: &DisplayHandle, window: &<Self as ToplevelInfoHandler>::Window) {
+        if let Some(mapped) = self.common.shell.element_for_surface(window).cloned() {
+            self.common.shell.minimize_request(&mapped);
+        }
+    }
+
+    fn unminimize(&mut self, _dh: &DisplayHandle, window: &<Self as ToplevelInfoHandler>::Window) {
+        let seat = self.common.last_active_seat().clone();
+        self.common.shell.unminimize_request(window, &seat);
+        self.common.shell.summon_from_scratchpad(window, &seat);
+    }
 }
 
 impl ManagementWindow for CosmicSurface {
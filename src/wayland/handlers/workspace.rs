@@ -9,6 +9,7 @@ use crate::{
     },
 };
 use smithay::reexports::wayland_server::DisplayHandle;
+use tracing::debug;
 
 impl WorkspaceClientHandler for ClientState {
     fn workspace_state(&self) -> &WorkspaceClientState {
@@ -37,10 +38,40 @@ impl WorkspaceHandler for State {
                     });
 
                     if let Some((output, idx)) = maybe {
-                        let _ = self.common.shell.activate(&output, idx); // TODO: move cursor?
+                        if self.common.shell.activate(&output, idx).is_ok() {
+                            let seat = self.common.last_active_seat().clone();
+                            Common::warp_cursor_to_workspace(self, &seat, &output);
+                        }
                     }
                 }
-                _ => {}
+                Request::Create { in_group, name } => {
+                    let name = (!name.is_empty()).then_some(name);
+                    if self
+                        .common
+                        .shell
+                        .workspaces
+                        .create_workspace(
+                            &in_group,
+                            name,
+                            &mut self.common.shell.workspace_state.update(),
+                        )
+                        .is_none()
+                    {
+                        debug!("Ignoring request to create workspace in {:?}", in_group);
+                    }
+                }
+                Request::Remove(handle) => {
+                    if !self
+                        .common
+                        .shell
+                        .workspaces
+                        .remove_workspace(&handle, &mut self.common.shell.workspace_state.update())
+                    {
+                        debug!("Ignoring request to remove workspace {:?}", handle);
+                    }
+                }
+                // there is always one active workspace per output
+                Request::Deactivate(_) => {}
             }
         }
     }
@@ -143,9 +143,10 @@ impl XdgActivationHandler for State {
                             }
                         }
 
-                        let target = element.into();
+                        let target = element.clone().into();
                         if workspace == &current_workspace.handle && in_current_workspace {
                             Shell::set_focus(self, Some(&target), &seat, None);
+                            Common::warp_cursor_to(self, &seat, &element);
                         } else if let Some((w, _)) = target
                             .toplevel()
                             .and_then(|t| self.common.shell.workspace_for_surface(&t))
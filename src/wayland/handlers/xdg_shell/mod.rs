@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use crate::{
-    shell::{element::CosmicWindow, grabs::ReleaseMode, CosmicMapped, CosmicSurface, ManagedLayer},
+    shell::{grabs::ReleaseMode, CosmicSurface},
     utils::prelude::*,
     wayland::protocols::screencopy::SessionType,
 };
@@ -162,6 +162,17 @@ impl XdgShellHandler for State {
         Shell::resize_request(self, surface.wl_surface(), &seat, serial, edges.into())
     }
 
+    fn minimize_request(&mut self, surface: ToplevelSurface) {
+        if let Some(mapped) = self
+            .common
+            .shell
+            .element_for_wl_surface(surface.wl_surface())
+            .cloned()
+        {
+            self.common.shell.minimize_request(&mapped)
+        }
+    }
+
     fn maximize_request(&mut self, surface: ToplevelSurface) {
         if let Some(mapped) = self
             .common
@@ -185,168 +196,20 @@ impl XdgShellHandler for State {
     }
 
     fn fullscreen_request(&mut self, surface: ToplevelSurface, output: Option<WlOutput>) {
-        let active_output = {
-            let seat = self.common.last_active_seat();
-            seat.active_output()
-        };
         let output = output
             .as_ref()
             .and_then(Output::from_resource)
-            .unwrap_or_else(|| active_output.clone());
-
-        if let Some(mapped) = self
-            .common
-            .shell
-            .element_for_wl_surface(surface.wl_surface())
-            .cloned()
-        {
-            if let Some(set) = self
-                .common
-                .shell
-                .workspaces
-                .sets
-                .values_mut()
-                .find(|set| set.sticky_layer.mapped().any(|m| m == &mapped))
-            {
-                let mapped = if mapped
-                    .stack_ref()
-                    .map(|stack| stack.len() > 1)
-                    .unwrap_or(false)
-                {
-                    let stack = mapped.stack_ref().unwrap();
-                    let surface = stack
-                        .surfaces()
-                        .find(|s| s.wl_surface().as_ref() == Some(surface.wl_surface()))
-                        .unwrap();
-                    stack.remove_window(&surface);
-                    CosmicMapped::from(CosmicWindow::new(
-                        surface,
-                        self.common.event_loop_handle.clone(),
-                        self.common.theme.clone(),
-                    ))
-                } else {
-                    set.sticky_layer.unmap(&mapped);
-                    mapped
-                };
-
-                let workspace_handle = self.common.shell.active_space(&output).handle.clone();
-                for (window, _) in mapped.windows() {
-                    self.common
-                        .shell
-                        .toplevel_info_state
-                        .toplevel_enter_output(&window, &output);
-                    self.common
-                        .shell
-                        .toplevel_info_state
-                        .toplevel_enter_workspace(&window, &workspace_handle);
-                }
-
-                let workspace = self.common.shell.active_space_mut(&output);
-                workspace.floating_layer.map(mapped.clone(), None);
-                workspace.fullscreen_request(
-                    &mapped.active_window(),
-                    Some((ManagedLayer::Sticky, workspace_handle)),
-                );
-            } else if let Some(workspace) = self.common.shell.space_for_mut(&mapped) {
-                if workspace.output != output {
-                    let (mapped, layer) = if mapped
-                        .stack_ref()
-                        .map(|stack| stack.len() > 1)
-                        .unwrap_or(false)
-                    {
-                        let stack = mapped.stack_ref().unwrap();
-                        let surface = stack
-                            .surfaces()
-                            .find(|s| s.wl_surface().as_ref() == Some(surface.wl_surface()))
-                            .unwrap();
-                        stack.remove_window(&surface);
-                        (
-                            CosmicMapped::from(CosmicWindow::new(
-                                surface,
-                                self.common.event_loop_handle.clone(),
-                                self.common.theme.clone(),
-                            )),
-                            if workspace.is_tiled(&mapped) {
-                                ManagedLayer::Tiling
-                            } else {
-                                ManagedLayer::Floating
-                            },
-                        )
-                    } else {
-                        let layer = workspace.unmap(&mapped).unwrap().layer;
-                        (mapped, layer)
-                    };
-                    let handle = workspace.handle.clone();
-                    std::mem::drop(workspace);
-
-                    let workspace_handle = self.common.shell.active_space(&output).handle.clone();
-                    for (window, _) in mapped.windows() {
-                        self.common
-                            .shell
-                            .toplevel_info_state
-                            .toplevel_enter_output(&window, &output);
-                        self.common
-                            .shell
-                            .toplevel_info_state
-                            .toplevel_enter_workspace(&window, &workspace_handle);
-                    }
+            .unwrap_or_else(|| self.common.last_active_seat().active_output());
 
-                    let workspace = self.common.shell.active_space_mut(&output);
-                    workspace.floating_layer.map(mapped.clone(), None);
-                    workspace.fullscreen_request(&mapped.active_window(), Some((layer, handle)));
-                } else {
-                    let (window, _) = mapped
-                        .windows()
-                        .find(|(w, _)| w.wl_surface().as_ref() == Some(surface.wl_surface()))
-                        .unwrap();
-                    workspace.fullscreen_request(&window, None)
-                }
-            }
-        } else {
-            if let Some(o) = self
-                .common
-                .shell
-                .pending_windows
-                .iter_mut()
-                .find(|(s, _, _)| s.wl_surface().as_ref() == Some(surface.wl_surface()))
-                .map(|(_, _, o)| o)
-            {
-                *o = Some(output);
-            }
+        if let Some(window) = cosmic_surface(&self.common.shell, &surface) {
+            let evlh = self.common.event_loop_handle.clone();
+            self.common.shell.fullscreen_request(&window, output, evlh);
         }
     }
 
     fn unfullscreen_request(&mut self, surface: ToplevelSurface) {
-        if let Some(mapped) = self
-            .common
-            .shell
-            .element_for_wl_surface(surface.wl_surface())
-            .cloned()
-        {
-            if let Some(workspace) = self.common.shell.space_for_mut(&mapped) {
-                let (window, _) = mapped
-                    .windows()
-                    .find(|(w, _)| w.wl_surface().as_ref() == Some(surface.wl_surface()))
-                    .unwrap();
-                if let Some((layer, previous_workspace)) = workspace.unfullscreen_request(&window) {
-                    let old_handle = workspace.handle.clone();
-                    let new_workspace_handle = self
-                        .common
-                        .shell
-                        .workspaces
-                        .space_for_handle(&previous_workspace)
-                        .is_some()
-                        .then_some(previous_workspace)
-                        .unwrap_or(old_handle); // if the workspace doesn't exist anymore, we can still remap on the right layer
-
-                    self.common.shell.remap_unfullscreened_window(
-                        mapped,
-                        &old_handle,
-                        &new_workspace_handle,
-                        layer,
-                    );
-                }
-            }
+        if let Some(window) = cosmic_surface(&self.common.shell, &surface) {
+            self.common.shell.unfullscreen_request(&window);
         }
     }
 
@@ -416,3 +279,22 @@ impl XdgShellHandler for State {
 }
 
 delegate_xdg_shell!(State);
+
+/// The mapped or pending window of `surface`
+fn cosmic_surface(shell: &Shell, surface: &ToplevelSurface) -> Option<CosmicSurface> {
+    shell
+        .element_for_wl_surface(surface.wl_surface())
+        .and_then(|mapped| {
+            mapped
+                .windows()
+                .find(|(w, _)| w.wl_surface().as_ref() == Some(surface.wl_surface()))
+                .map(|(w, _)| w)
+        })
+        .or_else(|| {
+            shell
+                .pending_windows
+                .iter()
+                .find(|(s, _, _)| s.wl_surface().as_ref() == Some(surface.wl_surface()))
+                .map(|(s, _, _)| s.clone())
+        })
+}
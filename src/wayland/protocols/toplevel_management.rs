@@ -114,6 +114,18 @@ impl ToplevelManagementState {
         }
     }
 
+    /// Rectangles set for `window` by any client, relative to their surfaces
+    pub fn rectangles(
+        &self,
+        window: &impl ManagementWindow,
+    ) -> Vec<(WlSurface, Rectangle<i32, Logical>)> {
+        if let Some(state) = window.user_data().get::<ToplevelState>() {
+            state.lock().unwrap().rectangles.values().cloned().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
     pub fn global_id(&self) -> GlobalId {
         self.global.clone()
     }
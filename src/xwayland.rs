@@ -157,8 +157,13 @@ impl XwmHandler for State {
         if self.common.shell.element_for_surface(&surface).is_some() {
             return;
         }
-
+        // mapping an iconic window restores it
         let seat = self.common.last_active_seat().clone();
+        if let Some(mapped) = self.common.shell.unminimize_request(&surface, &seat) {
+            Common::set_focus(self, Some(&mapped.into()), &seat, None);
+            return;
+        }
+
         if let Some(context) = startup_id
             .map(XdgActivationToken::from)
             .and_then(|token| {
@@ -261,6 +266,14 @@ impl XwmHandler for State {
             } else {
                 space.unmap(&element);
             }
+        } else if let Some(element) = self.common.shell.minimized_element(&surface).cloned() {
+            if element.is_stack() && element.stack_ref().unwrap().len() >= 2 {
+                element.stack_ref().unwrap().remove_window(&surface);
+            } else {
+                for space in self.common.shell.workspaces.spaces_mut() {
+                    space.minimized_windows.retain(|m| m.window != element);
+                }
+            }
         }
 
         let outputs = if let Some(wl_surface) = window.wl_surface() {
@@ -423,6 +436,19 @@ impl XwmHandler for State {
         }
     }
 
+    fn minimize_request(&mut self, _xwm: XwmId, window: X11Surface) {
+        let surface = CosmicSurface::X11(window);
+        if let Some(mapped) = self.common.shell.element_for_surface(&surface).cloned() {
+            self.common.shell.minimize_request(&mapped);
+        }
+    }
+
+    fn unminimize_request(&mut self, _xwm: XwmId, window: X11Surface) {
+        let surface = CosmicSurface::X11(window);
+        let seat = self.common.last_active_seat().clone();
+        self.common.shell.unminimize_request(&surface, &seat);
+    }
+
     fn maximize_request(&mut self, _xwm: XwmId, window: X11Surface) {
         let surface = CosmicSurface::X11(window);
         if let Some(mapped) = self.common.shell.element_for_surface(&surface).cloned() {